@@ -0,0 +1,15 @@
+#version 430 core
+//
+#define LIGHT_COUNT 4
+//
+out vec4 FragColor;
+//
+uniform vec3 lights[LIGHT_COUNT];
+//
+//
+void main()
+{
+
+    FragColor = vec4(lights[0],1.0);
+
+}
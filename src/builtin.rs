@@ -0,0 +1,800 @@
+//
+// ------------------------------------------------------------------------------------------
+// Built-ins
+//
+// The functions and variables that glsl declares for every shader (section 7 and 8 of the
+// GLSL specification) with the versions, profiles, stages and extensions where they exist
+//
+use crate::datatype::{ShaderType,VersionBranch};
+use crate::function::ParameterQualifier;
+//
+//
+/// Where a built-in can be used
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub struct Availability {
+
+    // the first desktop version that has it, None if it doesn't exist on desktop
+    pub desktop:            Option<u16>,
+    // the first es version that has it, None if it doesn't exist in es
+    pub es:                 Option<u16>,
+    // the desktop version where the core profile removed it (only compatibility keeps it).
+    // es 3.00 removed the same built-ins
+    pub removed_in_core:    Option<u16>,
+    // the stages where it exists, every stage when empty
+    pub stages:             &'static [ShaderType],
+    // the extension that make it available before its version
+    pub extension:          Option<&'static str>,
+
+}
+//
+impl Availability {
+    //
+    pub(crate) const fn since(desktop:u16,es:u16) -> Self {
+
+        Availability { desktop: Some(desktop), es: Some(es), removed_in_core: None, stages: &[], extension: None }
+
+    }
+    //
+    pub(crate) const fn desktop(desktop:u16) -> Self {
+
+        Availability { desktop: Some(desktop), es: None, removed_in_core: None, stages: &[], extension: None }
+
+    }
+    //
+    /// Only with an extension, no version of glsl has it
+    pub(crate) const fn only_with(extension:&'static str) -> Self {
+
+        Availability { desktop: None, es: None, removed_in_core: None, stages: &[], extension: Some(extension) }
+
+    }
+    //
+    pub(crate) const fn stages(self,stages:&'static [ShaderType]) -> Self { Availability { stages, ..self } }
+    //
+    pub(crate) const fn removed_in_core(self,version:u16) -> Self { Availability { removed_in_core: Some(version), ..self } }
+    //
+    pub(crate) const fn extension(self,extension:&'static str) -> Self { Availability { extension: Some(extension), ..self } }
+    //
+    /// Check if the built-in can be used by a shader
+    pub fn is_available(&self,target:&ShaderTarget) -> bool {
+
+        let stage = self.stages.is_empty() || target.stage.is_none_or(|s| self.stages.contains(&s));
+
+        stage && (self.version_allows(target) || self.extension_allows(target))
+
+    }
+    //
+    /// Check only the version and the profile, not the stage
+    pub(crate) fn version_allows(&self,target:&ShaderTarget) -> bool {
+
+        match target.branch {
+
+            VersionBranch::ES => {
+
+                let removed = self.removed_in_core.is_some() && target.version >= 300;
+
+                self.es.is_some_and(|v| target.version >= v) && !removed
+
+            },
+            _ => {
+
+                let removed = target.is_core() && self.removed_in_core.is_some_and(|r| target.version >= r);
+
+                self.desktop.is_some_and(|v| target.version >= v) && !removed
+
+            }
+
+        }
+
+    }
+    //
+    pub(crate) fn extension_allows(&self,target:&ShaderTarget) -> bool {
+
+        // '#extension all : warn' enables every extension
+        self.extension.is_some_and(|e| target.extensions.iter().any(|x| x == e || x == "all"))
+
+    }
+    //
+    /// The first version of the profile of the target that has it
+    pub fn minimum_version(&self,branch:VersionBranch) -> Option<u16> {
+
+        match branch {
+
+            VersionBranch::ES => self.es,
+            _ => self.desktop
+
+        }
+
+    }
+    //
+}
+//
+//
+/// What a shader is compiled for: the built-ins available depend on it
+#[derive(Debug,PartialEq,Clone)]
+pub struct ShaderTarget {
+
+    // None when the stage is not known, every stage is accepted then
+    pub stage:      Option<ShaderType>,
+    pub version:    u16,
+    pub branch:     VersionBranch,
+    // the extensions enabled with '#extension'
+    pub extensions: Vec<String>,
+
+}
+//
+impl ShaderTarget {
+    //
+    pub fn new(stage:Option<ShaderType>,version:u16,branch:VersionBranch) -> Self {
+
+        ShaderTarget { stage, version, branch, extensions: Vec::new() }
+
+    }
+    //
+    /// The same target with these extensions enabled
+    pub fn with_extensions(self,extensions:Vec<String>) -> Self { ShaderTarget { extensions, ..self } }
+    //
+    /// A desktop shader without profile is a core one since 1.50
+    pub fn is_core(&self) -> bool {
+
+        match self.branch {
+
+            VersionBranch::CORE => true,
+            VersionBranch::UNKNOWN => self.version >= 150,
+            _ => false
+
+        }
+
+    }
+    //
+}
+//
+//
+/// One overload of a built-in function
+#[derive(Debug,PartialEq,Clone)]
+pub struct BuiltinFunction {
+
+    pub name:           &'static str,
+    pub return_type:    String,
+    pub parameters:     Vec<(ParameterQualifier,String)>,
+    pub availability:   Availability,
+
+}
+//
+impl BuiltinFunction {
+    //
+    /// The signature as glsl would write it (ex: 'vec4 texture(sampler2D,vec2)')
+    pub fn signature(&self) -> String {
+
+        let params:Vec<&str> = self.parameters.iter().map(|(_,t)| t.as_str()).collect();
+
+        format!("{} {}({})",self.return_type,self.name,params.join(","))
+
+    }
+    //
+}
+//
+//
+/// How a built-in variable can be used
+#[allow(non_camel_case_types)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum BuiltinQualifier {
+
+    IN,
+    OUT,
+    // a value that can be read and written (gl_out of the tessellation control shader)
+    INOUT,
+    CONST,
+    UNIFORM,
+
+}
+//
+//
+/// A variable declared by glsl (ex: gl_Position)
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub struct BuiltinVariable {
+
+    pub name:           &'static str,
+    // the type with its array size if it is one (ex: 'float[]', 'gl_PerVertex[]')
+    pub type_name:      &'static str,
+    pub qualifier:      BuiltinQualifier,
+    pub availability:   Availability,
+
+}
+//
+//
+/// A built-in function written with the generic types of the specification. It is
+/// expanded to every real overload when it is queried
+struct GenericSignature {
+
+    return_type:    &'static str,
+    name:           &'static str,
+    // 'out ' and 'inout ' are written in front of the type
+    parameters:     &'static [&'static str],
+    availability:   Availability,
+
+}
+//
+const fn f(return_type:&'static str,name:&'static str,parameters:&'static [&'static str],availability:Availability) -> GenericSignature {
+
+    GenericSignature { return_type, name, parameters, availability }
+
+}
+//
+const fn v(name:&'static str,type_name:&'static str,qualifier:BuiltinQualifier,availability:Availability) -> BuiltinVariable {
+
+    BuiltinVariable { name, type_name, qualifier, availability }
+
+}
+//
+//
+// the availabilities the most used
+const ALL: Availability = Availability::since(110,100);
+const GLSL130: Availability = Availability::since(130,300);
+const GLSL400: Availability = Availability::since(400,310);
+const DOUBLE: Availability = Availability::desktop(400);
+const TEXTURE: Availability = Availability::since(130,300);
+const IMAGE: Availability = Availability::since(420,310).extension("GL_ARB_shader_image_load_store");
+const ATOMIC: Availability = Availability::since(430,310);
+const DEPRECATED_TEXTURE: Availability = Availability::since(110,100).removed_in_core(140);
+const MULTISAMPLE: Availability = Availability::since(150,310).extension("GL_ARB_texture_multisample");
+const MULTISAMPLE_ARRAY: Availability = Availability::since(150,320).extension("GL_ARB_texture_multisample");
+const CUBE_ARRAY: Availability = Availability::since(400,320).extension("GL_ARB_texture_cube_map_array");
+const VULKAN: Availability = Availability::only_with("GL_KHR_vulkan_glsl");
+//
+const VERTEX: &[ShaderType] = &[ShaderType::VERTEX];
+const FRAGMENT: &[ShaderType] = &[ShaderType::FRAGMENT];
+const GEOMETRY: &[ShaderType] = &[ShaderType::GEOMETRY];
+const TESSCONTROL: &[ShaderType] = &[ShaderType::TESSCONTROL];
+const TESSEVALUATION: &[ShaderType] = &[ShaderType::TESSEVALUATION];
+const TESSELLATION: &[ShaderType] = &[ShaderType::TESSCONTROL,ShaderType::TESSEVALUATION];
+const COMPUTE: &[ShaderType] = &[ShaderType::COMPUTE];
+const VERTEX_OUTPUT_STAGES: &[ShaderType] = &[
+    ShaderType::VERTEX,
+    ShaderType::GEOMETRY,
+    ShaderType::TESSEVALUATION
+];
+//
+//
+/// Every built-in function
+const FUNCTIONS: &[GenericSignature] = &[
+    //
+    // angle and trigonometry
+    f("genType","radians",&["genType"],ALL),
+    f("genType","degrees",&["genType"],ALL),
+    f("genType","sin",&["genType"],ALL),
+    f("genType","cos",&["genType"],ALL),
+    f("genType","tan",&["genType"],ALL),
+    f("genType","asin",&["genType"],ALL),
+    f("genType","acos",&["genType"],ALL),
+    f("genType","atan",&["genType","genType"],ALL),
+    f("genType","atan",&["genType"],ALL),
+    f("genType","sinh",&["genType"],GLSL130),
+    f("genType","cosh",&["genType"],GLSL130),
+    f("genType","tanh",&["genType"],GLSL130),
+    f("genType","asinh",&["genType"],GLSL130),
+    f("genType","acosh",&["genType"],GLSL130),
+    f("genType","atanh",&["genType"],GLSL130),
+    //
+    // exponential
+    f("genType","pow",&["genType","genType"],ALL),
+    f("genType","exp",&["genType"],ALL),
+    f("genType","log",&["genType"],ALL),
+    f("genType","exp2",&["genType"],ALL),
+    f("genType","log2",&["genType"],ALL),
+    f("genType","sqrt",&["genType"],ALL),
+    f("genDType","sqrt",&["genDType"],DOUBLE),
+    f("genType","inversesqrt",&["genType"],ALL),
+    f("genDType","inversesqrt",&["genDType"],DOUBLE),
+    //
+    // common
+    f("genType","abs",&["genType"],ALL),
+    f("genIType","abs",&["genIType"],GLSL130),
+    f("genDType","abs",&["genDType"],DOUBLE),
+    f("genType","sign",&["genType"],ALL),
+    f("genIType","sign",&["genIType"],GLSL130),
+    f("genDType","sign",&["genDType"],DOUBLE),
+    f("genType","floor",&["genType"],ALL),
+    f("genDType","floor",&["genDType"],DOUBLE),
+    f("genType","ceil",&["genType"],ALL),
+    f("genDType","ceil",&["genDType"],DOUBLE),
+    f("genType","fract",&["genType"],ALL),
+    f("genDType","fract",&["genDType"],DOUBLE),
+    f("genType","trunc",&["genType"],GLSL130),
+    f("genType","round",&["genType"],GLSL130),
+    f("genType","roundEven",&["genType"],GLSL130),
+    f("genType","mod",&["genType","float"],ALL),
+    f("genType","mod",&["genType","genType"],ALL),
+    f("genDType","mod",&["genDType","double"],DOUBLE),
+    f("genDType","mod",&["genDType","genDType"],DOUBLE),
+    f("genType","modf",&["genType","out genType"],GLSL130),
+    f("genType","min",&["genType","genType"],ALL),
+    f("genType","min",&["genType","float"],ALL),
+    f("genIType","min",&["genIType","genIType"],GLSL130),
+    f("genIType","min",&["genIType","int"],GLSL130),
+    f("genUType","min",&["genUType","genUType"],GLSL130),
+    f("genUType","min",&["genUType","uint"],GLSL130),
+    f("genDType","min",&["genDType","genDType"],DOUBLE),
+    f("genDType","min",&["genDType","double"],DOUBLE),
+    f("genType","max",&["genType","genType"],ALL),
+    f("genType","max",&["genType","float"],ALL),
+    f("genIType","max",&["genIType","genIType"],GLSL130),
+    f("genIType","max",&["genIType","int"],GLSL130),
+    f("genUType","max",&["genUType","genUType"],GLSL130),
+    f("genUType","max",&["genUType","uint"],GLSL130),
+    f("genDType","max",&["genDType","genDType"],DOUBLE),
+    f("genDType","max",&["genDType","double"],DOUBLE),
+    f("genType","clamp",&["genType","genType","genType"],ALL),
+    f("genType","clamp",&["genType","float","float"],ALL),
+    f("genIType","clamp",&["genIType","genIType","genIType"],GLSL130),
+    f("genIType","clamp",&["genIType","int","int"],GLSL130),
+    f("genUType","clamp",&["genUType","genUType","genUType"],GLSL130),
+    f("genUType","clamp",&["genUType","uint","uint"],GLSL130),
+    f("genDType","clamp",&["genDType","genDType","genDType"],DOUBLE),
+    f("genDType","clamp",&["genDType","double","double"],DOUBLE),
+    f("genType","mix",&["genType","genType","genType"],ALL),
+    f("genType","mix",&["genType","genType","float"],ALL),
+    f("genType","mix",&["genType","genType","genBType"],GLSL130),
+    f("genDType","mix",&["genDType","genDType","genDType"],DOUBLE),
+    f("genDType","mix",&["genDType","genDType","double"],DOUBLE),
+    f("genType","step",&["genType","genType"],ALL),
+    f("genType","step",&["float","genType"],ALL),
+    f("genType","smoothstep",&["genType","genType","genType"],ALL),
+    f("genType","smoothstep",&["float","float","genType"],ALL),
+    f("genBType","isnan",&["genType"],GLSL130),
+    f("genBType","isinf",&["genType"],GLSL130),
+    f("genIType","floatBitsToInt",&["genType"],Availability::since(330,300)),
+    f("genUType","floatBitsToUint",&["genType"],Availability::since(330,300)),
+    f("genType","intBitsToFloat",&["genIType"],Availability::since(330,300)),
+    f("genType","uintBitsToFloat",&["genUType"],Availability::since(330,300)),
+    f("genType","fma",&["genType","genType","genType"],Availability::since(400,320)),
+    f("genType","frexp",&["genType","out genIType"],GLSL400),
+    f("genType","ldexp",&["genType","genIType"],GLSL400),
+    //
+    // packing
+    f("uint","packUnorm2x16",&["vec2"],Availability::since(420,300).extension("GL_ARB_shading_language_packing")),
+    f("uint","packSnorm2x16",&["vec2"],Availability::since(420,300).extension("GL_ARB_shading_language_packing")),
+    f("uint","packHalf2x16",&["vec2"],Availability::since(420,300).extension("GL_ARB_shading_language_packing")),
+    f("vec2","unpackUnorm2x16",&["uint"],Availability::since(420,300).extension("GL_ARB_shading_language_packing")),
+    f("vec2","unpackSnorm2x16",&["uint"],Availability::since(420,300).extension("GL_ARB_shading_language_packing")),
+    f("vec2","unpackHalf2x16",&["uint"],Availability::since(420,300).extension("GL_ARB_shading_language_packing")),
+    f("uint","packUnorm4x8",&["vec4"],GLSL400),
+    f("uint","packSnorm4x8",&["vec4"],GLSL400),
+    f("vec4","unpackUnorm4x8",&["uint"],GLSL400),
+    f("vec4","unpackSnorm4x8",&["uint"],GLSL400),
+    f("double","packDouble2x32",&["uvec2"],DOUBLE),
+    f("uvec2","unpackDouble2x32",&["double"],DOUBLE),
+    //
+    // geometric
+    f("float","length",&["genType"],ALL),
+    f("double","length",&["genDType"],DOUBLE),
+    f("float","distance",&["genType","genType"],ALL),
+    f("double","distance",&["genDType","genDType"],DOUBLE),
+    f("float","dot",&["genType","genType"],ALL),
+    f("double","dot",&["genDType","genDType"],DOUBLE),
+    f("vec3","cross",&["vec3","vec3"],ALL),
+    f("dvec3","cross",&["dvec3","dvec3"],DOUBLE),
+    f("genType","normalize",&["genType"],ALL),
+    f("genDType","normalize",&["genDType"],DOUBLE),
+    f("genType","faceforward",&["genType","genType","genType"],ALL),
+    f("genType","reflect",&["genType","genType"],ALL),
+    f("genDType","reflect",&["genDType","genDType"],DOUBLE),
+    f("genType","refract",&["genType","genType","float"],ALL),
+    f("genDType","refract",&["genDType","genDType","double"],DOUBLE),
+    //
+    // matrices
+    f("mat","matrixCompMult",&["mat","mat"],Availability::since(120,300)),
+    f("dmat","matrixCompMult",&["dmat","dmat"],DOUBLE),
+    f("matN","outerProduct",&["vec","vec"],Availability::since(120,300)),
+    f("matN","transpose",&["matN"],Availability::since(120,300)),
+    f("dmatN","transpose",&["dmatN"],DOUBLE),
+    f("float","determinant",&["matN"],Availability::since(150,300)),
+    f("double","determinant",&["dmatN"],DOUBLE),
+    f("matN","inverse",&["matN"],Availability::since(140,300)),
+    f("dmatN","inverse",&["dmatN"],DOUBLE),
+    //
+    // vector relational
+    f("bvec","lessThan",&["vec","vec"],ALL),
+    f("bvec","lessThan",&["ivec","ivec"],ALL),
+    f("bvec","lessThan",&["uvec","uvec"],GLSL130),
+    f("bvec","lessThanEqual",&["vec","vec"],ALL),
+    f("bvec","lessThanEqual",&["ivec","ivec"],ALL),
+    f("bvec","lessThanEqual",&["uvec","uvec"],GLSL130),
+    f("bvec","greaterThan",&["vec","vec"],ALL),
+    f("bvec","greaterThan",&["ivec","ivec"],ALL),
+    f("bvec","greaterThan",&["uvec","uvec"],GLSL130),
+    f("bvec","greaterThanEqual",&["vec","vec"],ALL),
+    f("bvec","greaterThanEqual",&["ivec","ivec"],ALL),
+    f("bvec","greaterThanEqual",&["uvec","uvec"],GLSL130),
+    f("bvec","equal",&["vec","vec"],ALL),
+    f("bvec","equal",&["ivec","ivec"],ALL),
+    f("bvec","equal",&["uvec","uvec"],GLSL130),
+    f("bvec","equal",&["bvec","bvec"],ALL),
+    f("bvec","notEqual",&["vec","vec"],ALL),
+    f("bvec","notEqual",&["ivec","ivec"],ALL),
+    f("bvec","notEqual",&["uvec","uvec"],GLSL130),
+    f("bvec","notEqual",&["bvec","bvec"],ALL),
+    f("bool","any",&["bvec"],ALL),
+    f("bool","all",&["bvec"],ALL),
+    f("bvec","not",&["bvec"],ALL),
+    //
+    // integers
+    f("genUType","uaddCarry",&["genUType","genUType","out genUType"],GLSL400),
+    f("genUType","usubBorrow",&["genUType","genUType","out genUType"],GLSL400),
+    f("void","umulExtended",&["genUType","genUType","out genUType","out genUType"],GLSL400),
+    f("void","imulExtended",&["genIType","genIType","out genIType","out genIType"],GLSL400),
+    f("genIType","bitfieldExtract",&["genIType","int","int"],GLSL400),
+    f("genUType","bitfieldExtract",&["genUType","int","int"],GLSL400),
+    f("genIType","bitfieldInsert",&["genIType","genIType","int","int"],GLSL400),
+    f("genUType","bitfieldInsert",&["genUType","genUType","int","int"],GLSL400),
+    f("genIType","bitfieldReverse",&["genIType"],GLSL400),
+    f("genUType","bitfieldReverse",&["genUType"],GLSL400),
+    f("genIType","bitCount",&["genIType"],GLSL400),
+    f("genIType","bitCount",&["genUType"],GLSL400),
+    f("genIType","findLSB",&["genIType"],GLSL400),
+    f("genIType","findLSB",&["genUType"],GLSL400),
+    f("genIType","findMSB",&["genIType"],GLSL400),
+    f("genIType","findMSB",&["genUType"],GLSL400),
+    //
+    // textures
+    f("int","textureSize",&["gsampler1D","int"],Availability::desktop(130)),
+    f("ivec2","textureSize",&["gsampler2D","int"],TEXTURE),
+    f("ivec3","textureSize",&["gsampler3D","int"],TEXTURE),
+    f("ivec2","textureSize",&["gsamplerCube","int"],TEXTURE),
+    f("ivec3","textureSize",&["gsampler2DArray","int"],TEXTURE),
+    f("ivec2","textureSize",&["sampler2DShadow","int"],TEXTURE),
+    f("ivec2","textureSize",&["gsampler2DMS"],MULTISAMPLE),
+    f("ivec3","textureSize",&["gsampler2DMSArray"],MULTISAMPLE_ARRAY),
+    f("ivec3","textureSize",&["gsamplerCubeArray","int"],CUBE_ARRAY),
+    f("ivec3","textureSize",&["samplerCubeArrayShadow","int"],CUBE_ARRAY),
+    f("gvec4","texture",&["gsampler1D","float"],Availability::desktop(130)),
+    f("gvec4","texture",&["gsampler2D","vec2"],TEXTURE),
+    f("gvec4","texture",&["gsampler2D","vec2","float"],TEXTURE.stages(FRAGMENT)),
+    f("gvec4","texture",&["gsampler3D","vec3"],TEXTURE),
+    f("gvec4","texture",&["gsamplerCube","vec3"],TEXTURE),
+    f("gvec4","texture",&["gsampler2DArray","vec3"],TEXTURE),
+    f("float","texture",&["sampler2DShadow","vec3"],TEXTURE),
+    f("float","texture",&["samplerCubeShadow","vec4"],TEXTURE),
+    f("gvec4","texture",&["gsamplerCubeArray","vec4"],CUBE_ARRAY),
+    f("float","texture",&["samplerCubeArrayShadow","vec4","float"],CUBE_ARRAY),
+    f("gvec4","textureProj",&["gsampler2D","vec3"],TEXTURE),
+    f("gvec4","textureProj",&["gsampler2D","vec4"],TEXTURE),
+    f("gvec4","textureLod",&["gsampler2D","vec2","float"],TEXTURE),
+    f("gvec4","textureLod",&["gsampler3D","vec3","float"],TEXTURE),
+    f("gvec4","textureLod",&["gsamplerCube","vec3","float"],TEXTURE),
+    f("gvec4","textureLod",&["gsampler2DArray","vec3","float"],TEXTURE),
+    f("gvec4","textureLod",&["gsamplerCubeArray","vec4","float"],CUBE_ARRAY),
+    f("gvec4","textureOffset",&["gsampler2D","vec2","ivec2"],TEXTURE),
+    f("gvec4","texelFetch",&["gsampler2D","ivec2","int"],TEXTURE),
+    f("gvec4","texelFetch",&["gsampler3D","ivec3","int"],TEXTURE),
+    f("gvec4","texelFetch",&["gsampler2DArray","ivec3","int"],TEXTURE),
+    f("gvec4","texelFetch",&["gsampler2DMS","ivec2","int"],MULTISAMPLE),
+    f("gvec4","texelFetch",&["gsampler2DMSArray","ivec3","int"],MULTISAMPLE_ARRAY),
+    f("gvec4","textureGrad",&["gsampler2D","vec2","vec2","vec2"],TEXTURE),
+    f("gvec4","textureGrad",&["gsampler3D","vec3","vec3","vec3"],TEXTURE),
+    f("gvec4","textureGrad",&["gsamplerCube","vec3","vec3","vec3"],TEXTURE),
+    f("gvec4","textureGather",&["gsampler2D","vec2"],GLSL400.extension("GL_ARB_texture_gather")),
+    f("gvec4","textureGather",&["gsampler2D","vec2","int"],GLSL400.extension("GL_ARB_texture_gather")),
+    f("vec2","textureQueryLod",&["gsampler2D","vec2"],Availability::desktop(400).stages(FRAGMENT)),
+    f("int","textureQueryLevels",&["gsampler2D"],Availability::desktop(430)),
+    //
+    // input attachments of vulkan
+    f("gvec4","subpassLoad",&["gsubpassInput"],VULKAN.stages(FRAGMENT)),
+    f("gvec4","subpassLoad",&["gsubpassInputMS","int"],VULKAN.stages(FRAGMENT)),
+    //
+    // textures of the first versions, removed by the core profile
+    f("vec4","texture2D",&["sampler2D","vec2"],DEPRECATED_TEXTURE),
+    f("vec4","texture2D",&["sampler2D","vec2","float"],DEPRECATED_TEXTURE.stages(FRAGMENT)),
+    f("vec4","texture2DProj",&["sampler2D","vec3"],DEPRECATED_TEXTURE),
+    f("vec4","texture2DProj",&["sampler2D","vec4"],DEPRECATED_TEXTURE),
+    f("vec4","texture2DLod",&["sampler2D","vec2","float"],DEPRECATED_TEXTURE.stages(VERTEX)),
+    f("vec4","textureCube",&["samplerCube","vec3"],DEPRECATED_TEXTURE),
+    f("vec4","textureCubeLod",&["samplerCube","vec3","float"],DEPRECATED_TEXTURE.stages(VERTEX)),
+    f("vec4","texture3D",&["sampler3D","vec3"],Availability::desktop(110).removed_in_core(140)),
+    f("vec4","shadow2D",&["sampler2DShadow","vec3"],Availability::desktop(110).removed_in_core(140)),
+    //
+    // images
+    f("ivec2","imageSize",&["gimage2D"],Availability::since(430,310)),
+    f("ivec3","imageSize",&["gimage3D"],Availability::since(430,310)),
+    f("ivec3","imageSize",&["gimage2DArray"],Availability::since(430,310)),
+    f("gvec4","imageLoad",&["gimage2D","ivec2"],IMAGE),
+    f("gvec4","imageLoad",&["gimage3D","ivec3"],IMAGE),
+    f("gvec4","imageLoad",&["gimage2DArray","ivec3"],IMAGE),
+    f("void","imageStore",&["gimage2D","ivec2","gvec4"],IMAGE),
+    f("void","imageStore",&["gimage3D","ivec3","gvec4"],IMAGE),
+    f("void","imageStore",&["gimage2DArray","ivec3","gvec4"],IMAGE),
+    f("uint","imageAtomicAdd",&["uimage2D","ivec2","uint"],IMAGE),
+    f("int","imageAtomicAdd",&["iimage2D","ivec2","int"],IMAGE),
+    f("uint","imageAtomicExchange",&["uimage2D","ivec2","uint"],IMAGE),
+    f("uint","imageAtomicCompSwap",&["uimage2D","ivec2","uint","uint"],IMAGE),
+    //
+    // atomic operations on buffer and shared variables
+    f("uint","atomicAdd",&["inout uint","uint"],ATOMIC),
+    f("int","atomicAdd",&["inout int","int"],ATOMIC),
+    f("uint","atomicMin",&["inout uint","uint"],ATOMIC),
+    f("int","atomicMin",&["inout int","int"],ATOMIC),
+    f("uint","atomicMax",&["inout uint","uint"],ATOMIC),
+    f("int","atomicMax",&["inout int","int"],ATOMIC),
+    f("uint","atomicAnd",&["inout uint","uint"],ATOMIC),
+    f("int","atomicAnd",&["inout int","int"],ATOMIC),
+    f("uint","atomicOr",&["inout uint","uint"],ATOMIC),
+    f("int","atomicOr",&["inout int","int"],ATOMIC),
+    f("uint","atomicXor",&["inout uint","uint"],ATOMIC),
+    f("int","atomicXor",&["inout int","int"],ATOMIC),
+    f("uint","atomicExchange",&["inout uint","uint"],ATOMIC),
+    f("int","atomicExchange",&["inout int","int"],ATOMIC),
+    f("uint","atomicCompSwap",&["inout uint","uint","uint"],ATOMIC),
+    f("int","atomicCompSwap",&["inout int","int","int"],ATOMIC),
+    f("uint","atomicCounterIncrement",&["atomic_uint"],Availability::since(420,310).extension("GL_ARB_shader_atomic_counters")),
+    f("uint","atomicCounterDecrement",&["atomic_uint"],Availability::since(420,310).extension("GL_ARB_shader_atomic_counters")),
+    f("uint","atomicCounter",&["atomic_uint"],Availability::since(420,310).extension("GL_ARB_shader_atomic_counters")),
+    //
+    // derivatives (es 1.00 only has them with the extension)
+    f("genType","dFdx",&["genType"],Availability::since(110,300).stages(FRAGMENT).extension("GL_OES_standard_derivatives")),
+    f("genType","dFdy",&["genType"],Availability::since(110,300).stages(FRAGMENT).extension("GL_OES_standard_derivatives")),
+    f("genType","fwidth",&["genType"],Availability::since(110,300).stages(FRAGMENT).extension("GL_OES_standard_derivatives")),
+    f("genType","dFdxFine",&["genType"],Availability::desktop(450).stages(FRAGMENT).extension("GL_ARB_derivative_control")),
+    f("genType","dFdyFine",&["genType"],Availability::desktop(450).stages(FRAGMENT).extension("GL_ARB_derivative_control")),
+    f("genType","dFdxCoarse",&["genType"],Availability::desktop(450).stages(FRAGMENT).extension("GL_ARB_derivative_control")),
+    f("genType","dFdyCoarse",&["genType"],Availability::desktop(450).stages(FRAGMENT).extension("GL_ARB_derivative_control")),
+    f("genType","fwidthFine",&["genType"],Availability::desktop(450).stages(FRAGMENT).extension("GL_ARB_derivative_control")),
+    f("genType","fwidthCoarse",&["genType"],Availability::desktop(450).stages(FRAGMENT).extension("GL_ARB_derivative_control")),
+    f("genType","interpolateAtCentroid",&["genType"],Availability::since(400,320).stages(FRAGMENT)),
+    f("genType","interpolateAtSample",&["genType","int"],Availability::since(400,320).stages(FRAGMENT)),
+    f("genType","interpolateAtOffset",&["genType","vec2"],Availability::since(400,320).stages(FRAGMENT)),
+    //
+    // geometry shader
+    f("void","EmitVertex",&[],Availability::since(150,320).stages(GEOMETRY)),
+    f("void","EndPrimitive",&[],Availability::since(150,320).stages(GEOMETRY)),
+    f("void","EmitStreamVertex",&["int"],Availability::desktop(400).stages(GEOMETRY)),
+    f("void","EndStreamPrimitive",&["int"],Availability::desktop(400).stages(GEOMETRY)),
+    //
+    // synchronization
+    f("void","barrier",&[],Availability::since(400,310).stages(&[ShaderType::TESSCONTROL,ShaderType::COMPUTE])),
+    f("void","memoryBarrier",&[],IMAGE),
+    f("void","memoryBarrierAtomicCounter",&[],Availability::since(430,310)),
+    f("void","memoryBarrierBuffer",&[],Availability::since(430,310)),
+    f("void","memoryBarrierImage",&[],Availability::since(430,310)),
+    f("void","memoryBarrierShared",&[],Availability::since(430,310).stages(COMPUTE)),
+    f("void","groupMemoryBarrier",&[],Availability::since(430,310).stages(COMPUTE)),
+    //
+    // index of a resource array that is not the same for every invocation
+    f("int","nonuniformEXT",&["int"],Availability::only_with("GL_EXT_nonuniform_qualifier")),
+    f("uint","nonuniformEXT",&["uint"],Availability::only_with("GL_EXT_nonuniform_qualifier")),
+    //
+];
+//
+//
+/// Every built-in variable
+const VARIABLES: &[BuiltinVariable] = &[
+    //
+    // vertex shader
+    v("gl_VertexID","int",BuiltinQualifier::IN,GLSL130.stages(VERTEX)),
+    v("gl_InstanceID","int",BuiltinQualifier::IN,Availability::since(140,300).stages(VERTEX)),
+    v("gl_DrawID","int",BuiltinQualifier::IN,Availability::desktop(460).stages(VERTEX).extension("GL_ARB_shader_draw_parameters")),
+    v("gl_BaseVertex","int",BuiltinQualifier::IN,Availability::desktop(460).stages(VERTEX).extension("GL_ARB_shader_draw_parameters")),
+    v("gl_BaseInstance","int",BuiltinQualifier::IN,Availability::desktop(460).stages(VERTEX).extension("GL_ARB_shader_draw_parameters")),
+    v("gl_VertexIndex","int",BuiltinQualifier::IN,VULKAN.stages(VERTEX)),
+    v("gl_InstanceIndex","int",BuiltinQualifier::IN,VULKAN.stages(VERTEX)),
+    //
+    // outputs of the stages before the rasterization
+    v("gl_Position","vec4",BuiltinQualifier::OUT,ALL.stages(VERTEX_OUTPUT_STAGES)),
+    v("gl_PointSize","float",BuiltinQualifier::OUT,ALL.stages(VERTEX_OUTPUT_STAGES)),
+    v("gl_ClipDistance","float[]",BuiltinQualifier::OUT,Availability::desktop(130).stages(VERTEX_OUTPUT_STAGES)),
+    v("gl_CullDistance","float[]",BuiltinQualifier::OUT,Availability::desktop(450).stages(VERTEX_OUTPUT_STAGES)),
+    //
+    // geometry and tessellation shaders
+    v("gl_in","gl_PerVertex[]",BuiltinQualifier::IN,Availability::since(150,320).stages(&[
+        ShaderType::GEOMETRY,
+        ShaderType::TESSCONTROL,
+        ShaderType::TESSEVALUATION
+    ])),
+    v("gl_PrimitiveIDIn","int",BuiltinQualifier::IN,Availability::since(150,320).stages(GEOMETRY)),
+    v("gl_InvocationID","int",BuiltinQualifier::IN,Availability::since(400,320).stages(&[ShaderType::GEOMETRY,ShaderType::TESSCONTROL])),
+    v("gl_PrimitiveID","int",BuiltinQualifier::OUT,Availability::since(150,320).stages(GEOMETRY)),
+    v("gl_Layer","int",BuiltinQualifier::OUT,Availability::since(150,320).stages(GEOMETRY)),
+    v("gl_ViewportIndex","int",BuiltinQualifier::OUT,Availability::desktop(410).stages(GEOMETRY)),
+    v("gl_PatchVerticesIn","int",BuiltinQualifier::IN,Availability::since(400,320).stages(TESSELLATION)),
+    v("gl_PrimitiveID","int",BuiltinQualifier::IN,Availability::since(150,320).stages(&[
+        ShaderType::TESSCONTROL,
+        ShaderType::TESSEVALUATION,
+        ShaderType::FRAGMENT
+    ])),
+    v("gl_out","gl_PerVertex[]",BuiltinQualifier::INOUT,Availability::since(400,320).stages(TESSCONTROL)),
+    v("gl_TessLevelOuter","float[4]",BuiltinQualifier::OUT,Availability::since(400,320).stages(TESSCONTROL)),
+    v("gl_TessLevelInner","float[2]",BuiltinQualifier::OUT,Availability::since(400,320).stages(TESSCONTROL)),
+    v("gl_TessLevelOuter","float[4]",BuiltinQualifier::IN,Availability::since(400,320).stages(TESSEVALUATION)),
+    v("gl_TessLevelInner","float[2]",BuiltinQualifier::IN,Availability::since(400,320).stages(TESSEVALUATION)),
+    v("gl_TessCoord","vec3",BuiltinQualifier::IN,Availability::since(400,320).stages(TESSEVALUATION)),
+    //
+    // fragment shader
+    v("gl_FragCoord","vec4",BuiltinQualifier::IN,ALL.stages(FRAGMENT)),
+    v("gl_FrontFacing","bool",BuiltinQualifier::IN,ALL.stages(FRAGMENT)),
+    v("gl_PointCoord","vec2",BuiltinQualifier::IN,ALL.stages(FRAGMENT)),
+    v("gl_SampleID","int",BuiltinQualifier::IN,Availability::since(400,320).stages(FRAGMENT)),
+    v("gl_SamplePosition","vec2",BuiltinQualifier::IN,Availability::since(400,320).stages(FRAGMENT)),
+    v("gl_SampleMaskIn","int[]",BuiltinQualifier::IN,Availability::since(400,320).stages(FRAGMENT)),
+    v("gl_Layer","int",BuiltinQualifier::IN,Availability::since(430,320).stages(FRAGMENT)),
+    v("gl_ViewportIndex","int",BuiltinQualifier::IN,Availability::desktop(430).stages(FRAGMENT)),
+    v("gl_HelperInvocation","bool",BuiltinQualifier::IN,Availability::since(450,310).stages(FRAGMENT)),
+    v("gl_FragDepth","float",BuiltinQualifier::OUT,Availability::since(110,300).stages(FRAGMENT)),
+    v("gl_SampleMask","int[]",BuiltinQualifier::OUT,Availability::since(400,320).stages(FRAGMENT)),
+    v("gl_FragColor","vec4",BuiltinQualifier::OUT,ALL.removed_in_core(140).stages(FRAGMENT)),
+    v("gl_FragData","vec4[]",BuiltinQualifier::OUT,ALL.removed_in_core(140).stages(FRAGMENT)),
+    //
+    // compute shader
+    v("gl_NumWorkGroups","uvec3",BuiltinQualifier::IN,Availability::since(430,310).stages(COMPUTE).extension("GL_ARB_compute_shader")),
+    v("gl_WorkGroupSize","uvec3",BuiltinQualifier::CONST,Availability::since(430,310).stages(COMPUTE).extension("GL_ARB_compute_shader")),
+    v("gl_WorkGroupID","uvec3",BuiltinQualifier::IN,Availability::since(430,310).stages(COMPUTE).extension("GL_ARB_compute_shader")),
+    v("gl_LocalInvocationID","uvec3",BuiltinQualifier::IN,Availability::since(430,310).stages(COMPUTE).extension("GL_ARB_compute_shader")),
+    v("gl_GlobalInvocationID","uvec3",BuiltinQualifier::IN,Availability::since(430,310).stages(COMPUTE).extension("GL_ARB_compute_shader")),
+    v("gl_LocalInvocationIndex","uint",BuiltinQualifier::IN,Availability::since(430,310).stages(COMPUTE).extension("GL_ARB_compute_shader")),
+    //
+    // every stage
+    v("gl_DepthRange","gl_DepthRangeParameters",BuiltinQualifier::UNIFORM,ALL),
+    v("gl_MaxVertexAttribs","int",BuiltinQualifier::CONST,ALL),
+    v("gl_MaxTextureImageUnits","int",BuiltinQualifier::CONST,ALL),
+    v("gl_MaxCombinedTextureImageUnits","int",BuiltinQualifier::CONST,ALL),
+    v("gl_MaxDrawBuffers","int",BuiltinQualifier::CONST,ALL),
+    v("gl_MaxClipDistances","int",BuiltinQualifier::CONST,Availability::desktop(130)),
+    //
+];
+//
+//
+/// Every overload of a built-in function, whatever the shader
+///
+/// # parameters
+///
+/// * name - the name of the function (ex: 'texture')
+///
+pub fn builtin_functions(name:&str) -> Vec<BuiltinFunction> {
+
+    FUNCTIONS.iter().filter(|f| f.name == name).flat_map(expand).collect()
+
+}
+//
+//
+/// The overloads of a built-in function that a shader can call
+pub fn available_functions(name:&str,target:&ShaderTarget) -> Vec<BuiltinFunction> {
+
+    FUNCTIONS
+        .iter()
+        .filter(|f| f.name == name && f.availability.is_available(target))
+        .flat_map(expand)
+        .collect()
+
+}
+//
+//
+/// Every declaration of a built-in variable. A variable can be declared for several stages
+/// with different qualifiers (gl_Position is an output of the vertex shader)
+pub fn builtin_variables(name:&str) -> impl Iterator<Item = &'static BuiltinVariable> + '_ {
+
+    VARIABLES.iter().filter(move |v| v.name == name)
+
+}
+//
+//
+/// The built-in variable of this name that a shader can use
+pub fn available_variable(name:&str,target:&ShaderTarget) -> Option<&'static BuiltinVariable> {
+
+    builtin_variables(name).find(|v| v.availability.is_available(target))
+
+}
+//
+//
+/// Every built-in variable that a shader can use
+pub fn available_variables(target:&ShaderTarget) -> Vec<&'static BuiltinVariable> {
+
+    VARIABLES.iter().filter(|v| v.availability.is_available(target)).collect()
+
+}
+//
+//
+/// Create every real overload of a generic signature
+fn expand(signature:&GenericSignature) -> Vec<BuiltinFunction> {
+    //
+    let mut types:Vec<&str> = signature.parameters.iter().map(|p| split_qualifier(p).1).collect();
+    types.push(signature.return_type);
+    //
+    // how many overloads the generic types create
+    let count = types.iter().map(|t| generic_types(t).map(|list| list.len()).unwrap_or(1)).max().unwrap_or(1);
+    //
+    // the sampler and image types exist for floats, signed and unsigned integers
+    let prefixes:&[&str] = match types.iter().any(|t| is_generic_opaque(t)) {
+
+        true => &["","i","u"],
+        false => &[""]
+
+    };
+    //
+    let mut functions:Vec<BuiltinFunction> = Vec::with_capacity(count * prefixes.len());
+
+    for i in 0..count {
+
+        for prefix in prefixes {
+
+            let concrete = |t:&str| -> String {
+
+                match generic_types(t) {
+
+                    Some(list) => list[i].to_string(),
+                    None if is_generic_opaque(t) =>
+                        format!("{}{}",prefix,&t[1..]),
+                    None => t.to_string()
+
+                }
+
+            };
+
+            functions.push(BuiltinFunction {
+                name:           signature.name,
+                return_type:    concrete(signature.return_type),
+                parameters:     signature.parameters.iter().map(|p| {
+
+                    let (qualifier,t) = split_qualifier(p);
+                    (qualifier,concrete(t))
+
+                }).collect(),
+                availability:   signature.availability
+            });
+
+        }
+
+    }
+
+    functions
+    //
+}
+//
+//
+/// The generic types that have a float, a signed and an unsigned version ('gsampler2D' is
+/// 'sampler2D', 'isampler2D' and 'usampler2D')
+fn is_generic_opaque(name:&str) -> bool {
+
+    name == "gvec4" || ["gsampler","gimage","gsubpassInput"].iter().any(|g| name.starts_with(g))
+
+}
+//
+//
+/// Split 'out genType' to (OUT,"genType")
+fn split_qualifier(parameter:&str) -> (ParameterQualifier,&str) {
+
+    if let Some(t) = parameter.strip_prefix("out ") {
+        return (ParameterQualifier::OUT,t);
+    }
+
+    if let Some(t) = parameter.strip_prefix("inout ") {
+        return (ParameterQualifier::INOUT,t);
+    }
+
+    (ParameterQualifier::IN,parameter)
+
+}
+//
+//
+/// The real types of a generic type of the specification
+fn generic_types(name:&str) -> Option<&'static [&'static str]> {
+
+    Some(match name {
+
+        "genType" =>    &["float","vec2","vec3","vec4"],
+        "genIType" =>   &["int","ivec2","ivec3","ivec4"],
+        "genUType" =>   &["uint","uvec2","uvec3","uvec4"],
+        "genBType" =>   &["bool","bvec2","bvec3","bvec4"],
+        "genDType" =>   &["double","dvec2","dvec3","dvec4"],
+        "vec" =>        &["vec2","vec3","vec4"],
+        "ivec" =>       &["ivec2","ivec3","ivec4"],
+        "uvec" =>       &["uvec2","uvec3","uvec4"],
+        "bvec" =>       &["bvec2","bvec3","bvec4"],
+        "dvec" =>       &["dvec2","dvec3","dvec4"],
+        "matN" =>       &["mat2","mat3","mat4"],
+        "dmatN" =>      &["dmat2","dmat3","dmat4"],
+        "mat" =>        &["mat2","mat3","mat4","mat2x3","mat2x4","mat3x2","mat3x4","mat4x2","mat4x3"],
+        "dmat" =>       &["dmat2","dmat3","dmat4","dmat2x3","dmat2x4","dmat3x2","dmat3x4","dmat4x2","dmat4x3"],
+        _ => return None
+
+    })
+
+}
+//
+//
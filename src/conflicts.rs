@@ -0,0 +1,548 @@
+//
+// ------------------------------------------------------------------------------------------
+// Layout conflicts
+//
+// The locations and the bindings given by the layout qualifiers must not overlap and must
+// stay within the limits of the implementation. A variable takes several locations or
+// bindings when it is an array, a matrix or a 64-bit vector
+//
+use std::fmt;
+use crate::ShaderFileInfo;
+use crate::lexer::Span;
+use crate::limits::Limits;
+use crate::program::{ShaderProgram,interface_array};
+use crate::resource::ResourceKind;
+use crate::locations::{location_count,block_location_count,components};
+use crate::datatype::{ShaderType,StorageQualifier,LayoutVarType,ArraySize,opaque_family};
+//
+//
+/// What is wrong with a layout qualifier
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum LayoutIssueKind {
+
+    // a location already taken: the variable that takes it and the location
+    LOCATION_OVERLAP(String,u32),
+    // a binding already taken: the resource that takes it, the set and the binding
+    BINDING_OVERLAP(String,u32,u32),
+    // the last location taken and the number of locations there are
+    LOCATION_OUT_OF_RANGE(u32,u32),
+    // the last binding taken and the number of bindings there are
+    BINDING_OUT_OF_RANGE(u32,u32),
+    // the set and the number of sets there are
+    SET_OUT_OF_RANGE(u32,u32),
+
+}
+//
+//
+/// A variable or a block with a wrong layout qualifier
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct LayoutIssue {
+
+    pub name:   String,
+    pub kind:   LayoutIssueKind,
+    pub stage:  Option<ShaderType>,
+    pub span:   Span,
+    // both start at 1
+    pub line:   usize,
+    pub column: usize,
+
+}
+//
+impl fmt::Display for LayoutIssue {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        match &self.kind {
+
+            LayoutIssueKind::LOCATION_OVERLAP(other,location) =>
+                write!(f,"'{}' takes the location {} that '{}' already takes",self.name,location,other)?,
+
+            LayoutIssueKind::BINDING_OVERLAP(other,set,binding) =>
+                write!(f,"'{}' takes the binding {} of the set {} that '{}' already takes",self.name,binding,set,other)?,
+
+            LayoutIssueKind::LOCATION_OUT_OF_RANGE(last,max) =>
+                write!(f,"'{}' takes the location {} but there are {} locations",self.name,last,max)?,
+
+            LayoutIssueKind::BINDING_OUT_OF_RANGE(last,max) =>
+                write!(f,"'{}' takes the binding {} but there are {} bindings",self.name,last,max)?,
+
+            LayoutIssueKind::SET_OUT_OF_RANGE(set,max) =>
+                write!(f,"'{}' is in the set {} but there are {} sets",self.name,set,max)?
+
+        }
+
+        match self.stage {
+
+            Some(stage) => write!(f," ({} shader {}:{})",stage.as_str(),self.line,self.column),
+            None => write!(f," ({}:{})",self.line,self.column)
+
+        }
+
+    }
+
+}
+//
+//
+/// The locations or the bindings a declaration takes
+struct Range<'a> {
+
+    name:       &'a str,
+    first:      u32,
+    count:      u32,
+    // the components of the locations, a bit by component. Always all of them for the bindings
+    components: u8,
+    // the 'index' of the outputs of a dual-source blending, 0 for the others
+    index:      u32,
+    span:       Span,
+
+}
+//
+impl Range<'_> {
+    //
+    /// The location or the binding after the last one, u64 so it doesn't overflow
+    fn end(&self) -> u64 {
+
+        self.first as u64 + self.count as u64
+
+    }
+    //
+    /// The last location or binding taken
+    fn last(&self) -> u32 {
+
+        self.first.saturating_add(self.count.saturating_sub(1))
+
+    }
+    //
+}
+//
+//
+/// Where the issues of a shader are
+struct Source<'a> {
+
+    stage:  Option<ShaderType>,
+    source: &'a str,
+
+}
+//
+impl Source<'_> {
+    //
+    fn issue(&self,name:&str,kind:LayoutIssueKind,span:Span) -> LayoutIssue {
+
+        let (line,column) = span.line_column(self.source);
+
+        LayoutIssue { name: name.to_string(), kind, stage: self.stage, span, line, column }
+
+    }
+    //
+}
+//
+//
+/// A resource that takes bindings
+struct Binding<'a> {
+
+    range:  Range<'a>,
+    units:  Units,
+    set:    Option<u32>,
+    source: Source<'a>,
+
+}
+//
+//
+/// The bindings a resource of opengl is in, each kind of resource has its own bindings
+/// and its own limit
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+enum Units {
+
+    UNIFORM_BUFFER,
+    STORAGE_BUFFER,
+    // the samplers, and the textures and the subpass inputs of vulkan glsl
+    TEXTURE,
+    IMAGE,
+    ATOMIC_COUNTER,
+
+}
+//
+impl Units {
+    //
+    /// The bindings a resource is in, None for the resources without binding
+    ///
+    /// # parameters
+    ///
+    /// * kind - the kind of the resource
+    /// * type_name - the type of the variable, only used for the opaque types
+    ///
+    fn of(kind:ResourceKind,type_name:&str) -> Option<Self> {
+
+        match kind {
+
+            ResourceKind::UNIFORM_BLOCK => Some(Units::UNIFORM_BUFFER),
+
+            ResourceKind::STORAGE_BLOCK => Some(Units::STORAGE_BUFFER),
+
+            ResourceKind::OPAQUE => match opaque_family(type_name) {
+
+                "atomic_uint" => Some(Units::ATOMIC_COUNTER),
+                family if family.starts_with("image") => Some(Units::IMAGE),
+                _ => Some(Units::TEXTURE)
+
+            },
+
+            _ => None
+
+        }
+
+    }
+    //
+    /// The number of bindings there are
+    fn max(&self,limits:&Limits) -> u32 {
+
+        match self {
+
+            Units::UNIFORM_BUFFER => limits.max_uniform_buffer_bindings,
+            Units::STORAGE_BUFFER => limits.max_shader_storage_buffer_bindings,
+            Units::TEXTURE => limits.max_combined_texture_image_units,
+            Units::IMAGE => limits.max_image_units,
+            Units::ATOMIC_COUNTER => limits.max_atomic_counter_buffer_bindings
+
+        }
+
+    }
+    //
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Find the inputs, outputs and uniforms whose locations overlap, the resources whose
+    /// bindings overlap and the values out of the limits. Two variables can share a
+    /// location when they take different components of it
+    pub fn check_layouts(&self,limits:&Limits) -> Vec<LayoutIssue> {
+
+        let mut issues = self.check_locations(limits);
+
+        issues.extend(check_bindings(self.bindings(),limits));
+
+        issues
+
+    }
+    //
+    /// Check the locations of the inputs, the outputs and the uniforms
+    fn check_locations(&self,limits:&Limits) -> Vec<LayoutIssue> {
+
+        let source = Source { stage: self.shader_type(), source: self.source() };
+
+        let mut issues:Vec<LayoutIssue> = Vec::new();
+
+        for output in [false,true] {
+
+            let storage = match output { true => StorageQualifier::OUT, false => StorageQualifier::IN };
+            let vertex_input = !output && self.shader_type() == Some(ShaderType::VERTEX);
+
+            let max = match (self.shader_type(),output) {
+
+                (Some(ShaderType::VERTEX),false) => limits.max_vertex_attribs,
+                (Some(ShaderType::FRAGMENT),true) => limits.max_draw_buffers,
+                _ => limits.max_varying_vectors
+
+            };
+
+            let variables = self.variables()
+                .filter(|v| v.has_qualifier(&storage))
+                .filter_map(|v| {
+
+                    let array = interface_array(self,&storage,v.array_size(),v.has_qualifier(&StorageQualifier::PATCH));
+
+                    Some(Range {
+                        name: v.name(),
+                        first: v.location()?,
+                        count: location_count(v.var_type(),array.as_ref(),vertex_input)?,
+                        components: components(v),
+                        index: v.layout_value(LayoutVarType::INDEX).unwrap_or(0),
+                        span: v.span()
+                    })
+
+                });
+
+            let blocks = self.blocks()
+                .filter(|b| b.has_qualifier(&storage))
+                .filter_map(|b| Some(Range {
+                    name: b.name(),
+                    first: b.layout_value(LayoutVarType::LOCATION)?,
+                    count: block_location_count(b),
+                    components: 0b1111,
+                    index: 0,
+                    span: b.span()
+                }));
+
+            let ranges:Vec<Range> = variables.chain(blocks).collect();
+
+            check_ranges(&ranges,max,&source,&mut issues);
+
+        }
+        //
+        // a uniform takes a location by element of its array
+        let uniforms:Vec<Range> = self.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::UNIFORM))
+            .filter_map(|v| Some(Range {
+                name: v.name(),
+                first: v.location()?,
+                count: element_count(v.array_size()),
+                components: 0b1111,
+                index: 0,
+                span: v.span()
+            }))
+            .collect();
+
+        check_ranges(&uniforms,limits.max_uniform_locations,&source,&mut issues);
+
+        issues
+
+    }
+    //
+    /// The resources of the shader declared with a binding
+    fn bindings(&self) -> Vec<Binding<'_>> {
+
+        let variables = self.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::UNIFORM) && v.var_type().is_opaque())
+            .filter_map(|v| Some(Binding {
+                range: Range {
+                    name: v.name(),
+                    first: v.layout_value(LayoutVarType::BINDING)?,
+                    count: element_count(v.array_size()),
+                    components: 0b1111,
+                    index: 0,
+                    span: v.span()
+                },
+                units: Units::of(ResourceKind::OPAQUE,&v.var_type().to_string())?,
+                set: v.layout_value(LayoutVarType::SET),
+                source: Source { stage: self.shader_type(), source: self.source() }
+            }));
+
+        let blocks = self.blocks()
+            .filter_map(|b| {
+
+                let units = match (b.has_qualifier(&StorageQualifier::UNIFORM),b.has_qualifier(&StorageQualifier::BUFFER)) {
+
+                    (true,_) => Units::UNIFORM_BUFFER,
+                    (_,true) => Units::STORAGE_BUFFER,
+                    _ => return None
+
+                };
+
+                Some(Binding {
+                    range: Range {
+                        name: b.name(),
+                        first: b.layout_value(LayoutVarType::BINDING)?,
+                        count: element_count(b.array_size()),
+                        components: 0b1111,
+                        index: 0,
+                        span: b.span()
+                    },
+                    units,
+                    set: b.layout_value(LayoutVarType::SET),
+                    source: Source { stage: self.shader_type(), source: self.source() }
+                })
+
+            });
+
+        variables.chain(blocks).collect()
+
+    }
+    //
+}
+//
+//
+impl ShaderProgram {
+    //
+    /// Check the layouts of every stage. The bindings are checked over the whole program,
+    /// a resource declared by several stages takes its bindings once
+    pub fn check_layouts(&self,limits:&Limits) -> Vec<LayoutIssue> {
+
+        let mut issues:Vec<LayoutIssue> = self.stages().flat_map(|s| s.check_locations(limits)).collect();
+
+        let resources = self.resources();
+
+        let bindings = resources.iter().filter_map(|r| {
+
+            let declaration = r.declarations[0];
+            let shader = self.stage(declaration.stage)?;
+
+            Some(Binding {
+                range: Range {
+                    name: r.name.as_str(),
+                    first: r.binding?,
+                    count: element_count(r.array_size.as_ref()),
+                    components: 0b1111,
+                    index: 0,
+                    span: declaration.span
+                },
+                units: Units::of(r.kind,&r.type_name)?,
+                set: r.set,
+                source: Source { stage: Some(declaration.stage), source: shader.source() }
+            })
+
+        }).collect();
+
+        issues.extend(check_bindings(bindings,limits));
+
+        issues
+
+    }
+    //
+}
+//
+//
+/// Check the bindings of resources. The resources of vulkan glsl (when a set is written)
+/// share the bindings of their set and an array takes a single binding, the ones of opengl
+/// only share them with the resources in the same units
+fn check_bindings(mut bindings:Vec<Binding>,limits:&Limits) -> Vec<LayoutIssue> {
+
+    let mut issues:Vec<LayoutIssue> = Vec::new();
+    let vulkan = bindings.iter().any(|b| b.set.is_some());
+
+    if vulkan {
+        bindings.iter_mut().for_each(|b| b.range.count = 1);
+    }
+
+    for binding in bindings.iter() {
+
+        if let Some(set) = binding.set.filter(|s| *s >= limits.max_bound_descriptor_sets) {
+
+            issues.push(binding.source.issue(
+                binding.range.name,
+                LayoutIssueKind::SET_OUT_OF_RANGE(set,limits.max_bound_descriptor_sets),
+                binding.range.span
+            ));
+
+        }
+
+    }
+
+    // the set, and the units when they don't share their bindings
+    type Key = (u32,Option<Units>);
+
+    let mut groups:Vec<(Key,Vec<&Binding>)> = Vec::new();
+
+    for binding in bindings.iter() {
+
+        let key = match vulkan {
+
+            true => (binding.set.unwrap_or(0),None),
+            false => (0,Some(binding.units))
+
+        };
+
+        match groups.iter_mut().find(|(k,_)| *k == key) {
+
+            Some((_,group)) => group.push(binding),
+            None => groups.push((key,vec![binding]))
+
+        }
+
+    }
+
+    for ((set,_),group) in groups {
+
+        for (index,binding) in group.iter().enumerate() {
+
+            let range = &binding.range;
+
+            let max = binding.units.max(limits);
+
+            if range.end() > max as u64 {
+
+                issues.push(binding.source.issue(
+                    range.name,
+                    LayoutIssueKind::BINDING_OUT_OF_RANGE(range.last(),max),
+                    range.span
+                ));
+
+            }
+
+            if let Some((other,taken)) = group[..index].iter().find_map(|o| overlap(&o.range,range).map(|b| (o,b))) {
+
+                issues.push(binding.source.issue(
+                    range.name,
+                    LayoutIssueKind::BINDING_OVERLAP(other.range.name.to_string(),set,taken),
+                    range.span
+                ));
+
+            }
+
+        }
+
+    }
+
+    issues
+
+}
+//
+//
+/// Check the locations of a list of declarations
+///
+/// # parameters
+///
+/// * ranges - the declarations in the order of the shader
+/// * max - the number of locations there are
+/// * source - where the declarations are
+/// * issues - where to add the issues found
+///
+fn check_ranges(ranges:&[Range],max:u32,source:&Source,issues:&mut Vec<LayoutIssue>) {
+
+    for (index,range) in ranges.iter().enumerate() {
+
+        if range.end() > max as u64 {
+
+            issues.push(source.issue(
+                range.name,
+                LayoutIssueKind::LOCATION_OUT_OF_RANGE(range.last(),max),
+                range.span
+            ));
+
+        }
+
+        if let Some((other,location)) = ranges[..index].iter().find_map(|o| overlap(o,range).map(|l| (o,l))) {
+            issues.push(source.issue(range.name,LayoutIssueKind::LOCATION_OVERLAP(other.name.to_string(),location),range.span));
+        }
+
+    }
+
+}
+//
+//
+/// The first location or binding two ranges both take, with a component in common and
+/// the same index
+fn overlap(first:&Range,second:&Range) -> Option<u32> {
+
+    if first.components & second.components == 0 || first.index != second.index {
+        return None;
+    }
+
+    let start = first.first.max(second.first);
+    let end = first.end().min(second.end());
+
+    match (start as u64) < end {
+
+        true => Some(start),
+        false => None
+
+    }
+
+}
+//
+//
+/// The number of elements of an array, 1 when it is not one
+fn element_count(size:Option<&ArraySize>) -> u32 {
+
+    match size {
+
+        Some(ArraySize::SIZED(n)) => *n,
+        _ => 1
+
+    }
+
+}
+//
+//
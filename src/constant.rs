@@ -0,0 +1,1319 @@
+//
+// ------------------------------------------------------------------------------------------
+// Constant expressions
+//
+// Evaluate the expressions that glsl consider constant (section 4.3.3 of the GLSL
+// specification). They are used in the initializer of the 'const' variables and in the
+// size of the arrays
+//
+use std::collections::HashMap;
+
+use crate::EParser;
+use crate::literal::{Literal,FromLiteral};
+use crate::datatype::ScalarType;
+use crate::expression::{Expression,ExpressionKind,UnaryOperator,BinaryOperator};
+//
+//
+/// The value of a constant expression
+///     example:
+///         '2.0 * 3.0'         => SCALAR(FLOAT(6.0))
+///         'vec2(0.5)'         => VECTOR([FLOAT(0.5),FLOAT(0.5)])
+///         'mat2(1.0)'         => MATRIX { columns: 2, rows: 2, [1.0,0.0,0.0,1.0] }
+///         'int[2](1,2)'       => ARRAY([SCALAR(INT(1)),SCALAR(INT(2))])
+///
+#[derive(Debug,PartialEq,Clone)]
+pub enum ConstValue {
+
+    SCALAR  (Literal),
+    VECTOR  (Vec<Literal>),
+    // the components are stored column by column
+    MATRIX  { columns: usize, rows: usize, components: Vec<Literal> },
+    ARRAY   (Vec<ConstValue>),
+
+}
+//
+impl ConstValue {
+    //
+    /// The type of the components. An empty array has none
+    pub fn scalar_type(&self) -> Option<ScalarType> {
+
+        match self {
+
+            Self::SCALAR(l) => Some(ScalarType::of(l)),
+            Self::VECTOR(v) => v.first().map(ScalarType::of),
+            Self::MATRIX { components, .. } => components.first().map(ScalarType::of),
+            Self::ARRAY(a) => a.first().and_then(|e| e.scalar_type())
+
+        }
+
+    }
+    //
+    /// All the components one after the other. Matrices are read column by column and
+    /// arrays element by element
+    pub fn components(&self) -> Vec<Literal> {
+
+        match self {
+
+            Self::SCALAR(l) => vec![*l],
+            Self::VECTOR(v) => v.clone(),
+            Self::MATRIX { components, .. } => components.clone(),
+            Self::ARRAY(a) => a.iter().flat_map(|e| e.components()).collect()
+
+        }
+
+    }
+    //
+    /// The number of columns and rows. A scalar is (1,1) and a vector is one column.
+    /// An array have the shape of its elements
+    pub fn shape(&self) -> (usize,usize) {
+
+        match self {
+
+            Self::SCALAR(_) => (1,1),
+            Self::VECTOR(v) => (1,v.len()),
+            Self::MATRIX { columns, rows, .. } => (*columns,*rows),
+            Self::ARRAY(a) => a.first().map(|e| e.shape()).unwrap_or((1,1))
+
+        }
+
+    }
+    //
+    /// The glsl name of the type of the value (ex: 'vec3', 'dmat2x3', 'int[2]')
+    pub fn type_name(&self) -> String {
+
+        let scalar = self.scalar_type().unwrap_or(ScalarType::FLOAT);
+
+        match self {
+
+            Self::SCALAR(_) => scalar.as_str().to_string(),
+            Self::VECTOR(v) => format!("{}vec{}",scalar.prefix(),v.len()),
+            Self::MATRIX { columns, rows, .. } if columns == rows =>
+                format!("{}mat{}",scalar.prefix(),columns),
+            Self::MATRIX { columns, rows, .. } => format!("{}mat{}x{}",scalar.prefix(),columns,rows),
+            Self::ARRAY(a) => match a.first() {
+
+                Some(e) => format!("{}[{}]",e.type_name(),a.len()),
+                None => "[]".to_string()
+
+            }
+
+        }
+
+    }
+    //
+    /// Return the value if it can be used as the size of an array (a positive integer)
+    pub fn as_array_size(&self) -> Option<u32> {
+
+        match self {
+
+            Self::SCALAR(Literal::INT(i)) if *i > 0 => Some(*i as u32),
+            Self::SCALAR(Literal::UINT(u)) if *u > 0 => Some(*u),
+            _ => None
+
+        }
+
+    }
+    //
+    /// Convert every component to another type
+    pub(crate) fn convert(&self,to:ScalarType) -> ConstValue {
+
+        match self {
+
+            Self::SCALAR(l) => Self::SCALAR(to.convert(*l)),
+            Self::VECTOR(v) => Self::VECTOR(v.iter().map(|l| to.convert(*l)).collect()),
+            Self::MATRIX { columns, rows, components } => Self::MATRIX {
+                columns: *columns,
+                rows: *rows,
+                components: components.iter().map(|l| to.convert(*l)).collect()
+            },
+            Self::ARRAY(a) => Self::ARRAY(a.iter().map(|e| e.convert(to)).collect())
+
+        }
+
+    }
+    //
+    /// Create a scalar, a vector or a matrix from its components
+    pub(crate) fn from_shape(columns:usize,rows:usize,components:Vec<Literal>) -> ConstValue {
+
+        match (columns,rows) {
+
+            (1,1) => Self::SCALAR(components[0]),
+            (1,_) => Self::VECTOR(components),
+            _ => Self::MATRIX { columns, rows, components }
+
+        }
+
+    }
+    //
+    fn is_scalar(&self) -> bool { matches!(self,Self::SCALAR(_)) }
+    //
+}
+//
+//
+/// Return the component type, the number of columns and of rows of a type that can be
+/// constructed (ex: 'vec3' => (FLOAT,1,3), 'dmat2x3' => (DOUBLE,2,3))
+pub fn type_shape(name:&str) -> Option<(ScalarType,usize,usize)> {
+    //
+    let scalar = match name {
+
+        "bool" =>   Some(ScalarType::BOOL),
+        "int" =>    Some(ScalarType::INT),
+        "uint" =>   Some(ScalarType::UINT),
+        "float" =>  Some(ScalarType::FLOAT),
+        "double" => Some(ScalarType::DOUBLE),
+        _ => None
+
+    };
+
+    if let Some(s) = scalar {
+        return Some((s,1,1));
+    }
+
+    let dimension = |d:&str| match d {
+
+        "2" => Some(2),
+        "3" => Some(3),
+        "4" => Some(4),
+        _ => None
+
+    };
+    //
+    // vectors
+    let (prefix,rest) = match name.find("vec") {
+
+        Some(i) => (&name[..i],&name[i + 3..]),
+        None => ("",name)
+
+    };
+
+    if name.contains("vec") {
+
+        let scalar = match prefix {
+
+            "" =>   ScalarType::FLOAT,
+            "b" =>  ScalarType::BOOL,
+            "i" =>  ScalarType::INT,
+            "u" =>  ScalarType::UINT,
+            "d" =>  ScalarType::DOUBLE,
+            _ => return None
+
+        };
+
+        return Some((scalar,1,dimension(rest)?));
+
+    }
+    //
+    // matrices, 'matN' is 'matNxN'
+    let (scalar,rest) = match name.strip_prefix("dmat") {
+
+        Some(r) => (ScalarType::DOUBLE,r),
+        None => (ScalarType::FLOAT,name.strip_prefix("mat")?)
+
+    };
+
+    match rest.split_once('x') {
+
+        Some((c,r)) => Some((scalar,dimension(c)?,dimension(r)?)),
+        None => Some((scalar,dimension(rest)?,dimension(rest)?))
+
+    }
+    //
+}
+//
+//
+/// Evaluate a constant expression
+///
+/// # parameters
+///
+/// * expr - the expression to evaluate
+/// * constants - the value of the constants that the expression can use by their name
+///
+/// # Error causes
+///
+/// * The expression use something that is not constant (a uniform, a texture lookup, ...)
+/// * The operands of an operator or the arguments of a function have the wrong types
+/// * An integer division by zero or an index out of range
+///
+pub fn evaluate(expr:&Expression,constants:&HashMap<String,ConstValue>) -> Result<ConstValue,EParser> {
+    //
+    let error = |reason:String| EParser::CONSTANT_EXPRESSION(expr.span.start,reason);
+    //
+    match &expr.kind {
+
+        ExpressionKind::LITERAL(lit) => Ok(ConstValue::SCALAR(*lit)),
+
+        ExpressionKind::IDENTIFIER(name) => match constants.get(name) {
+
+            Some(v) => Ok(v.clone()),
+            None => Err(error(format!("'{}' is not a constant",name)))
+
+        },
+
+        ExpressionKind::UNARY(op,_) if op.modifies_operand() =>
+            Err(error(format!("'{}' is not allowed in a constant expression",op.as_str()))),
+
+        ExpressionKind::ASSIGNMENT(..) =>
+            Err(error("an assignment is not allowed in a constant expression".to_string())),
+
+        ExpressionKind::SEQUENCE(_) =>
+            Err(error("the comma operator is not allowed in a constant expression".to_string())),
+
+        ExpressionKind::UNARY(op,operand) => {
+
+            let value = evaluate(operand,constants)?;
+            unary(*op,&value).map_err(error)
+
+        },
+
+        ExpressionKind::BINARY(op,left,right) => {
+
+            let left = evaluate(left,constants)?;
+            let right = evaluate(right,constants)?;
+            binary(*op,&left,&right).map_err(error)
+
+        },
+        //
+        // only the selected expression is evaluated
+        ExpressionKind::TERNARY(condition,accept,reject) => {
+
+            match evaluate(condition,constants)? {
+
+                ConstValue::SCALAR(Literal::BOOL(true)) => evaluate(accept,constants),
+                ConstValue::SCALAR(Literal::BOOL(false)) => evaluate(reject,constants),
+                v => Err(error(format!("the condition is a {} instead of a bool",v.type_name())))
+
+            }
+
+        },
+
+        ExpressionKind::CALL(name,args) => {
+
+            let values = args
+                .iter()
+                .map(|a| evaluate(a,constants))
+                .collect::<Result<Vec<ConstValue>,EParser>>()?;
+
+            match type_shape(name) {
+
+                Some((scalar,columns,rows)) => construct(scalar,columns,rows,&values),
+                None => builtin(name,&values)
+
+            }.map_err(error)
+
+        },
+
+        ExpressionKind::ARRAY_CONSTRUCTOR(name,size,args) => {
+
+            let values = args
+                .iter()
+                .map(|a| evaluate(a,constants))
+                .collect::<Result<Vec<ConstValue>,EParser>>()?;
+
+            let size = match size {
+
+                Some(s) => match evaluate(s,constants)?.as_array_size() {
+
+                    Some(n) => Some(n as usize),
+                    None => return Err(error("the size of an array must be a positive integer".to_string()))
+
+                },
+                None => None
+
+            };
+
+            construct_array(name,size,values).map_err(error)
+
+        },
+
+        ExpressionKind::INDEX(value,index) => {
+
+            let value = evaluate(value,constants)?;
+            let index = evaluate(index,constants)?;
+            index_value(&value,&index).map_err(error)
+
+        },
+
+        ExpressionKind::FIELD(value,field) => {
+
+            let value = evaluate(value,constants)?;
+            swizzle(&value,field).map_err(error)
+
+        },
+        //
+        // the length of an array, a vector or the number of columns of a matrix
+        ExpressionKind::METHOD(value,method,args) if method == "length" && args.is_empty() => {
+
+            let length = match evaluate(value,constants)? {
+
+                ConstValue::ARRAY(elements) => elements.len(),
+                ConstValue::VECTOR(components) => components.len(),
+                ConstValue::MATRIX { columns, .. } => columns,
+                v => return Err(error(format!("a {} has no length",v.type_name())))
+
+            };
+
+            Ok(ConstValue::SCALAR(Literal::INT(length as i32)))
+
+        },
+
+        ExpressionKind::METHOD(_,method,_) => Err(error(format!("unknown method '{}'",method))),
+
+    }
+    //
+}
+//
+//
+fn unary(op:UnaryOperator,value:&ConstValue) -> Result<ConstValue,String> {
+    //
+    if matches!(value,ConstValue::ARRAY(_)) {
+        return Err("an operator cant be used on an array".to_string());
+    }
+
+    let scalar = value.scalar_type().unwrap_or(ScalarType::BOOL);
+
+    let components:Vec<Literal> = match (op,scalar) {
+
+        (UnaryOperator::PLUS,s) if s != ScalarType::BOOL => return Ok(value.clone()),
+
+        (UnaryOperator::MINUS,s) if s != ScalarType::BOOL => value.components().iter().map(|l| match l {
+
+            Literal::INT(i) =>      Literal::INT(i.wrapping_neg()),
+            Literal::UINT(u) =>     Literal::UINT(u.wrapping_neg()),
+            Literal::FLOAT(f) =>    Literal::FLOAT(-f),
+            Literal::DOUBLE(d) =>   Literal::DOUBLE(-d),
+            Literal::BOOL(b) =>     Literal::BOOL(*b)
+
+        }).collect(),
+
+        (UnaryOperator::NOT,ScalarType::BOOL) if value.is_scalar() =>
+            vec![Literal::BOOL(!bool_value(value))],
+
+        (UnaryOperator::BIT_NOT,ScalarType::INT | ScalarType::UINT) => value.components().iter().map(|l| match l {
+
+            Literal::INT(i) => Literal::INT(!i),
+            Literal::UINT(u) => Literal::UINT(!u),
+            l => *l
+
+        }).collect(),
+
+        _ => return Err(format!("the operator {:?} cant be used on a {}",op,value.type_name()))
+
+    };
+
+    let (columns,rows) = value.shape();
+
+    Ok(ConstValue::from_shape(columns,rows,components))
+    //
+}
+//
+//
+fn binary(op:BinaryOperator,left:&ConstValue,right:&ConstValue) -> Result<ConstValue,String> {
+    //
+    let mismatch = || format!(
+        "the operator '{}' cant be used between a {} and a {}",
+        op.as_str(),
+        left.type_name(),
+        right.type_name()
+    );
+    //
+    match op {
+
+        BinaryOperator::AND | BinaryOperator::OR | BinaryOperator::XOR => {
+
+            match (left,right) {
+
+                (ConstValue::SCALAR(Literal::BOOL(a)),ConstValue::SCALAR(Literal::BOOL(b))) =>
+                    Ok(ConstValue::SCALAR(Literal::BOOL(match op {
+
+                        BinaryOperator::AND => *a && *b,
+                        BinaryOperator::OR => *a || *b,
+                        _ => a ^ b
+
+                    }))),
+                _ => Err(mismatch())
+
+            }
+
+        },
+
+        BinaryOperator::EQ | BinaryOperator::NE => {
+
+            let (left,right) = common_type(left,right).ok_or_else(mismatch)?;
+
+            if left.shape() != right.shape() || left.components().len() != right.components().len() {
+                return Err(mismatch());
+            }
+
+            let equal = left == right;
+
+            Ok(ConstValue::SCALAR(Literal::BOOL(if op == BinaryOperator::EQ { equal } else { !equal })))
+
+        },
+
+        BinaryOperator::LT | BinaryOperator::GT | BinaryOperator::LE | BinaryOperator::GE => {
+
+            let (left,right) = common_type(left,right).ok_or_else(mismatch)?;
+
+            let (a,b) = match (&left,&right) {
+
+                (ConstValue::SCALAR(a),ConstValue::SCALAR(b)) if !matches!(a,Literal::BOOL(_)) =>
+                    (f64::from_literal(*a),f64::from_literal(*b)),
+                _ => return Err(mismatch())
+
+            };
+
+            Ok(ConstValue::SCALAR(Literal::BOOL(match op {
+
+                BinaryOperator::LT => a < b,
+                BinaryOperator::GT => a > b,
+                BinaryOperator::LE => a <= b,
+                _ => a >= b
+
+            })))
+
+        },
+        //
+        // the type of a shift is the type of the left operand
+        BinaryOperator::SHL | BinaryOperator::SHR => {
+
+            let is_integer = |v:&ConstValue| matches!(
+                v.scalar_type(),
+                Some(ScalarType::INT | ScalarType::UINT)
+            );
+
+            if !is_integer(left) || !is_integer(right) ||
+                (left.is_scalar() && !right.is_scalar()) {
+                return Err(mismatch());
+            }
+
+            componentwise(left,right,|a,b| {
+
+                let count = match b {
+
+                    Literal::INT(i) => i as i64,
+                    Literal::UINT(u) => u as i64,
+                    _ => 0
+
+                };
+                // the result of a shift by a negative count or by the bits of the type is
+                // undefined, it is not folded to a value
+                if !(0..32).contains(&count) {
+                    return Err(format!("a shift by {} is out of the range 0 to 31",count));
+                }
+
+                let n = count as u32;
+
+                Ok(match (a,op) {
+
+                    (Literal::INT(i),BinaryOperator::SHL) => Literal::INT(i.wrapping_shl(n)),
+                    (Literal::INT(i),_) => Literal::INT(i.wrapping_shr(n)),
+                    (Literal::UINT(u),BinaryOperator::SHL) => Literal::UINT(u.wrapping_shl(n)),
+                    (Literal::UINT(u),_) => Literal::UINT(u.wrapping_shr(n)),
+                    (l,_) => l
+
+                })
+
+            }).ok_or_else(mismatch)?
+
+        },
+        //
+        // linear algebra product
+        BinaryOperator::MUL if is_matrix_product(left,right) => {
+
+            let (left,right) = common_type(left,right).ok_or_else(mismatch)?;
+            matrix_product(&left,&right).ok_or_else(mismatch)
+
+        },
+
+        _ => {
+
+            let (left,right) = common_type(left,right).ok_or_else(mismatch)?;
+
+            let integer_only = matches!(
+                op,
+                BinaryOperator::MOD | BinaryOperator::BIT_AND |
+                BinaryOperator::BIT_OR | BinaryOperator::BIT_XOR
+            );
+
+            match left.scalar_type() {
+
+                Some(ScalarType::BOOL) | None => return Err(mismatch()),
+                Some(ScalarType::FLOAT | ScalarType::DOUBLE) if integer_only => return Err(mismatch()),
+                _ => {}
+
+            }
+
+            componentwise(&left,&right,|a,b| arithmetic(op,a,b)).ok_or_else(mismatch)?
+
+        }
+
+    }
+    //
+}
+//
+//
+/// Apply an arithmetic operator on two literals of the same type
+fn arithmetic(op:BinaryOperator,a:Literal,b:Literal) -> Result<Literal,String> {
+    //
+    let division_by_zero = || "integer division by zero".to_string();
+    let overflow = || "the integer division overflows".to_string();
+    //
+    Ok(match (a,b) {
+
+        (Literal::INT(a),Literal::INT(b)) => Literal::INT(match op {
+
+            BinaryOperator::ADD => a.wrapping_add(b),
+            BinaryOperator::SUB => a.wrapping_sub(b),
+            BinaryOperator::MUL => a.wrapping_mul(b),
+            BinaryOperator::DIV | BinaryOperator::MOD if b == 0 => return Err(division_by_zero()),
+            BinaryOperator::DIV => a.checked_div(b).ok_or_else(overflow)?,
+            BinaryOperator::MOD => a.checked_rem(b).ok_or_else(overflow)?,
+            BinaryOperator::BIT_AND => a & b,
+            BinaryOperator::BIT_OR => a | b,
+            _ => a ^ b
+
+        }),
+
+        (Literal::UINT(a),Literal::UINT(b)) => Literal::UINT(match op {
+
+            BinaryOperator::ADD => a.wrapping_add(b),
+            BinaryOperator::SUB => a.wrapping_sub(b),
+            BinaryOperator::MUL => a.wrapping_mul(b),
+            BinaryOperator::DIV | BinaryOperator::MOD if b == 0 => return Err(division_by_zero()),
+            BinaryOperator::DIV => a.checked_div(b).ok_or_else(overflow)?,
+            BinaryOperator::MOD => a.checked_rem(b).ok_or_else(overflow)?,
+            BinaryOperator::BIT_AND => a & b,
+            BinaryOperator::BIT_OR => a | b,
+            _ => a ^ b
+
+        }),
+
+        (Literal::FLOAT(a),Literal::FLOAT(b)) => Literal::FLOAT(match op {
+
+            BinaryOperator::ADD => a + b,
+            BinaryOperator::SUB => a - b,
+            BinaryOperator::MUL => a * b,
+            _ => a / b
+
+        }),
+
+        (Literal::DOUBLE(a),Literal::DOUBLE(b)) => Literal::DOUBLE(match op {
+
+            BinaryOperator::ADD => a + b,
+            BinaryOperator::SUB => a - b,
+            BinaryOperator::MUL => a * b,
+            _ => a / b
+
+        }),
+
+        _ => return Err(format!("cant apply '{}' on a {} and a {}",op.as_str(),a.type_name(),b.type_name()))
+
+    })
+    //
+}
+//
+//
+/// Convert both values to the same type following the implicit conversions
+fn common_type(left:&ConstValue,right:&ConstValue) -> Option<(ConstValue,ConstValue)> {
+
+    let (a,b) = (left.scalar_type()?,right.scalar_type()?);
+
+    if a.converts_implicitly_to(b) {
+        Some((left.convert(b),right.clone()))
+    } else if b.converts_implicitly_to(a) {
+        Some((left.clone(),right.convert(a)))
+    } else {
+        None
+    }
+
+}
+//
+//
+/// Apply an operation component by component. When one of the values is a scalar, it is
+/// used with every component of the other. Return None if the shapes are not the same
+fn componentwise<F>(left:&ConstValue,right:&ConstValue,f:F) -> Option<Result<ConstValue,String>>
+where F: Fn(Literal,Literal) -> Result<Literal,String>
+{
+    //
+    if matches!(left,ConstValue::ARRAY(_)) || matches!(right,ConstValue::ARRAY(_)) {
+        return None;
+    }
+
+    let (a,b) = (left.components(),right.components());
+
+    let shape = match (left.is_scalar(),right.is_scalar()) {
+
+        (true,_) => right.shape(),
+        (_,true) => left.shape(),
+        _ if left.shape() == right.shape() => left.shape(),
+        _ => return None
+
+    };
+
+    let count = a.len().max(b.len());
+    let mut components:Vec<Literal> = Vec::with_capacity(count);
+
+    for i in 0..count {
+
+        let x = if a.len() == 1 { a[0] } else { a[i] };
+        let y = if b.len() == 1 { b[0] } else { b[i] };
+
+        match f(x,y) {
+
+            Ok(l) => components.push(l),
+            Err(e) => return Some(Err(e))
+
+        }
+
+    }
+
+    Some(Ok(ConstValue::from_shape(shape.0,shape.1,components)))
+    //
+}
+//
+//
+fn is_matrix_product(left:&ConstValue,right:&ConstValue) -> bool {
+
+    matches!(
+        (left,right),
+        (ConstValue::MATRIX { .. },ConstValue::MATRIX { .. } | ConstValue::VECTOR(_)) |
+        (ConstValue::VECTOR(_),ConstValue::MATRIX { .. })
+    )
+
+}
+//
+//
+/// The linear algebra product of a matrix with a matrix or a vector. A vector on the left
+/// is a row vector and a vector on the right is a column vector
+fn matrix_product(left:&ConstValue,right:&ConstValue) -> Option<ConstValue> {
+    //
+    let scalar = left.scalar_type()?;
+    let a:Vec<f64> = left.components().into_iter().map(f64::from_literal).collect();
+    let b:Vec<f64> = right.components().into_iter().map(f64::from_literal).collect();
+    //
+    // a vector on the left is a matrix of one row, on the right a matrix of one column
+    let (a_columns,a_rows) = match left {
+
+        ConstValue::VECTOR(v) => (v.len(),1),
+        _ => left.shape()
+
+    };
+    let (b_columns,b_rows) = right.shape();
+
+    if a_columns != b_rows {
+        return None;
+    }
+
+    let mut components:Vec<Literal> = Vec::with_capacity(b_columns * a_rows);
+
+    for c in 0..b_columns {
+        for r in 0..a_rows {
+
+            let sum:f64 = (0..a_columns).map(|k| a[k * a_rows + r] * b[c * b_rows + k]).sum();
+            components.push(scalar.convert(Literal::DOUBLE(sum)));
+
+        }
+    }
+
+    Some(match (left,right) {
+
+        (ConstValue::VECTOR(_),_) | (_,ConstValue::VECTOR(_)) => ConstValue::VECTOR(components),
+        _ => ConstValue::MATRIX { columns: b_columns, rows: a_rows, components }
+
+    })
+    //
+}
+//
+//
+/// Construct a scalar, a vector or a matrix with the rules of the section 5.4 of the glsl
+/// specification
+fn construct(scalar:ScalarType,columns:usize,rows:usize,args:&[ConstValue]) -> Result<ConstValue,String> {
+    //
+    let name = ConstValue::from_shape(columns,rows,vec![scalar.convert(Literal::INT(0));columns * rows]).type_name();
+
+    if args.is_empty() {
+        return Err(format!("the constructor {} need at least one argument",name));
+    }
+
+    if args.iter().any(|a| matches!(a,ConstValue::ARRAY(_))) {
+        return Err(format!("the constructor {} cant take an array",name));
+    }
+
+    let count = columns * rows;
+    //
+    // one scalar fill a vector or the diagonal of a matrix
+    if let [ConstValue::SCALAR(lit)] = args {
+
+        let value = scalar.convert(*lit);
+
+        if columns == 1 {
+            return Ok(ConstValue::from_shape(1,rows,vec![value;rows]));
+        }
+
+        let zero = scalar.convert(Literal::INT(0));
+        let components = (0..count).map(|i| if i / rows == i % rows { value } else { zero }).collect();
+
+        return Ok(ConstValue::MATRIX { columns, rows, components });
+
+    }
+    //
+    // a matrix from a matrix take what overlap and the rest come from the identity matrix
+    if let [ConstValue::MATRIX { columns: from_columns, rows: from_rows, components: from }] = args {
+
+        if columns > 1 {
+
+            let components = (0..count).map(|i| {
+
+                let (c,r) = (i / rows,i % rows);
+
+                if c < *from_columns && r < *from_rows {
+                    scalar.convert(from[c * from_rows + r])
+                } else {
+                    scalar.convert(Literal::INT((c == r) as i32))
+                }
+
+            }).collect();
+
+            return Ok(ConstValue::MATRIX { columns, rows, components });
+
+        }
+
+    }
+    //
+    // otherwise the components of the arguments are taken in order. The last argument can
+    // be partially used but an argument that is not used at all is an error
+    let mut components:Vec<Literal> = Vec::with_capacity(count);
+
+    for arg in args.iter() {
+
+        if components.len() >= count {
+            return Err(format!("too many arguments for the constructor {}",name));
+        }
+
+        for lit in arg.components() {
+
+            if components.len() < count {
+                components.push(scalar.convert(lit));
+            }
+
+        }
+
+    }
+
+    if components.len() < count {
+
+        return Err(format!(
+            "the constructor {} need {} components but only {} were given",
+            name,
+            count,
+            components.len()
+        ));
+
+    }
+
+    Ok(ConstValue::from_shape(columns,rows,components))
+    //
+}
+//
+//
+fn construct_array(name:&str,size:Option<usize>,args:Vec<ConstValue>) -> Result<ConstValue,String> {
+    //
+    let (scalar,columns,rows) = match type_shape(name) {
+
+        Some(shape) => shape,
+        None => return Err(format!("cant construct an array of {}",name))
+
+    };
+
+    if let Some(n) = size {
+
+        if n != args.len() {
+            return Err(format!("the array have a size of {} but {} values were given",n,args.len()));
+        }
+
+    }
+
+    let mut elements:Vec<ConstValue> = Vec::with_capacity(args.len());
+
+    for arg in args {
+
+        let valid = arg.shape() == (columns,rows) &&
+            !matches!(arg,ConstValue::ARRAY(_)) &&
+            arg.scalar_type().is_some_and(|s| s.converts_implicitly_to(scalar));
+
+        if !valid {
+            return Err(format!("a {} cant be an element of an array of {}",arg.type_name(),name));
+        }
+
+        elements.push(arg.convert(scalar));
+
+    }
+
+    Ok(ConstValue::ARRAY(elements))
+    //
+}
+//
+//
+fn index_value(value:&ConstValue,index:&ConstValue) -> Result<ConstValue,String> {
+    //
+    let i = match index {
+
+        ConstValue::SCALAR(Literal::INT(i)) if *i >= 0 => *i as usize,
+        ConstValue::SCALAR(Literal::UINT(u)) => *u as usize,
+        ConstValue::SCALAR(Literal::INT(i)) => return Err(format!("the index {} is negative",i)),
+        _ => return Err(format!("an index must be an integer but found a {}",index.type_name()))
+
+    };
+
+    let out_of_range = |len:usize| format!("the index {} is out of range of a {}",i,len);
+
+    match value {
+
+        ConstValue::VECTOR(v) => v.get(i).map(|l| ConstValue::SCALAR(*l)).ok_or_else(|| out_of_range(v.len())),
+
+        ConstValue::MATRIX { columns, rows, components } if i < *columns =>
+            Ok(ConstValue::VECTOR(components[i * rows .. (i + 1) * rows].to_vec())),
+
+        ConstValue::MATRIX { columns, .. } => Err(out_of_range(*columns)),
+
+        ConstValue::ARRAY(a) => a.get(i).cloned().ok_or_else(|| out_of_range(a.len())),
+
+        ConstValue::SCALAR(_) => Err("a scalar cant be indexed".to_string())
+
+    }
+    //
+}
+//
+//
+/// Select the components of a vector with the letters of one of the sets xyzw, rgba or stpq
+fn swizzle(value:&ConstValue,field:&str) -> Result<ConstValue,String> {
+    //
+    let components = match value {
+
+        ConstValue::SCALAR(_) | ConstValue::VECTOR(_) => value.components(),
+        _ => return Err(format!("a {} has no field '{}'",value.type_name(),field))
+
+    };
+
+    let indexes = swizzle_indexes(field,components.len())?;
+    let selected:Vec<Literal> = indexes.iter().map(|i| components[*i]).collect();
+
+    Ok(ConstValue::from_shape(1,selected.len(),selected))
+    //
+}
+//
+//
+/// Return the index of the components selected by a swizzle on a vector of the size passed
+pub(crate) fn swizzle_indexes(field:&str,size:usize) -> Result<Vec<usize>,String> {
+    //
+    const SETS: [&str;3] = ["xyzw","rgba","stpq"];
+
+    if field.is_empty() || field.len() > 4 {
+        return Err(format!("'{}' is not a valid swizzle",field));
+    }
+
+    let set = match SETS.iter().find(|s| s.contains(&field[..1])) {
+
+        Some(s) => s,
+        None => return Err(format!("'{}' is not a valid swizzle",field))
+
+    };
+
+    let mut indexes:Vec<usize> = Vec::with_capacity(field.len());
+
+    for c in field.chars() {
+
+        match set.find(c) {
+
+            Some(i) if i < size => indexes.push(i),
+            Some(_) => return Err(format!("the swizzle '{}' is out of range of a vector of {}",field,size)),
+            None => return Err(format!("the swizzle '{}' mix different sets of letters",field))
+
+        }
+
+    }
+
+    Ok(indexes)
+    //
+}
+//
+//
+/// Evaluate a built-in function that can be used in a constant expression
+fn builtin(name:&str,args:&[ConstValue]) -> Result<ConstValue,String> {
+    //
+    if args.iter().any(|a| matches!(a,ConstValue::ARRAY(_))) {
+        return Err(format!("the function {} cant take an array",name));
+    }
+
+    let float_unary:Option<fn(f64) -> f64> = match name {
+
+        "radians" =>        Some(f64::to_radians),
+        "degrees" =>        Some(f64::to_degrees),
+        "sin" =>            Some(f64::sin),
+        "cos" =>            Some(f64::cos),
+        "tan" =>            Some(f64::tan),
+        "asin" =>           Some(f64::asin),
+        "acos" =>           Some(f64::acos),
+        "atan" if args.len() == 1 => Some(f64::atan),
+        "sinh" =>           Some(f64::sinh),
+        "cosh" =>           Some(f64::cosh),
+        "tanh" =>           Some(f64::tanh),
+        "asinh" =>          Some(f64::asinh),
+        "acosh" =>          Some(f64::acosh),
+        "atanh" =>          Some(f64::atanh),
+        "exp" =>            Some(f64::exp),
+        "log" =>            Some(f64::ln),
+        "exp2" =>           Some(f64::exp2),
+        "log2" =>           Some(f64::log2),
+        "sqrt" =>           Some(f64::sqrt),
+        "inversesqrt" =>    Some(|x| 1.0 / x.sqrt()),
+        "floor" =>          Some(f64::floor),
+        "ceil" =>           Some(f64::ceil),
+        "trunc" =>          Some(f64::trunc),
+        "round" =>          Some(f64::round),
+        "roundEven" =>      Some(|x:f64| {
+            let r = x.round();
+            if (x - x.trunc()).abs() == 0.5 { 2.0 * (x / 2.0).round() } else { r }
+        }),
+        "fract" =>          Some(|x:f64| x - x.floor()),
+        _ => None
+
+    };
+
+    if let Some(f) = float_unary {
+
+        check_count(name,args,1)?;
+        return apply(args,false,|x| f(x[0]));
+
+    }
+
+    match name {
+        //
+        // component by component
+        "abs" =>    { check_count(name,args,1)?; apply(args,true,|x| x[0].abs()) },
+        "sign" =>   { check_count(name,args,1)?; apply(args,true,|x| if x[0] == 0.0 { 0.0 } else { x[0].signum() }) },
+        "atan" =>   { check_count(name,args,2)?; apply(args,false,|x| x[0].atan2(x[1])) },
+        "pow" =>    { check_count(name,args,2)?; apply(args,false,|x| x[0].powf(x[1])) },
+        "mod" =>    { check_count(name,args,2)?; apply(args,false,|x| x[0] - x[1] * (x[0] / x[1]).floor()) },
+        "min" =>    { check_count(name,args,2)?; apply(args,true,|x| x[0].min(x[1])) },
+        "max" =>    { check_count(name,args,2)?; apply(args,true,|x| x[0].max(x[1])) },
+        "step" =>   { check_count(name,args,2)?; apply(args,false,|x| if x[1] < x[0] { 0.0 } else { 1.0 }) },
+        "clamp" =>  { check_count(name,args,3)?; apply(args,true,|x| x[0].max(x[1]).min(x[2])) },
+        "mix" =>    { check_count(name,args,3)?; apply(args,false,|x| x[0] * (1.0 - x[2]) + x[1] * x[2]) },
+        "smoothstep" => {
+
+            check_count(name,args,3)?;
+            apply(args,false,|x| {
+                let t = ((x[2] - x[0]) / (x[1] - x[0])).clamp(0.0,1.0);
+                t * t * (3.0 - 2.0 * t)
+            })
+
+        },
+        //
+        // geometric
+        "length" => {
+
+            check_count(name,args,1)?;
+            let v = floats(&args[0]);
+            float_result(args,vec![v.iter().map(|x| x * x).sum::<f64>().sqrt()],1,1)
+
+        },
+        "distance" => {
+
+            check_count(name,args,2)?;
+            let (a,b) = (floats(&args[0]),floats(&args[1]));
+            same_size(name,&a,&b)?;
+            float_result(args,vec![a.iter().zip(b.iter()).map(|(x,y)| (x - y) * (x - y)).sum::<f64>().sqrt()],1,1)
+
+        },
+        "dot" => {
+
+            check_count(name,args,2)?;
+            let (a,b) = (floats(&args[0]),floats(&args[1]));
+            same_size(name,&a,&b)?;
+            float_result(args,vec![dot(&a,&b)],1,1)
+
+        },
+        "cross" => {
+
+            check_count(name,args,2)?;
+            let (a,b) = (floats(&args[0]),floats(&args[1]));
+
+            if a.len() != 3 || b.len() != 3 {
+                return Err("cross only take two vec3".to_string());
+            }
+
+            float_result(args,vec![
+                a[1] * b[2] - b[1] * a[2],
+                a[2] * b[0] - b[2] * a[0],
+                a[0] * b[1] - b[0] * a[1]
+            ],1,3)
+
+        },
+        "normalize" => {
+
+            check_count(name,args,1)?;
+            let v = floats(&args[0]);
+            let length = dot(&v,&v).sqrt();
+            float_result(args,v.iter().map(|x| x / length).collect(),1,v.len())
+
+        },
+        "reflect" => {
+
+            check_count(name,args,2)?;
+            let (i,n) = (floats(&args[0]),floats(&args[1]));
+            same_size(name,&i,&n)?;
+            let d = dot(&n,&i);
+            float_result(args,i.iter().zip(n.iter()).map(|(i,n)| i - 2.0 * d * n).collect(),1,i.len())
+
+        },
+        "faceforward" => {
+
+            check_count(name,args,3)?;
+            let (n,i,nref) = (floats(&args[0]),floats(&args[1]),floats(&args[2]));
+            same_size(name,&n,&i)?;
+            same_size(name,&n,&nref)?;
+            let sign = if dot(&nref,&i) < 0.0 { 1.0 } else { -1.0 };
+            float_result(args,n.iter().map(|x| x * sign).collect(),1,n.len())
+
+        },
+        //
+        // matrices
+        "matrixCompMult" => {
+
+            check_count(name,args,2)?;
+
+            if !matches!(args[0],ConstValue::MATRIX { .. }) || args[0].shape() != args[1].shape() {
+                return Err("matrixCompMult take two matrices of the same size".to_string());
+            }
+
+            apply(args,false,|x| x[0] * x[1])
+
+        },
+        "transpose" => {
+
+            check_count(name,args,1)?;
+
+            let (columns,rows) = match &args[0] {
+
+                ConstValue::MATRIX { columns, rows, .. } => (*columns,*rows),
+                _ => return Err("transpose take a matrix".to_string())
+
+            };
+
+            let m = floats(&args[0]);
+            let t = (0..columns * rows).map(|i| m[(i % columns) * rows + i / columns]).collect();
+
+            float_result(args,t,rows,columns)
+
+        },
+        "determinant" => {
+
+            check_count(name,args,1)?;
+
+            match &args[0] {
+
+                ConstValue::MATRIX { columns, rows, .. } if columns == rows =>
+                    float_result(args,vec![determinant(&floats(&args[0]),*columns)],1,1),
+                _ => Err("determinant take a square matrix".to_string())
+
+            }
+
+        },
+        "outerProduct" => {
+
+            check_count(name,args,2)?;
+            let (c,r) = (floats(&args[0]),floats(&args[1]));
+
+            if !matches!(args[0],ConstValue::VECTOR(_)) || !matches!(args[1],ConstValue::VECTOR(_)) {
+                return Err("outerProduct take two vectors".to_string());
+            }
+
+            let m = (0..c.len() * r.len()).map(|i| c[i % c.len()] * r[i / c.len()]).collect();
+
+            float_result(args,m,r.len(),c.len())
+
+        },
+        //
+        // vector relational
+        "lessThan" | "lessThanEqual" | "greaterThan" | "greaterThanEqual" | "equal" | "notEqual" => {
+
+            check_count(name,args,2)?;
+
+            let (a,b) = match common_type(&args[0],&args[1]) {
+
+                Some((a,b)) if matches!(a,ConstValue::VECTOR(_)) && a.shape() == b.shape() => (a,b),
+                _ => return Err(format!("{} take two vectors of the same type",name))
+
+            };
+
+            let result:Vec<Literal> = a.components().into_iter().zip(b.components()).map(|(x,y)| {
+
+                let (x,y) = (f64::from_literal(x),f64::from_literal(y));
+
+                Literal::BOOL(match name {
+
+                    "lessThan" => x < y,
+                    "lessThanEqual" => x <= y,
+                    "greaterThan" => x > y,
+                    "greaterThanEqual" => x >= y,
+                    "equal" => x == y,
+                    _ => x != y
+
+                })
+
+            }).collect();
+
+            Ok(ConstValue::VECTOR(result))
+
+        },
+        "any" | "all" | "not" => {
+
+            check_count(name,args,1)?;
+
+            let values:Vec<bool> = match &args[0] {
+
+                ConstValue::VECTOR(v) if args[0].scalar_type() == Some(ScalarType::BOOL) =>
+                    v.iter().map(|l| matches!(l,Literal::BOOL(true))).collect(),
+                _ => return Err(format!("{} take a boolean vector",name))
+
+            };
+
+            Ok(match name {
+
+                "any" => ConstValue::SCALAR(Literal::BOOL(values.iter().any(|b| *b))),
+                "all" => ConstValue::SCALAR(Literal::BOOL(values.iter().all(|b| *b))),
+                _ => ConstValue::VECTOR(values.iter().map(|b| Literal::BOOL(!b)).collect())
+
+            })
+
+        },
+
+        _ => Err(format!("'{}' is not a function that can be used in a constant expression",name))
+
+    }
+    //
+}
+//
+//
+fn check_count(name:&str,args:&[ConstValue],count:usize) -> Result<(),String> {
+
+    match args.len() == count {
+
+        true => Ok(()),
+        false => Err(format!("{} take {} arguments but {} were given",name,count,args.len()))
+
+    }
+
+}
+//
+//
+fn same_size(name:&str,a:&[f64],b:&[f64]) -> Result<(),String> {
+
+    match a.len() == b.len() {
+
+        true => Ok(()),
+        false => Err(format!("the arguments of {} must have the same size",name))
+
+    }
+
+}
+//
+//
+fn floats(value:&ConstValue) -> Vec<f64> {
+
+    value.components().into_iter().map(f64::from_literal).collect()
+
+}
+//
+//
+fn dot(a:&[f64],b:&[f64]) -> f64 { a.iter().zip(b.iter()).map(|(x,y)| x * y).sum() }
+//
+//
+fn determinant(m:&[f64],n:usize) -> f64 {
+    //
+    if n == 1 {
+        return m[0];
+    }
+    //
+    // expansion along the first column
+    (0..n).map(|r| {
+
+        let minor:Vec<f64> = (1..n)
+            .flat_map(|c| (0..n).filter(move |row| *row != r).map(move |row| (c,row)))
+            .map(|(c,row)| m[c * n + row])
+            .collect();
+
+        let sign = if r % 2 == 0 { 1.0 } else { -1.0 };
+
+        sign * m[r] * determinant(&minor,n - 1)
+
+    }).sum()
+    //
+}
+//
+//
+/// Create the result of a function that return floats. It is a double if one of the
+/// arguments is a double
+fn float_result(args:&[ConstValue],values:Vec<f64>,columns:usize,rows:usize) -> Result<ConstValue,String> {
+
+    let scalar = match args.iter().any(|a| a.scalar_type() == Some(ScalarType::DOUBLE)) {
+
+        true => ScalarType::DOUBLE,
+        false => ScalarType::FLOAT
+
+    };
+
+    let components = values.into_iter().map(|v| scalar.convert(Literal::DOUBLE(v))).collect();
+
+    Ok(ConstValue::from_shape(columns,rows,components))
+
+}
+//
+//
+/// Apply a function component by component on the arguments. The scalar arguments are
+/// used for every component. When keep_integer is true and all the arguments are of the
+/// same integer type, the result keep this type (ex: 'max(1,2)' is an int)
+fn apply<F>(args:&[ConstValue],keep_integer:bool,f:F) -> Result<ConstValue,String>
+where F: Fn(&[f64]) -> f64
+{
+    //
+    let shaped = args.iter().find(|a| !a.is_scalar()).unwrap_or(&args[0]);
+    let (columns,rows) = shaped.shape();
+    let count = shaped.components().len();
+
+    let values:Vec<Vec<f64>> = args.iter().map(floats).collect();
+
+    if values.iter().any(|v| v.len() != 1 && v.len() != count) {
+        return Err("the arguments dont have the same size".to_string());
+    }
+
+    let first = args[0].scalar_type();
+    let integer = keep_integer &&
+        matches!(first,Some(ScalarType::INT | ScalarType::UINT)) &&
+        args.iter().all(|a| a.scalar_type() == first);
+
+    let result:Vec<f64> = (0..count).map(|i| {
+
+        let x:Vec<f64> = values.iter().map(|v| if v.len() == 1 { v[0] } else { v[i] }).collect();
+        f(&x)
+
+    }).collect();
+
+    match (integer,first) {
+
+        (true,Some(scalar)) => Ok(ConstValue::from_shape(
+            columns,
+            rows,
+            result.into_iter().map(|v| scalar.convert(Literal::DOUBLE(v))).collect()
+        )),
+        _ => float_result(args,result,columns,rows)
+
+    }
+    //
+}
+//
+//
+fn bool_value(value:&ConstValue) -> bool { matches!(value,ConstValue::SCALAR(Literal::BOOL(true))) }
+//
+//
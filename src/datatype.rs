@@ -1,31 +1,66 @@
 
+use std::collections::BTreeMap;
+use crate::literal::{Literal,FromLiteral};
+use crate::constant::ConstValue;
+use crate::function::FunctionDeclaration;
+use crate::lexer::Span;
+use crate::pragma::Pragma;
+use crate::layout::MemoryLayout;
 //
 /// The different type of shader file program
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
 pub enum ShaderType {
 
     VERTEX,
     FRAGMENT,
     TESSCONTROL,
-    GEOMETRY
+    TESSEVALUATION,
+    GEOMETRY,
+    COMPUTE
+
+}
+//
+impl ShaderType {
+    //
+    /// The name of the stage in the messages
+    pub fn as_str(&self) -> &str {
+
+        match self {
+
+            Self::VERTEX =>         "vertex",
+            Self::FRAGMENT =>       "fragment",
+            Self::TESSCONTROL =>    "tessellation control",
+            Self::TESSEVALUATION => "tessellation evaluation",
+            Self::GEOMETRY =>       "geometry",
+            Self::COMPUTE =>        "compute"
 
+        }
+
+    }
+    //
 }
 //
 //
 /// These are the different types of declaration that this library will store 
 /// in the shader info struct 
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
 #[derive(Debug,PartialEq)]
 pub enum DeclarationLine {
 
     PREPROCESSOR(PreprocessorDeclarationType),
-    VARIABLE(ShaderVariables)
+    VARIABLE(ShaderVariables),
+    FUNCTION(FunctionDeclaration),
+    BLOCK(InterfaceBlock),
+    // a layout qualifier declared alone for the stage ('layout(local_size_x = 8) in;') and
+    // where it is declared
+    DEFAULT_QUALIFIER(Vec<StorageQualifier>,Span)
 
 }
 //
 //
 /// The variable data types that GLSL accepts. They store also the variable value if the 
 /// program declares data to it. Otherwise, they store nothing.
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Clone)]
 pub enum VariableType {
 
     //scalar types
@@ -56,27 +91,45 @@ pub enum VariableType {
     DVEC3(Option<[f64;3]>),
     DVEC4(Option<[f64;4]>),
 
-    // matrices types
-    MAT2(Option<[[f32;4]; 2]>),
-    MAT3(Option<[[f32;4]; 3]>),
+    // matrices types. The values are stored column by column so 'value[column][row]'
+    MAT2(Option<[[f32;2]; 2]>),
+    MAT3(Option<[[f32;3]; 3]>),
     MAT4(Option<[[f32;4]; 4]>),
 
     // Double precision matrices types
-    DMAT2(Option<[[f64;4]; 2]>),
-    DMAT3(Option<[[f64;4]; 3]>),
+    DMAT2(Option<[[f64;2]; 2]>),
+    DMAT3(Option<[[f64;3]; 3]>),
     DMAT4(Option<[[f64;4]; 4]>),
 
+    // non-square matrices types, 'matCxR' have C columns and R rows
+    MAT2X3(Option<[[f32;3]; 2]>),
+    MAT2X4(Option<[[f32;4]; 2]>),
+    MAT3X2(Option<[[f32;2]; 3]>),
+    MAT3X4(Option<[[f32;4]; 3]>),
+    MAT4X2(Option<[[f32;2]; 4]>),
+    MAT4X3(Option<[[f32;3]; 4]>),
+
+    DMAT2X3(Option<[[f64;3]; 2]>),
+    DMAT2X4(Option<[[f64;4]; 2]>),
+    DMAT3X2(Option<[[f64;2]; 3]>),
+    DMAT3X4(Option<[[f64;4]; 3]>),
+    DMAT4X2(Option<[[f64;2]; 4]>),
+    DMAT4X3(Option<[[f64;3]; 4]>),
+
     // texture sampler types
     SAMPLER2D(Option<f32>),
     USAMPLER2D(Option<u32>),
     ISAMPLER2D(Option<i32>),
 
-    //TODO: add other type when it will be needed like atomic and Image 
+    // the other opaque types kept by their name: samplers, textures, images, subpass
+    // inputs and atomic counters ('image2D', 'usamplerCube', 'subpassInput', ...)
+    OPAQUE(String),
 
 }
 //
 impl VariableType {
     //
+    #[allow(clippy::inherent_to_string)]
     pub(crate) fn to_string(&self) -> String {
 
         match self {
@@ -104,18 +157,163 @@ impl VariableType {
             Self::DMAT2(_) =>       "dmat2",
             Self::DMAT3(_) =>       "dmat3",
             Self::DMAT4(_) =>       "dmat4",
+            Self::MAT2X3(_) =>      "mat2x3",
+            Self::MAT2X4(_) =>      "mat2x4",
+            Self::MAT3X2(_) =>      "mat3x2",
+            Self::MAT3X4(_) =>      "mat3x4",
+            Self::MAT4X2(_) =>      "mat4x2",
+            Self::MAT4X3(_) =>      "mat4x3",
+            Self::DMAT2X3(_) =>     "dmat2x3",
+            Self::DMAT2X4(_) =>     "dmat2x4",
+            Self::DMAT3X2(_) =>     "dmat3x2",
+            Self::DMAT3X4(_) =>     "dmat3x4",
+            Self::DMAT4X2(_) =>     "dmat4x2",
+            Self::DMAT4X3(_) =>     "dmat4x3",
             Self::IVEC2(_) =>       "ivec2",
             Self::IVEC3(_) =>       "ivec3",
             Self::IVEC4(_) =>       "ivec4",
-            Self::SAMPLER2D(_) =>   "sampler2d",
+            Self::SAMPLER2D(_) =>   "sampler2D",
             Self::ISAMPLER2D(_) =>  "isampler2D",
-            Self::USAMPLER2D(_) =>  "usampler2D"
+            Self::USAMPLER2D(_) =>  "usampler2D",
+            Self::OPAQUE(name) =>   name.as_str()
 
         
         }.to_string()
 
     }
     //
+    /// The number of columns and rows of the type. A scalar is (1,1), a vector is one column
+    /// and a 'matCxR' have C columns of R rows. Opaque types like samplers have none
+    pub fn dimensions(&self) -> Option<(usize,usize)> {
+
+        crate::constant::type_shape(self.to_string().as_str()).map(|(_,columns,rows)| (columns,rows))
+
+    }
+    //
+    /// The type of the components. Opaque types like samplers have none
+    pub fn scalar_type(&self) -> Option<ScalarType> {
+
+        crate::constant::type_shape(self.to_string().as_str()).map(|(scalar,_,_)| scalar)
+
+    }
+    //
+    pub fn is_matrix(&self) -> bool { self.dimensions().is_some_and(|(columns,_)| columns > 1) }
+    //
+    pub fn is_vector(&self) -> bool { self.dimensions().is_some_and(|(c,r)| c == 1 && r > 1) }
+    //
+    pub fn is_scalar(&self) -> bool { self.dimensions() == Some((1,1)) }
+    //
+    /// Samplers and images, the types that are not data
+    pub fn is_opaque(&self) -> bool { self.dimensions().is_none() }
+    //
+}
+//
+//
+/// The type of the components of a scalar, a vector or a matrix
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Hash)]
+pub enum ScalarType {
+
+    BOOL,
+    INT,
+    UINT,
+    FLOAT,
+    DOUBLE,
+
+}
+//
+impl ScalarType {
+    //
+    /// The name of the type in glsl
+    pub fn as_str(&self) -> &str {
+
+        match self {
+
+            Self::BOOL =>   "bool",
+            Self::INT =>    "int",
+            Self::UINT =>   "uint",
+            Self::FLOAT =>  "float",
+            Self::DOUBLE => "double",
+
+        }
+
+    }
+    //
+    /// The prefix glsl put in front of the vector and matrix types (ex: 'i' for 'ivec3')
+    pub fn prefix(&self) -> &str {
+
+        match self {
+
+            Self::BOOL =>   "b",
+            Self::INT =>    "i",
+            Self::UINT =>   "u",
+            Self::FLOAT =>  "",
+            Self::DOUBLE => "d",
+
+        }
+
+    }
+    //
+    /// Check if glsl convert implicitly this type to the other 
+    /// (int -> uint -> float -> double). A bool is never converted
+    pub fn converts_implicitly_to(&self,other:ScalarType) -> bool {
+
+        if *self == other {
+            return true;
+        }
+
+        match (self.rank(),other.rank()) {
+
+            (Some(from),Some(to)) => from < to,
+            _ => false
+
+        }
+
+    }
+    //
+    fn rank(&self) -> Option<u8> {
+
+        match self {
+
+            Self::BOOL =>   None,
+            Self::INT =>    Some(0),
+            Self::UINT =>   Some(1),
+            Self::FLOAT =>  Some(2),
+            Self::DOUBLE => Some(3),
+
+        }
+
+    }
+    //
+    /// Convert a literal to this type with the rules of the glsl constructors
+    pub(crate) fn convert(&self,lit:Literal) -> Literal {
+
+        match self {
+
+            Self::BOOL =>   Literal::BOOL(bool::from_literal(lit)),
+            Self::INT =>    Literal::INT(i32::from_literal(lit)),
+            Self::UINT =>   Literal::UINT(u32::from_literal(lit)),
+            Self::FLOAT =>  Literal::FLOAT(f32::from_literal(lit)),
+            Self::DOUBLE => Literal::DOUBLE(f64::from_literal(lit)),
+
+        }
+
+    }
+    //
+    /// The type of a literal
+    pub fn of(lit:&Literal) -> Self {
+
+        match lit {
+
+            Literal::BOOL(_) =>     Self::BOOL,
+            Literal::INT(_) =>      Self::INT,
+            Literal::UINT(_) =>     Self::UINT,
+            Literal::FLOAT(_) =>    Self::FLOAT,
+            Literal::DOUBLE(_) =>   Self::DOUBLE,
+
+        }
+
+    }
+    //
 }
 //
 //
@@ -127,16 +325,45 @@ impl VariableType {
 #[derive(Debug,PartialEq)]
 pub enum PreprocessorDeclarationType {
 
-    VERSION(u16,VersionBranch)
+    VERSION(u16,VersionBranch),
+    // '#extension GL_ARB_separate_shader_objects : enable'. The name can be 'all'
+    EXTENSION(String,ExtensionBehavior),
+    // '#include "lights.glsl"', only with GL_GOOGLE_include_directive or
+    // GL_ARB_shading_language_include
+    INCLUDE(String),
+    PRAGMA(Pragma)
 
 }
 //
 //
+/// What an '#extension' line asks for the extension
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum ExtensionBehavior {
+
+    // the extension must be supported, the shader doesn't compile otherwise
+    REQUIRE,
+    ENABLE,
+    // enabled, but the compiler warns when it is used
+    WARN,
+    DISABLE
+
+}
+//
+impl ExtensionBehavior {
+    //
+    /// Check if the extension can be used after this line
+    pub fn enables(&self) -> bool { *self != ExtensionBehavior::DISABLE }
+    //
+}
+//
+//
 /// The different type of specifier that are suppose to come with the glsl compiler version
-#[derive(Debug,PartialEq)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
 pub enum VersionBranch {
 
     CORE,
+    COMPATIBILITY,
+    ES,
     UNKNOWN
 
 }
@@ -155,6 +382,14 @@ pub enum StorageQualifier {
     IN,
     OUT,
     UNIFORM,
+    // the storage blocks ('buffer Particles { ... };')
+    BUFFER,
+    // the variables the invocations of a work group of a compute shader share
+    SHARED,
+    // auxiliary qualifiers of the inputs and outputs
+    PATCH,
+    CENTROID,
+    SAMPLE,
     LAYOUT(LayoutDeclaration)
 
 
@@ -174,6 +409,11 @@ impl StorageQualifier {
             Self::OUT =>        "out",
             Self::LAYOUT(layout) => layout.raw.as_str(),
             Self::UNIFORM =>    "uniform",
+            Self::BUFFER =>     "buffer",
+            Self::SHARED =>     "shared",
+            Self::PATCH =>      "patch",
+            Self::CENTROID =>   "centroid",
+            Self::SAMPLE =>     "sample",
         
         }
 
@@ -182,6 +422,48 @@ impl StorageQualifier {
 }
 //
 //
+/// How an output of a stage is interpolated to the input of the next one
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum InterpolationQualifier {
+
+    SMOOTH,
+    FLAT,
+    NOPERSPECTIVE,
+
+}
+//
+impl InterpolationQualifier {
+    //
+    /// The qualifier with this name
+    pub(crate) fn from_name(name:&str) -> Option<Self> {
+
+        match name {
+
+            "smooth" =>         Some(Self::SMOOTH),
+            "flat" =>           Some(Self::FLAT),
+            "noperspective" =>  Some(Self::NOPERSPECTIVE),
+            _ =>                None
+
+        }
+
+    }
+    //
+    pub fn as_str(&self) -> &str {
+
+        match self {
+
+            Self::SMOOTH =>         "smooth",
+            Self::FLAT =>           "flat",
+            Self::NOPERSPECTIVE =>  "noperspective"
+
+        }
+
+    }
+    //
+}
+//
+//
 /// Store the declaration in parentheses when the line content have a layout storage 
 /// declaration. 
 ///     example of what this struct should store in: 
@@ -207,17 +489,44 @@ impl LayoutDeclaration {
     /// Add (LayoutVarType,u32) at the end of the vector variables  
     pub(crate) fn push(&mut self, var:(LayoutVarType,u32)) { self.variables.push(var) }
     //
+    /// The layout as written in the source ('layout (location = 2)')
+    pub fn raw(&self) -> &str { self.raw.as_str() }
+    //
+    /// The value given to a layout variable ('location' => 2)
+    pub fn get(&self, var:LayoutVarType) -> Option<u32> {
+
+        self.variables.iter().find(|(v,_)| *v == var).map(|(_,value)| *value)
+
+    }
+    //
 }
 //
 //
 /// Type of variable possible that could be declared in the parentheses of a layout 
 /// declaration
-#[derive(Debug,PartialEq)]
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
 pub enum LayoutVarType {
 
     LOCATION,
     BINDING,
     COMPONENT,
+    // the descriptor set of a resource in vulkan glsl
+    SET,
+    // the input of the blending of a fragment output, 1 for the second source
+    INDEX,
+    // the byte of a member of a block where it starts
+    OFFSET,
+    // the id of a specialization constant of vulkan glsl
+    CONSTANT_ID,
+    // the size of the work group of a compute shader, on each axis
+    LOCAL_SIZE_X,
+    LOCAL_SIZE_Y,
+    LOCAL_SIZE_Z,
+    // the id of the specialization constant that gives the size on an axis
+    LOCAL_SIZE_X_ID,
+    LOCAL_SIZE_Y_ID,
+    LOCAL_SIZE_Z_ID,
 
     //TODO: add other possible declarations see:
     //   https://www.khronos.org/opengl/wiki/Layout_Qualifier_(GLSL)
@@ -233,9 +542,16 @@ pub enum LayoutVarType {
 #[derive(Debug,PartialEq)]
 pub struct ShaderVariables {
 
-    name:       String,
-    store_type: Vec<StorageQualifier>,
-    var_type:   VariableType,
+    name:           String,
+    store_type:     Vec<StorageQualifier>,
+    var_type:       VariableType,
+    annotations:    Annotations,
+    array_size:     Option<ArraySize>,
+    value:          Option<ConstValue>,
+    interpolation:  Option<InterpolationQualifier>,
+    span:           Span,
+    // a function reachable from 'main' uses it
+    used:           bool,
 
 
 }
@@ -249,14 +565,328 @@ impl ShaderVariables {
         var_type:   VariableType ) -> Self {
 
         ShaderVariables { 
-            name:       name.to_string(),
-            store_type: store_type,
-            var_type:   var_type 
+            name:           name.to_string(),
+            store_type,
+            var_type,
+            annotations:    Annotations::init(),
+            array_size:     None,
+            value:          None,
+            interpolation:  None,
+            span:           Span::default(),
+            used:           false
         }
     
     }
     //
+    /// Merge annotations found for this variable with the one it already have. A key 
+    /// that already exist is overwritten by the new value
+    pub(crate) fn annotate(&mut self, annotations:Annotations) {
+
+        self.annotations.extend(annotations)
+
+    }
+    //
+    pub(crate) fn set_array_size(&mut self, size:Option<ArraySize>) { self.array_size = size }
+    //
+    pub(crate) fn set_value(&mut self, value:Option<ConstValue>) { self.value = value }
+    //
+    pub(crate) fn set_span(&mut self, span:Span) { self.span = span }
+    //
+    pub(crate) fn set_interpolation(&mut self, interpolation:Option<InterpolationQualifier>) {
+        self.interpolation = interpolation
+    }
+    //
+    pub(crate) fn set_used(&mut self, used:bool) { self.used = used }
+    //
+    /// The name of the variable as declared in the shader source
+    pub fn name(&self) -> &str { self.name.as_str() }
+    //
+    /// The storage qualifiers found in front of the variable declaration
+    pub fn storage_qualifiers(&self) -> &[StorageQualifier] { &self.store_type }
+    //
+    /// The data type of the variable with its value if one was declared
+    pub fn var_type(&self) -> &VariableType { &self.var_type }
+    //
+    /// The annotations attached to this variable by a doc comment or a pragma
+    pub fn annotations(&self) -> &Annotations { &self.annotations }
+    //
+    /// The size of the array if the variable is one
+    pub fn array_size(&self) -> Option<&ArraySize> { self.array_size.as_ref() }
+    //
+    /// The evaluated value of the initializer if the variable have one 
+    pub fn value(&self) -> Option<&ConstValue> { self.value.as_ref() }
+    //
+    /// Where the declaration is in the shader source, from its first qualifier to its ';'
+    pub fn span(&self) -> Span { self.span }
+    //
+    /// The interpolation qualifier written in front of the variable, None when it is the
+    /// default one
+    pub fn interpolation(&self) -> Option<InterpolationQualifier> { self.interpolation }
+    //
+    /// Check if a function reachable from 'main' reads or writes the variable, the
+    /// drivers call it an active variable
+    pub fn is_used(&self) -> bool { self.used }
+    //
+    /// Check if the variable have a storage qualifier
+    pub fn has_qualifier(&self, qualifier:&StorageQualifier) -> bool {
+
+        self.store_type.iter().any(|q| q == qualifier)
+
+    }
+    //
+    /// The value of a variable of the layout qualifier ('layout(location = 2)' => 2)
+    pub fn layout_value(&self, var:LayoutVarType) -> Option<u32> {
+
+        layout_value(&self.store_type,var)
+
+    }
+    //
+    /// The location given by the layout qualifier
+    pub fn location(&self) -> Option<u32> { self.layout_value(LayoutVarType::LOCATION) }
+    //
 }
 //
 //
+/// A block of variables declared together: the uniform and storage blocks and the blocks of
+/// inputs and outputs of a stage
+///     example:
+///         'out VertexData { vec3 normal; } vs_out[2];'
+///             - name => 'VertexData'
+///             - instance_name => 'vs_out'
+///             - members => [normal]
+///             - array_size => SIZED(2)
+///
+#[derive(Debug,PartialEq)]
+pub struct InterfaceBlock {
+
+    name:           String,
+    instance_name:  Option<String>,
+    store_type:     Vec<StorageQualifier>,
+    members:        Vec<ShaderVariables>,
+    array_size:     Option<ArraySize>,
+    annotations:    Annotations,
+    span:           Span,
+
+}
+//
+impl InterfaceBlock {
+    //
+    pub(crate) fn new(name:&str,store_type:Vec<StorageQualifier>,members:Vec<ShaderVariables>) -> Self {
+
+        InterfaceBlock {
+            name:           name.to_string(),
+            instance_name:  None,
+            store_type,
+            members,
+            array_size:     None,
+            annotations:    Annotations::init(),
+            span:           Span::default()
+        }
+
+    }
+    //
+    pub(crate) fn set_instance(&mut self, name:Option<String>, size:Option<ArraySize>) {
+
+        self.instance_name = name;
+        self.array_size = size;
+
+    }
+    //
+    pub(crate) fn annotate(&mut self, annotations:Annotations) { self.annotations.extend(annotations) }
+    //
+    pub(crate) fn set_span(&mut self, span:Span) { self.span = span }
+    //
+    pub(crate) fn members_mut(&mut self) -> &mut [ShaderVariables] { &mut self.members }
+    //
+    /// The name of the block, the one the stages use to match their blocks
+    pub fn name(&self) -> &str { self.name.as_str() }
+    //
+    /// The name used in the shader to access the members, None when the members are
+    /// accessed directly
+    pub fn instance_name(&self) -> Option<&str> { self.instance_name.as_deref() }
+    //
+    /// The storage qualifiers found in front of the block
+    pub fn storage_qualifiers(&self) -> &[StorageQualifier] { &self.store_type }
+    //
+    /// The variables declared in the block in their order
+    pub fn members(&self) -> &[ShaderVariables] { &self.members }
+    //
+    /// Check if one of the members is used from 'main', the block is active then
+    pub fn is_used(&self) -> bool { self.members.iter().any(|m| m.is_used()) }
+    //
+    /// The size of the array if the block is one
+    pub fn array_size(&self) -> Option<&ArraySize> { self.array_size.as_ref() }
+    //
+    /// The annotations attached to this block by a doc comment
+    pub fn annotations(&self) -> &Annotations { &self.annotations }
+    //
+    /// Where the block is in the shader source, from its first qualifier to its ';'
+    pub fn span(&self) -> Span { self.span }
+    //
+    /// Check if the block have a storage qualifier
+    pub fn has_qualifier(&self, qualifier:&StorageQualifier) -> bool {
+
+        self.store_type.iter().any(|q| q == qualifier)
+
+    }
+    //
+    /// The value of a variable of the layout qualifier ('layout(binding = 1)' => 1)
+    pub fn layout_value(&self, var:LayoutVarType) -> Option<u32> {
+
+        layout_value(&self.store_type,var)
+
+    }
+    //
+    /// The standard memory layout written in the layout qualifier, None for the layouts
+    /// that depend on the driver ('shared', 'packed') 
+    pub fn memory_layout(&self) -> Option<MemoryLayout> {
+
+        self.store_type.iter().find_map(|q| match q {
+
+            StorageQualifier::LAYOUT(layout) => layout.raw
+                .split(|c:char| !(c.is_alphanumeric() || c == '_'))
+                .find_map(|w| match w {
+
+                    "std140" => Some(MemoryLayout::STD140),
+                    "std430" => Some(MemoryLayout::STD430),
+                    _ => None
 
+                }),
+            _ => None
+
+        })
+
+    }
+    //
+    /// Check if the block holds the push constants of vulkan glsl ('layout(push_constant)')
+    pub fn is_push_constant(&self) -> bool {
+
+        self.store_type.iter().any(|q| match q {
+
+            StorageQualifier::LAYOUT(layout) => layout.raw
+                .split(|c:char| !(c.is_alphanumeric() || c == '_'))
+                .any(|w| w == "push_constant"),
+            _ => false
+
+        })
+
+    }
+    //
+}
+//
+//
+/// The name of an opaque type without the 'i' or 'u' of the types of integers
+///     example:
+///         'usampler2D' => 'sampler2D'
+///         'image2D'    => 'image2D'
+///
+pub(crate) fn opaque_family(name:&str) -> &str {
+
+    match name.strip_prefix(['i','u']) {
+
+        Some(base) if ["sampler","texture","image","subpassInput"].iter().any(|f| base.starts_with(f)) => base,
+        _ => name
+
+    }
+
+}
+//
+//
+/// Find the value of a layout variable in the storage qualifiers
+fn layout_value(store_type:&[StorageQualifier],var:LayoutVarType) -> Option<u32> {
+
+    store_type.iter().find_map(|q| match q {
+
+        StorageQualifier::LAYOUT(layout) => layout.get(var),
+        _ => None
+
+    })
+
+}
+//
+//
+/// The size of a variable declared as an array
+///     example:
+///         'float weights[N * 2];' => SIZED(8) if N is 4
+///         'float weights[];'      => UNSIZED
+///
+#[derive(Debug,PartialEq,Clone)]
+pub enum ArraySize {
+
+    SIZED(u32),
+    UNSIZED,
+
+}
+//
+//
+// ------------------------------------------------------------------------------------------
+// Annotations
+//
+//
+/// The value of an annotation attached to a variable. 
+///     example of how values are typed:
+///         '@hidden'           => FLAG
+///         '@range(0,1)'       => RANGE(0.0,1.0)
+///         '@label("Albedo")'  => STRING("Albedo")
+///         'step=0.1'          => FLOAT(0.1)
+///         'widget=slider'     => STRING("slider")
+///
+#[derive(Debug,PartialEq,Clone)]
+pub enum AnnotationValue {
+
+    FLAG,
+    BOOL    (bool),
+    INT     (i64),
+    FLOAT   (f64),
+    STRING  (String),
+    RANGE   (f64,f64),
+    LIST    (Vec<AnnotationValue>),
+
+}
+//
+//
+/// Store the annotations of a variable as a key/value map sorted by key. They come from 
+/// the doc comments ('///') in front of a declaration or from a '#pragma annotate' line
+///     example: 
+///         '/// @range(0,1) @label("Roughness")'
+///         '#pragma annotate roughness range=0..1 label="Roughness"'
+///
+#[derive(Debug,PartialEq,Clone,Default)]
+pub struct Annotations {
+
+    values: BTreeMap<String,AnnotationValue>
+
+}
+//
+impl Annotations {
+    //
+    /// Initialize the struct with no annotation in it
+    pub(crate) fn init() -> Self { Annotations { values: BTreeMap::new() } }
+    //
+    /// Add an annotation. Replace the value if the key already exist
+    pub(crate) fn push(&mut self, key:&str, value:AnnotationValue) { 
+        
+        self.values.insert(key.to_string(), value); 
+    
+    }
+    //
+    /// Add all the annotations of another struct
+    pub(crate) fn extend(&mut self, other:Annotations) { self.values.extend(other.values) }
+    //
+    /// Get the value of an annotation by its key
+    pub fn get(&self, key:&str) -> Option<&AnnotationValue> { self.values.get(key) }
+    //
+    /// Iterate over all the annotations sorted by key 
+    pub fn iter(&self) -> impl Iterator<Item = (&str,&AnnotationValue)> { 
+        
+        self.values.iter().map(|(k,v)| (k.as_str(),v)) 
+    
+    }
+    //
+    pub fn len(&self) -> usize { self.values.len() }
+    //
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+    //
+}
+//
+//
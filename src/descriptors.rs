@@ -0,0 +1,175 @@
+//
+// ------------------------------------------------------------------------------------------
+// Descriptor sets
+//
+// The layouts of the descriptor sets and the push constant ranges a vulkan pipeline needs
+// for a program, read from its resources. They don't depend on an api so a backend can
+// create its own objects from them
+//
+use crate::program::ShaderProgram;
+use crate::resource::{ProgramResource,ResourceKind};
+use crate::datatype::{ShaderType,ArraySize,opaque_family};
+//
+//
+/// The kind of descriptor a resource is bound with
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum DescriptorKind {
+
+    // 'sampler'
+    SAMPLER,
+    // 'sampler2D'
+    COMBINED_IMAGE_SAMPLER,
+    // 'texture2D'
+    SAMPLED_IMAGE,
+    // 'image2D'
+    STORAGE_IMAGE,
+    // 'samplerBuffer' and 'textureBuffer'
+    UNIFORM_TEXEL_BUFFER,
+    // 'imageBuffer'
+    STORAGE_TEXEL_BUFFER,
+    UNIFORM_BUFFER,
+    STORAGE_BUFFER,
+    // 'subpassInput'
+    INPUT_ATTACHMENT,
+
+}
+//
+//
+/// A binding of a descriptor set
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct DescriptorBinding {
+
+    pub binding:    u32,
+    pub kind:       DescriptorKind,
+    // the number of descriptors, None for an array without size
+    pub count:      Option<u32>,
+    // the stages that use the binding, in the order of the pipeline
+    pub stages:     Vec<ShaderType>,
+    // the name of the resource, the name of the block for the blocks
+    pub name:       String,
+
+}
+//
+//
+/// The bindings of a descriptor set sorted by binding
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct DescriptorSetLayout {
+
+    pub set:        u32,
+    pub bindings:   Vec<DescriptorBinding>,
+
+}
+//
+//
+/// The bytes of the push constants the stages use
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct PushConstantRange {
+
+    // the name of the block
+    pub name:       String,
+    pub stages:     Vec<ShaderType>,
+    pub offset:     usize,
+    pub size:       usize,
+
+}
+//
+//
+/// The descriptor of a resource, None for the resources without descriptor: the uniforms
+/// of the default block, the push constants and the atomic counters
+pub fn descriptor_kind(resource:&ProgramResource) -> Option<DescriptorKind> {
+
+    match resource.kind {
+
+        ResourceKind::UNIFORM_BLOCK => return Some(DescriptorKind::UNIFORM_BUFFER),
+        ResourceKind::STORAGE_BLOCK => return Some(DescriptorKind::STORAGE_BUFFER),
+        ResourceKind::OPAQUE => {},
+        _ => return None
+
+    }
+    //
+    // the types of integers are the same descriptors as the ones of floats
+    let name = resource.type_name.as_str();
+    let base = opaque_family(name);
+
+    let kind = match base {
+
+        "sampler" | "samplerShadow" => DescriptorKind::SAMPLER,
+        "samplerBuffer" | "textureBuffer" => DescriptorKind::UNIFORM_TEXEL_BUFFER,
+        "imageBuffer" => DescriptorKind::STORAGE_TEXEL_BUFFER,
+        _ if base.starts_with("sampler") => DescriptorKind::COMBINED_IMAGE_SAMPLER,
+        _ if base.starts_with("texture") => DescriptorKind::SAMPLED_IMAGE,
+        _ if base.starts_with("image") => DescriptorKind::STORAGE_IMAGE,
+        _ if base.starts_with("subpassInput") => DescriptorKind::INPUT_ATTACHMENT,
+        _ => return None
+
+    };
+
+    Some(kind)
+
+}
+//
+//
+impl ShaderProgram {
+    //
+    /// The layout of every descriptor set the resources of the program are in, sorted by
+    /// set. A resource without set is in the set 0 and the resources without binding
+    /// are not in it ('remap_bindings' gives them one)
+    pub fn descriptor_set_layouts(&self) -> Vec<DescriptorSetLayout> {
+
+        let mut sets:Vec<DescriptorSetLayout> = Vec::new();
+
+        for resource in self.resources() {
+
+            let (Some(kind),Some(binding)) = (descriptor_kind(&resource),resource.binding) else { continue };
+
+            let set = resource.set.unwrap_or(0);
+
+            let count = match resource.array_size {
+
+                Some(ArraySize::SIZED(n)) => Some(n),
+                Some(ArraySize::UNSIZED) => None,
+                None => Some(1)
+
+            };
+
+            let descriptor = DescriptorBinding { binding, kind, count, stages: resource.stages, name: resource.name };
+
+            match sets.iter_mut().find(|s| s.set == set) {
+
+                Some(layout) => layout.bindings.push(descriptor),
+                None => sets.push(DescriptorSetLayout { set, bindings: vec![descriptor] })
+
+            }
+
+        }
+
+        for layout in sets.iter_mut() {
+            layout.bindings.sort_by_key(|b| b.binding);
+        }
+
+        sets.sort_by_key(|s| s.set);
+
+        sets
+
+    }
+    //
+    /// The push constant blocks of the program with the stages that use them, none when no
+    /// stage does. The range starts at the member of the lowest offset, after the bytes the
+    /// offset qualifiers skip
+    pub fn push_constant_ranges(&self) -> Vec<PushConstantRange> {
+
+        self.resources().into_iter().filter(|r| r.kind == ResourceKind::PUSH_CONSTANT && !r.stages.is_empty()).filter_map(|r| {
+
+            let block = self.stage(r.declarations[0].stage)?.push_constants()?;
+            let offset = block.members.iter().map(|m| m.offset).min().unwrap_or(0);
+
+            Some(PushConstantRange { name: r.name, stages: r.stages, offset, size: block.size - offset })
+
+        }).collect()
+
+    }
+    //
+}
+//
+//
@@ -0,0 +1,693 @@
+//
+// ------------------------------------------------------------------------------------------
+// Expressions
+//
+// Parse the tokens of an expression to a tree following the operator precedence of the
+// section 5.1 of the GLSL specification
+//
+use crate::EParser;
+use crate::literal::Literal;
+use crate::lexer::{Span,Token,TokenKind,tokenize};
+//
+//
+/// A node of an expression tree and the part of the source it comes from
+#[derive(Debug,PartialEq,Clone)]
+pub struct Expression {
+
+    pub kind:   ExpressionKind,
+    pub span:   Span,
+
+}
+//
+//
+/// The different kind of expression
+///     example:
+///         'vec3(0.5)'     => CALL("vec3",[LITERAL(0.5)])
+///         'a.xy'          => FIELD(IDENTIFIER("a"),"xy")
+///         'float[2](a,b)' => ARRAY_CONSTRUCTOR("float",Some(LITERAL(2)),[a,b])
+///         'a += 2'        => ASSIGNMENT(Some(ADD),IDENTIFIER("a"),LITERAL(2))
+///         'v.length()'    => METHOD(IDENTIFIER("v"),"length",[])
+///
+#[allow(non_camel_case_types)]
+#[derive(Debug,PartialEq,Clone)]
+pub enum ExpressionKind {
+
+    LITERAL             (Literal),
+    IDENTIFIER          (String),
+    UNARY               (UnaryOperator,Box<Expression>),
+    BINARY              (BinaryOperator,Box<Expression>,Box<Expression>),
+    TERNARY             (Box<Expression>,Box<Expression>,Box<Expression>),
+    // a function call or a constructor
+    CALL                (String,Vec<Expression>),
+    ARRAY_CONSTRUCTOR   (String,Option<Box<Expression>>,Vec<Expression>),
+    INDEX               (Box<Expression>,Box<Expression>),
+    // a struct member or a swizzle
+    FIELD               (Box<Expression>,String),
+    // a method called on a value, glsl only have '.length()'
+    METHOD              (Box<Expression>,String,Vec<Expression>),
+    // '=' when there is no operator, '+=' is ADD, ...
+    ASSIGNMENT          (Option<BinaryOperator>,Box<Expression>,Box<Expression>),
+    // expressions separated by commas, the value is the last one
+    SEQUENCE            (Vec<Expression>),
+
+}
+//
+//
+/// The operators that take one operand
+#[allow(non_camel_case_types)]
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub enum UnaryOperator {
+
+    PLUS,
+    MINUS,
+    NOT,
+    BIT_NOT,
+    // '++a' and '--a'
+    PRE_INCREMENT,
+    PRE_DECREMENT,
+    // 'a++' and 'a--'
+    POST_INCREMENT,
+    POST_DECREMENT,
+
+}
+//
+impl UnaryOperator {
+    //
+    /// How the operator is written in the source
+    pub fn as_str(&self) -> &str {
+
+        match self {
+
+            Self::PLUS =>                               "+",
+            Self::MINUS =>                              "-",
+            Self::NOT =>                                "!",
+            Self::BIT_NOT =>                            "~",
+            Self::PRE_INCREMENT | Self::POST_INCREMENT => "++",
+            Self::PRE_DECREMENT | Self::POST_DECREMENT => "--",
+
+        }
+
+    }
+    //
+    /// Check if the operator write in its operand
+    pub fn modifies_operand(&self) -> bool {
+
+        matches!(self,Self::PRE_INCREMENT | Self::PRE_DECREMENT | Self::POST_INCREMENT | Self::POST_DECREMENT)
+
+    }
+    //
+}
+//
+//
+/// The operators that take two operands
+#[allow(non_camel_case_types)]
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub enum BinaryOperator {
+
+    MUL,
+    DIV,
+    MOD,
+    ADD,
+    SUB,
+    SHL,
+    SHR,
+    LT,
+    GT,
+    LE,
+    GE,
+    EQ,
+    NE,
+    BIT_AND,
+    BIT_XOR,
+    BIT_OR,
+    AND,
+    XOR,
+    OR,
+
+}
+//
+impl BinaryOperator {
+    //
+    /// Return the operator and its precedence level (higher bind tighter) for a symbol
+    fn from_symbol(symbol:&str) -> Option<(Self,u8)> {
+
+        Some(match symbol {
+
+            "*" =>  (Self::MUL,11),
+            "/" =>  (Self::DIV,11),
+            "%" =>  (Self::MOD,11),
+            "+" =>  (Self::ADD,10),
+            "-" =>  (Self::SUB,10),
+            "<<" => (Self::SHL,9),
+            ">>" => (Self::SHR,9),
+            "<" =>  (Self::LT,8),
+            ">" =>  (Self::GT,8),
+            "<=" => (Self::LE,8),
+            ">=" => (Self::GE,8),
+            "==" => (Self::EQ,7),
+            "!=" => (Self::NE,7),
+            "&" =>  (Self::BIT_AND,6),
+            "^" =>  (Self::BIT_XOR,5),
+            "|" =>  (Self::BIT_OR,4),
+            "&&" => (Self::AND,3),
+            "^^" => (Self::XOR,2),
+            "||" => (Self::OR,1),
+            _ => return None
+
+        })
+
+    }
+    //
+    /// Return the operator of a compound assignment ('+=' => ADD). '=' return Some(None)
+    fn from_assignment(symbol:&str) -> Option<Option<Self>> {
+
+        Some(Some(match symbol {
+
+            "=" =>      return Some(None),
+            "*=" =>     Self::MUL,
+            "/=" =>     Self::DIV,
+            "%=" =>     Self::MOD,
+            "+=" =>     Self::ADD,
+            "-=" =>     Self::SUB,
+            "<<=" =>    Self::SHL,
+            ">>=" =>    Self::SHR,
+            "&=" =>     Self::BIT_AND,
+            "^=" =>     Self::BIT_XOR,
+            "|=" =>     Self::BIT_OR,
+            _ => return None
+
+        }))
+
+    }
+    //
+    /// How the operator is written in the source
+    pub fn as_str(&self) -> &str {
+
+        match self {
+
+            Self::MUL =>        "*",
+            Self::DIV =>        "/",
+            Self::MOD =>        "%",
+            Self::ADD =>        "+",
+            Self::SUB =>        "-",
+            Self::SHL =>        "<<",
+            Self::SHR =>        ">>",
+            Self::LT =>         "<",
+            Self::GT =>         ">",
+            Self::LE =>         "<=",
+            Self::GE =>         ">=",
+            Self::EQ =>         "==",
+            Self::NE =>         "!=",
+            Self::BIT_AND =>    "&",
+            Self::BIT_XOR =>    "^",
+            Self::BIT_OR =>     "|",
+            Self::AND =>        "&&",
+            Self::XOR =>        "^^",
+            Self::OR =>         "||",
+
+        }
+
+    }
+    //
+}
+//
+//
+/// Parse a string that contain only one expression
+///
+/// # parameters
+///
+/// * source - the expression (ex: '2.0 * PI')
+/// * offset - the position of the expression in the file, added to every span
+///
+pub fn parse_expression(source:&str,offset:usize) -> Result<Expression,EParser> {
+
+    let tokens = tokenize(source,offset)?;
+    let mut parser = Parser::new(&tokens,offset + source.len());
+
+    let expr = parser.expression()?;
+    parser.expect_end()?;
+
+    Ok(expr)
+
+}
+//
+//
+/// Walk through a list of tokens. The expression, statement and declaration parsers are
+/// all implemented on it
+pub(crate) struct Parser<'a> {
+
+    tokens:     &'a [Token],
+    position:   usize,
+    // where the source end, used for the error at the end of the tokens
+    end:        usize,
+
+}
+//
+impl<'a> Parser<'a> {
+    //
+    pub(crate) fn new(tokens:&'a [Token],end:usize) -> Self {
+
+        Parser { tokens, position: 0, end }
+
+    }
+    //
+    pub(crate) fn peek(&self) -> Option<&'a Token> { self.tokens.get(self.position) }
+    //
+    pub(crate) fn peek_at(&self,n:usize) -> Option<&'a Token> { self.tokens.get(self.position + n) }
+    //
+    pub(crate) fn next(&mut self) -> Option<&'a Token> {
+
+        let t = self.tokens.get(self.position);
+        self.position += 1;
+        t
+
+    }
+    //
+    pub(crate) fn is_end(&self) -> bool { self.position >= self.tokens.len() }
+    //
+    /// Check if the next token is the symbol without consuming it
+    pub(crate) fn check(&self,symbol:&str) -> bool {
+
+        self.peek().is_some_and(|t| t.is_symbol(symbol))
+
+    }
+    //
+    /// Consume the next token if it is the symbol passed
+    pub(crate) fn accept(&mut self,symbol:&str) -> bool {
+
+        if self.check(symbol) {
+            self.position += 1;
+            return true;
+        }
+
+        false
+
+    }
+    //
+    /// Consume the next token that must be the symbol passed
+    pub(crate) fn expect(&mut self,symbol:&str) -> Result<Span,EParser> {
+
+        match self.peek() {
+
+            Some(t) if t.is_symbol(symbol) => { self.position += 1; Ok(t.span) },
+            _ => Err(self.error(format!("expected '{}'",symbol)))
+
+        }
+
+    }
+    //
+    /// Consume the next token that must be an identifier
+    pub(crate) fn expect_identifier(&mut self) -> Result<(String,Span),EParser> {
+
+        match self.peek() {
+
+            Some(Token { kind: TokenKind::IDENTIFIER(name), span }) => {
+                self.position += 1;
+                Ok((name.to_string(),*span))
+            },
+            _ => Err(self.error("expected an identifier".to_string()))
+
+        }
+
+    }
+    //
+    pub(crate) fn expect_end(&self) -> Result<(),EParser> {
+
+        match self.is_end() {
+
+            true => Ok(()),
+            false => Err(self.error("unexpected token after the end".to_string()))
+
+        }
+
+    }
+    //
+    /// The span of the last token consumed
+    pub(crate) fn previous_span(&self) -> Span {
+
+        match self.position.checked_sub(1).and_then(|p| self.tokens.get(p)) {
+
+            Some(t) => t.span,
+            None => Span::new(self.end,self.end)
+
+        }
+
+    }
+    //
+    /// Create a syntax error at the next token
+    pub(crate) fn error(&self,reason:String) -> EParser {
+
+        match self.peek() {
+
+            Some(t) => EParser::SYNTAX(format!("{} but found {}",reason,token_text(t)),t.span.start),
+            None => EParser::SYNTAX(format!("{} but found the end",reason),self.end)
+
+        }
+
+    }
+    //
+    /// expression: assignment (',' assignment)*
+    pub(crate) fn expression(&mut self) -> Result<Expression,EParser> {
+
+        let first = self.assignment()?;
+
+        if !self.check(",") {
+            return Ok(first);
+        }
+
+        let mut span = first.span;
+        let mut sequence = vec![first];
+
+        while self.accept(",") {
+
+            let expr = self.assignment()?;
+            span = span.merge(expr.span);
+            sequence.push(expr);
+
+        }
+
+        Ok(Expression { kind: ExpressionKind::SEQUENCE(sequence), span })
+
+    }
+    //
+    /// assignment: ternary (('=' | '+=' | ...) assignment)?
+    ///
+    /// The assignments are right associative ('a = b = c' is 'a = (b = c)')
+    pub(crate) fn assignment(&mut self) -> Result<Expression,EParser> {
+
+        let target = self.ternary()?;
+
+        let op = match self.peek().map(|t| &t.kind) {
+
+            Some(TokenKind::SYMBOL(s)) => match BinaryOperator::from_assignment(s) {
+
+                Some(op) => op,
+                None => return Ok(target)
+
+            },
+            _ => return Ok(target)
+
+        };
+
+        self.position += 1;
+
+        let value = self.assignment()?;
+        let span = target.span.merge(value.span);
+
+        Ok(Expression {
+            kind: ExpressionKind::ASSIGNMENT(op,Box::new(target),Box::new(value)),
+            span
+        })
+
+    }
+    //
+    /// ternary: binary ('?' expression ':' assignment)?
+    fn ternary(&mut self) -> Result<Expression,EParser> {
+
+        let condition = self.binary(1)?;
+
+        if !self.accept("?") {
+            return Ok(condition);
+        }
+
+        let accept = self.expression()?;
+        self.expect(":")?;
+        let reject = self.assignment()?;
+
+        let span = condition.span.merge(reject.span);
+
+        Ok(Expression {
+            kind: ExpressionKind::TERNARY(Box::new(condition),Box::new(accept),Box::new(reject)),
+            span
+        })
+
+    }
+    //
+    /// Precedence climbing for the binary operators. All of them are left associative
+    fn binary(&mut self,min_level:u8) -> Result<Expression,EParser> {
+
+        let mut left = self.unary()?;
+
+        while let Some(TokenKind::SYMBOL(s)) = self.peek().map(|t| &t.kind) {
+
+            let (op,level) = match BinaryOperator::from_symbol(s) {
+
+                Some((op,level)) if level >= min_level => (op,level),
+                _ => break
+
+            };
+
+            self.position += 1;
+
+            let right = self.binary(level + 1)?;
+            let span = left.span.merge(right.span);
+
+            left = Expression {
+                kind: ExpressionKind::BINARY(op,Box::new(left),Box::new(right)),
+                span
+            };
+
+        }
+
+        Ok(left)
+
+    }
+    //
+    /// unary: ('+' | '-' | '!' | '~' | '++' | '--') unary | postfix
+    fn unary(&mut self) -> Result<Expression,EParser> {
+
+        let op = match self.peek().map(|t| &t.kind) {
+
+            Some(TokenKind::SYMBOL("+")) => UnaryOperator::PLUS,
+            Some(TokenKind::SYMBOL("-")) => UnaryOperator::MINUS,
+            Some(TokenKind::SYMBOL("!")) => UnaryOperator::NOT,
+            Some(TokenKind::SYMBOL("~")) => UnaryOperator::BIT_NOT,
+            Some(TokenKind::SYMBOL("++")) => UnaryOperator::PRE_INCREMENT,
+            Some(TokenKind::SYMBOL("--")) => UnaryOperator::PRE_DECREMENT,
+            _ => return self.postfix()
+
+        };
+
+        let start = self.next().map(|t| t.span).unwrap_or_default();
+        let operand = self.unary()?;
+        let span = start.merge(operand.span);
+
+        Ok(Expression { kind: ExpressionKind::UNARY(op,Box::new(operand)), span })
+
+    }
+    //
+    /// postfix: primary ('[' expression ']' | '.' identifier arguments? | '++' | '--')*
+    fn postfix(&mut self) -> Result<Expression,EParser> {
+
+        let mut expr = self.primary()?;
+
+        loop {
+
+            if self.accept("[") {
+
+                let index = self.expression()?;
+                let end = self.expect("]")?;
+                let span = expr.span.merge(end);
+
+                expr = Expression {
+                    kind: ExpressionKind::INDEX(Box::new(expr),Box::new(index)),
+                    span
+                };
+
+            } else if self.accept(".") {
+
+                let (field,end) = self.expect_identifier()?;
+
+                if self.check("(") {
+
+                    let (args,end) = self.arguments()?;
+                    let span = expr.span.merge(end);
+
+                    expr = Expression { kind: ExpressionKind::METHOD(Box::new(expr),field,args), span };
+                    continue;
+
+                }
+
+                let span = expr.span.merge(end);
+
+                expr = Expression { kind: ExpressionKind::FIELD(Box::new(expr),field), span };
+
+            } else if self.check("++") || self.check("--") {
+
+                let op = match self.check("++") {
+
+                    true => UnaryOperator::POST_INCREMENT,
+                    false => UnaryOperator::POST_DECREMENT
+
+                };
+
+                let end = self.next().map(|t| t.span).unwrap_or_default();
+                let span = expr.span.merge(end);
+
+                expr = Expression { kind: ExpressionKind::UNARY(op,Box::new(expr)), span };
+
+            } else {
+                break;
+            }
+
+        }
+
+        Ok(expr)
+
+    }
+    //
+    /// primary: literal | identifier | call | array constructor | '(' expression ')'
+    fn primary(&mut self) -> Result<Expression,EParser> {
+
+        let token = match self.peek() {
+
+            Some(t) => t,
+            None => return Err(self.error("expected an expression".to_string()))
+
+        };
+
+        match &token.kind {
+
+            TokenKind::LITERAL(lit) => {
+
+                self.position += 1;
+                Ok(Expression { kind: ExpressionKind::LITERAL(*lit), span: token.span })
+
+            },
+
+            TokenKind::SYMBOL("(") => {
+
+                self.position += 1;
+                let expr = self.expression()?;
+                let end = self.expect(")")?;
+
+                Ok(Expression { kind: expr.kind, span: token.span.merge(end) })
+
+            },
+
+            TokenKind::IDENTIFIER(name) => {
+
+                self.position += 1;
+
+                if self.check("(") {
+
+                    let (args,end) = self.arguments()?;
+
+                    return Ok(Expression {
+                        kind: ExpressionKind::CALL(name.to_string(),args),
+                        span: token.span.merge(end)
+                    });
+
+                }
+                //
+                //  float[3](1.0,2.0,3.0)  ≠  values[3]
+                //       ^^^^^^^^
+                if self.check("[") && self.is_array_constructor() {
+
+                    self.position += 1;
+
+                    let size = match self.check("]") {
+
+                        true => None,
+                        false => Some(Box::new(self.expression()?))
+
+                    };
+
+                    self.expect("]")?;
+                    let (args,end) = self.arguments()?;
+
+                    return Ok(Expression {
+                        kind: ExpressionKind::ARRAY_CONSTRUCTOR(name.to_string(),size,args),
+                        span: token.span.merge(end)
+                    });
+
+                }
+
+                Ok(Expression { kind: ExpressionKind::IDENTIFIER(name.to_string()), span: token.span })
+
+            },
+
+            _ => Err(self.error("expected an expression".to_string()))
+
+        }
+
+    }
+    //
+    /// Parse the arguments of a call. Return them with the span of the close parentheses
+    pub(crate) fn arguments(&mut self) -> Result<(Vec<Expression>,Span),EParser> {
+
+        self.expect("(")?;
+
+        let mut args:Vec<Expression> = Vec::new();
+        //
+        // 'f()' and 'f(void)' have no argument
+        if self.peek().and_then(|t| t.identifier()) == Some("void") &&
+            self.peek_at(1).is_some_and(|t| t.is_symbol(")")) {
+
+            self.position += 1;
+
+        }
+
+        if !self.check(")") {
+
+            loop {
+
+                args.push(self.assignment()?);
+
+                if !self.accept(",") {
+                    break;
+                }
+
+            }
+
+        }
+
+        let end = self.expect(")")?;
+
+        Ok((args,end))
+
+    }
+    //
+    /// Look after the '[' at the current position to find if the matching ']' is followed
+    /// by a '('
+    fn is_array_constructor(&self) -> bool {
+
+        let mut depth:usize = 0;
+
+        for (i,t) in self.tokens[self.position..].iter().enumerate() {
+
+            if t.is_symbol("[") {
+                depth += 1;
+            } else if t.is_symbol("]") {
+
+                depth -= 1;
+
+                if depth == 0 {
+                    return self.peek_at(i + 1).is_some_and(|n| n.is_symbol("("));
+                }
+
+            }
+
+        }
+
+        false
+
+    }
+    //
+}
+//
+//
+/// How a token is written, used in the error messages
+pub(crate) fn token_text(token:&Token) -> String {
+
+    match &token.kind {
+
+        TokenKind::IDENTIFIER(name) => format!("'{}'",name),
+        TokenKind::SYMBOL(s) => format!("'{}'",s),
+        TokenKind::LITERAL(lit) => format!("the {} literal",lit.type_name())
+
+    }
+
+}
+//
+//
@@ -0,0 +1,114 @@
+//
+// ------------------------------------------------------------------------------------------
+// Fragment outputs
+//
+// The color attachments a fragment shader writes and what else it does to the fragments:
+// the second source of a dual-source blending, the depth it writes and the fragments it
+// discards
+//
+use crate::ShaderFileInfo;
+use crate::datatype::{ShaderType,ScalarType,StorageQualifier,LayoutVarType,ArraySize};
+//
+//
+/// A color attachment written by a fragment shader
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct RenderTarget {
+
+    // the output, with its element for the arrays ('colors[1]')
+    pub name:       String,
+    // the one written or the one 'assign_locations' gives
+    pub location:   u32,
+    // 1 for the second source of a dual-source blending
+    pub index:      u32,
+    pub components: u32,
+    pub scalar:     ScalarType,
+
+}
+//
+//
+/// What a fragment shader outputs
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct FragmentOutputs {
+
+    // sorted by location and index
+    pub targets:                Vec<RenderTarget>,
+    // an output has the index 1, the blending uses two colors of one location
+    pub dual_source_blending:   bool,
+    // 'gl_FragDepth' is written, the early depth test can't be done
+    pub writes_depth:           bool,
+    pub discards:               bool,
+
+}
+//
+impl FragmentOutputs {
+    //
+    /// The number of color attachments, up to the last location written
+    pub fn attachment_count(&self) -> u32 {
+
+        self.targets.iter().map(|t| t.location + 1).max().unwrap_or(0)
+
+    }
+    //
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// The outputs of a fragment shader, None for the other stages. An array of outputs
+    /// is a target by element at the locations that follow
+    pub fn fragment_outputs(&self) -> Option<FragmentOutputs> {
+
+        if self.shader_type() != Some(ShaderType::FRAGMENT) {
+            return None;
+        }
+
+        let assignments = self.assign_locations();
+
+        let mut targets:Vec<RenderTarget> = Vec::new();
+
+        for output in self.variables().filter(|v| v.has_qualifier(&StorageQualifier::OUT) && !v.name().starts_with("gl_")) {
+
+            let location = output.location().or_else(|| {
+                assignments.iter().find(|a| a.output && a.span == output.span()).map(|a| a.location)
+            });
+
+            let (Some(location),Some((_,rows)),Some(scalar)) =
+                (location,output.var_type().dimensions(),output.var_type().scalar_type()) else { continue };
+
+            let index = output.layout_value(LayoutVarType::INDEX).unwrap_or(0);
+
+            let elements = match output.array_size() {
+
+                Some(ArraySize::SIZED(n)) => (0..*n).map(|i| format!("{}[{}]",output.name(),i)).collect(),
+                _ => vec![output.name().to_string()]
+
+            };
+
+            for (offset,name) in elements.into_iter().enumerate() {
+
+                targets.push(RenderTarget {
+                    name,
+                    location: location + offset as u32,
+                    index,
+                    components: rows as u32,
+                    scalar
+                });
+
+            }
+
+        }
+
+        targets.sort_by_key(|t| (t.location,t.index));
+
+        Some(FragmentOutputs {
+            dual_source_blending: targets.iter().any(|t| t.index == 1),
+            targets,
+            writes_depth: self.writes_variable("gl_FragDepth"),
+            discards: self.uses_discard()
+        })
+
+    }
+    //
+}
+//
+//
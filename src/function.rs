@@ -0,0 +1,311 @@
+//
+// ------------------------------------------------------------------------------------------
+// Functions
+//
+// Parse the function prototypes and definitions of a shader (section 6.1 of the GLSL
+// specification)
+//
+use std::collections::HashMap;
+use crate::EParser;
+use crate::lexer::{Span,tokenize};
+use crate::expression::Parser;
+use crate::constant::{ConstValue,evaluate};
+use crate::datatype::ArraySize;
+use crate::statement::Statement;
+//
+//
+/// A function declared in a shader. A prototype has no body
+///     example:
+///         'vec3 shade(in vec3 n, out float d);'      => prototype
+///         'vec3 shade(in vec3 n, out float d) { }'   => definition
+///
+#[derive(Debug,PartialEq,Clone)]
+pub struct FunctionDeclaration {
+
+    pub name:           String,
+    pub return_type:    String,
+    pub parameters:     Vec<FunctionParameter>,
+    pub body:           Option<FunctionBody>,
+    pub span:           Span,
+
+}
+//
+impl FunctionDeclaration {
+    //
+    pub fn is_prototype(&self) -> bool { self.body.is_none() }
+    //
+    pub fn is_definition(&self) -> bool { self.body.is_some() }
+    //
+    /// The signature as glsl would write it without the names (ex: 'vec3 shade(vec3,float)')
+    pub fn signature(&self) -> String {
+
+        let params:Vec<String> = self.parameters.iter().map(|p| p.type_name.to_string()).collect();
+
+        format!("{} {}({})",self.return_type,self.name,params.join(","))
+
+    }
+    //
+}
+//
+//
+/// A parameter of a function
+#[derive(Debug,PartialEq,Clone)]
+pub struct FunctionParameter {
+
+    // a prototype can omit the name
+    pub name:           Option<String>,
+    pub type_name:      String,
+    pub qualifier:      ParameterQualifier,
+    pub constant:       bool,
+    // precision, memory and other qualifiers kept as written (ex: 'highp', 'readonly')
+    pub other_qualifiers: Vec<String>,
+    pub array_size:     Option<ArraySize>,
+    pub span:           Span,
+
+}
+//
+//
+/// How a parameter is passed to the function
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub enum ParameterQualifier {
+
+    IN,
+    OUT,
+    INOUT,
+
+}
+//
+//
+/// The statements between the braces of a function definition
+#[derive(Debug,PartialEq,Clone)]
+pub struct FunctionBody {
+
+    pub statements: Vec<Statement>,
+    pub span:       Span,
+
+}
+//
+//
+/// The qualifiers that can be in front of a parameter type beside 'const' and the direction
+const OTHER_PARAMETER_QUALIFIERS: [&str;9] = [
+    "highp", "mediump", "lowp", "precise",
+    "coherent", "volatile", "restrict", "readonly", "writeonly",
+];
+//
+//
+/// Parse a function prototype or definition
+///
+/// # parameters
+///
+/// * source - the text of the function from its return type to its ';' or closing brace
+/// * offset - the position of the function in the file, added to every span
+/// * constants - the constants declared before the function, the array sizes can use them
+///
+/// # Error causes
+///
+/// * The text doesn't follow 'type name(parameters)' followed by ';' or a body
+/// * A statement of the body is not valid
+///
+pub fn parse_function(source:&str,offset:usize,constants:&HashMap<String,ConstValue>) -> Result<FunctionDeclaration,EParser> {
+    //
+    let tokens = tokenize(source,offset)?;
+    let mut parser = Parser::new(&tokens,offset + source.len());
+    //
+    let start = parser.peek().map(|t| t.span).unwrap_or_default();
+    //
+    // the return type can have a precision qualifier
+    while parser.peek().and_then(|t| t.identifier()).is_some_and(|q| matches!(q,"highp" | "mediump" | "lowp" | "precise")) {
+        parser.next();
+    }
+
+    let (return_type,_) = parser.expect_identifier()?;
+
+    let return_type = match parser.array_size(constants)? {
+
+        Some(ArraySize::SIZED(n)) => format!("{}[{}]",return_type,n),
+        Some(ArraySize::UNSIZED) => return Err(parser.error("a function cant return an array without size".to_string())),
+        None => return_type
+
+    };
+
+    let (name,_) = parser.expect_identifier()?;
+
+    let parameters = parser.parameters(constants)?;
+    //
+    // prototype
+    if parser.accept(";") {
+
+        parser.expect_end()?;
+
+        return Ok(FunctionDeclaration {
+            name,
+            return_type,
+            parameters,
+            body: None,
+            span: start.merge(parser.previous_span())
+        });
+
+    }
+    //
+    // definition
+    let open = parser.expect("{")?;
+    let statements = parser.statements_until_close()?;
+
+    let close = parser.previous_span();
+    parser.expect_end()?;
+
+    Ok(FunctionDeclaration {
+        name,
+        return_type,
+        parameters,
+        body: Some(FunctionBody { statements, span: open.merge(close) }),
+        span: start.merge(close)
+    })
+    //
+}
+//
+//
+impl Parser<'_> {
+    //
+    /// Parse an optional array declaration '[size]' where the size is a constant expression
+    pub(crate) fn array_size(&mut self,constants:&HashMap<String,ConstValue>) -> Result<Option<ArraySize>,EParser> {
+
+        if !self.accept("[") {
+            return Ok(None);
+        }
+
+        if self.accept("]") {
+            return Ok(Some(ArraySize::UNSIZED));
+        }
+
+        let size = self.expression()?;
+        self.expect("]")?;
+
+        match evaluate(&size,constants) {
+
+            Ok(value) => match value.as_array_size() {
+
+                Some(n) => Ok(Some(ArraySize::SIZED(n))),
+                None => Err(EParser::SYNTAX("the size of an array must be a positive integer".to_string(),size.span.start))
+
+            },
+            Err(e) => Err(e)
+
+        }
+
+    }
+    //
+    /// parameters: '(' (void | parameter (',' parameter)*)? ')'
+    fn parameters(&mut self,constants:&HashMap<String,ConstValue>) -> Result<Vec<FunctionParameter>,EParser> {
+
+        self.expect("(")?;
+
+        let mut parameters:Vec<FunctionParameter> = Vec::new();
+
+        if self.peek().and_then(|t| t.identifier()) == Some("void") &&
+            self.peek_at(1).is_some_and(|t| t.is_symbol(")")) {
+
+            self.next();
+
+        }
+
+        if !self.check(")") {
+
+            loop {
+
+                parameters.push(self.parameter(constants)?);
+
+                if !self.accept(",") {
+                    break;
+                }
+
+            }
+
+        }
+
+        self.expect(")")?;
+
+        Ok(parameters)
+
+    }
+    //
+    /// parameter: qualifiers* type name? array?
+    fn parameter(&mut self,constants:&HashMap<String,ConstValue>) -> Result<FunctionParameter,EParser> {
+
+        let start = self.peek().map(|t| t.span).unwrap_or_default();
+
+        let mut qualifier:Option<ParameterQualifier> = None;
+        let mut constant = false;
+        let mut other_qualifiers:Vec<String> = Vec::new();
+
+        while let Some(word) = self.peek().and_then(|t| t.identifier()) {
+
+            let direction = match word {
+
+                "in" => Some(ParameterQualifier::IN),
+                "out" => Some(ParameterQualifier::OUT),
+                "inout" => Some(ParameterQualifier::INOUT),
+                _ => None
+
+            };
+
+            if let Some(d) = direction {
+
+                if qualifier.is_some() {
+                    return Err(self.error("a parameter can only have one of in, out or inout".to_string()));
+                }
+
+                qualifier = Some(d);
+
+            } else if word == "const" {
+
+                constant = true;
+
+            } else if OTHER_PARAMETER_QUALIFIERS.contains(&word) {
+
+                other_qualifiers.push(word.to_string());
+
+            } else {
+                break;
+            }
+
+            self.next();
+
+        }
+
+        let (type_name,_) = self.expect_identifier()?;
+        let mut array_size = self.array_size(constants)?;
+
+        let name = match self.peek().and_then(|t| t.identifier()) {
+
+            Some(n) => {
+
+                let n = n.to_string();
+                self.next();
+
+                if array_size.is_none() {
+                    array_size = self.array_size(constants)?;
+                }
+
+                Some(n)
+
+            },
+            None => None
+
+        };
+
+        Ok(FunctionParameter {
+            name,
+            type_name,
+            qualifier: qualifier.unwrap_or(ParameterQualifier::IN),
+            constant,
+            other_qualifiers,
+            array_size,
+            span: start.merge(self.previous_span())
+        })
+
+    }
+    //
+}
+//
+//
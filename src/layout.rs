@@ -0,0 +1,197 @@
+//
+// ------------------------------------------------------------------------------------------
+// Memory layout
+//
+// Compute the alignment and size of the variables in a uniform or a storage block following
+// the rules of the section 7.6.2.2 (Standard Uniform Block Layout) of the OpenGL
+// specification
+//
+use crate::datatype::{VariableType,ArraySize,ScalarType,ShaderVariables,LayoutVarType};
+//
+//
+/// The standard layouts of the members of a block
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum MemoryLayout {
+
+    // uniform blocks. Arrays and matrix columns are aligned on 16 bytes
+    STD140,
+    // storage blocks and push constants. Like std140 but without the 16 bytes rounding
+    STD430,
+
+}
+//
+//
+/// Where and how much space a variable take in a block
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub struct TypeLayout {
+
+    // the offset of the variable must be a multiple of it
+    pub alignment:      usize,
+    // the number of bytes used, None for an array without size
+    pub size:           Option<usize>,
+    // the distance between two elements when the variable is an array
+    pub array_stride:   Option<usize>,
+    // the distance between two columns when the variable is a matrix
+    pub matrix_stride:  Option<usize>,
+
+}
+//
+//
+/// Return the layout of a variable in a block
+///
+/// # parameters
+///
+/// * var_type - the type of the variable
+/// * array_size - the size of the array if the variable is one
+/// * layout - the layout of the block
+///
+/// Return None for the opaque types (samplers, images, ...) that cant be in a block
+///
+pub fn type_layout(
+    var_type:   &VariableType,
+    array_size: Option<&ArraySize>,
+    layout:     MemoryLayout) -> Option<TypeLayout> {
+    //
+    let (columns,rows) = var_type.dimensions()?;
+    let scalar_size = scalar_size(var_type.scalar_type()?);
+    //
+    // a vector of 2 is aligned on 2 components, a vector of 3 or 4 on 4
+    let vector_alignment = |components:usize| match components {
+
+        1 => scalar_size,
+        2 => 2 * scalar_size,
+        _ => 4 * scalar_size
+
+    };
+    //
+    // a matrix is an array of its columns
+    let (mut alignment,mut size,matrix_stride) = if columns > 1 {
+
+        let stride = round_alignment(vector_alignment(rows),layout);
+
+        (stride,columns * stride,Some(stride))
+
+    } else {
+
+        (vector_alignment(rows),rows * scalar_size,None)
+
+    };
+
+    let array_stride = match array_size {
+
+        Some(array) => {
+
+            alignment = round_alignment(alignment,layout);
+
+            let stride = round_up(size,alignment);
+
+            size = match array {
+
+                ArraySize::SIZED(n) => *n as usize * stride,
+                ArraySize::UNSIZED => 0
+
+            };
+
+            Some(stride)
+
+        },
+        None => None
+
+    };
+
+    Some(TypeLayout {
+        alignment,
+        size: match array_size { Some(ArraySize::UNSIZED) => None, _ => Some(size) },
+        array_stride,
+        matrix_stride
+    })
+    //
+}
+//
+//
+/// Return the offset and the layout of every member of a block, in their order. A member
+/// with an offset qualifier ('layout(offset = 16)') starts where it says
+///
+/// # parameters
+///
+/// * members - the members of the block
+/// * layout - the layout of the block
+///
+/// Return None if a member can't be in a block
+///
+pub fn member_layouts(members:&[ShaderVariables],layout:MemoryLayout) -> Option<Vec<(usize,TypeLayout)>> {
+
+    let mut offset = 0;
+    let mut layouts:Vec<(usize,TypeLayout)> = Vec::new();
+
+    for member in members {
+
+        let member_layout = type_layout(member.var_type(),member.array_size(),layout)?;
+
+        offset = match member.layout_value(LayoutVarType::OFFSET) {
+
+            Some(written) => written as usize,
+            None => round_up(offset,member_layout.alignment)
+
+        };
+
+        layouts.push((offset,member_layout));
+        //
+        // only the last member can be an array without size
+        offset += member_layout.size.unwrap_or(0);
+
+    }
+
+    Some(layouts)
+
+}
+//
+//
+/// The number of bytes the members of a block use, up to the end of the last one. None
+/// when the last member is an array without size or when a member can't be in a block
+pub fn block_size(members:&[ShaderVariables],layout:MemoryLayout) -> Option<usize> {
+
+    match member_layouts(members,layout)?.last() {
+
+        Some((offset,last)) => last.size.map(|size| offset + size),
+        None => Some(0)
+
+    }
+
+}
+//
+//
+/// The number of bytes of a component
+pub fn scalar_size(scalar:ScalarType) -> usize {
+
+    match scalar {
+
+        ScalarType::DOUBLE => 8,
+        _ => 4
+
+    }
+
+}
+//
+//
+/// Round a value up to the next multiple of the alignment
+pub fn round_up(value:usize,alignment:usize) -> usize {
+
+    value.div_ceil(alignment) * alignment
+
+}
+//
+//
+/// In std140, arrays and matrix columns are aligned at least like a vec4
+fn round_alignment(alignment:usize,layout:MemoryLayout) -> usize {
+
+    match layout {
+
+        MemoryLayout::STD140 => round_up(alignment,16),
+        MemoryLayout::STD430 => alignment
+
+    }
+
+}
+//
+//
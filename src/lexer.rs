@@ -0,0 +1,261 @@
+//
+// ------------------------------------------------------------------------------------------
+// Lexer
+//
+// Cut a glsl source in tokens. The comments and whitespaces are skipped and every token
+// keep its position in the source so errors and tools can point to it
+//
+use crate::EParser;
+use crate::literal::{Literal,parse_literal};
+//
+//
+/// Position of something in a shader source as a range of byte offsets
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Default,Hash,PartialOrd,Ord)]
+pub struct Span {
+
+    pub start:  usize,
+    pub end:    usize,
+
+}
+//
+impl Span {
+    //
+    pub fn new(start:usize,end:usize) -> Self { Span { start, end } }
+    //
+    /// The smallest span that cover both spans
+    pub fn merge(&self,other:Span) -> Span {
+
+        Span { start: self.start.min(other.start), end: self.end.max(other.end) }
+
+    }
+    //
+    /// Return the line and the column (both start at 1) of the start of the span
+    pub fn line_column(&self,source:&str) -> (usize,usize) {
+
+        let before = &source[..self.start.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+
+        (line,column)
+
+    }
+    //
+}
+//
+//
+/// The different kind of token
+#[derive(Debug,PartialEq,Clone)]
+pub enum TokenKind {
+
+    IDENTIFIER  (String),
+    LITERAL     (Literal),
+    SYMBOL      (&'static str),
+
+}
+//
+//
+/// A token and where it is in the source
+#[derive(Debug,PartialEq,Clone)]
+pub struct Token {
+
+    pub kind:   TokenKind,
+    pub span:   Span,
+
+}
+//
+impl Token {
+    //
+    /// Check if the token is the symbol passed
+    pub fn is_symbol(&self,symbol:&str) -> bool {
+
+        matches!(&self.kind, TokenKind::SYMBOL(s) if *s == symbol)
+
+    }
+    //
+    /// Return the name if the token is an identifier
+    pub fn identifier(&self) -> Option<&str> {
+
+        match &self.kind {
+
+            TokenKind::IDENTIFIER(name) => Some(name.as_str()),
+            _ => None
+
+        }
+
+    }
+    //
+}
+//
+//
+/// Every symbol glsl have. The longest ones are first because they are tried in order
+const SYMBOLS: [&str;48] = [
+    //
+    "<<=", ">>=",
+    //
+    "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "^^",
+    "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "##",
+    //
+    "(", ")", "[", "]", "{", "}", ".", ",", ";", ":", "?", "=",
+    "+", "-", "*", "/", "%", "<", ">", "!", "~", "&", "|", "^", "#", "\\",
+    //
+];
+//
+//
+/// Cut a glsl source in tokens
+///
+/// # parameters
+///
+/// * source - the glsl source or a part of it
+/// * offset - the position of the source in the file, added to every span
+///
+/// # Error causes
+///
+/// * A character that glsl doesn't use
+/// * A number that is not a valid literal
+/// * A block comment that is never closed
+///
+pub fn tokenize(source:&str,offset:usize) -> Result<Vec<Token>,EParser> {
+    //
+    let bytes = source.as_bytes();
+    let mut tokens:Vec<Token> = Vec::new();
+    let mut i:usize = 0;
+    //
+    while i < bytes.len() {
+
+        let c = bytes[i] as char;
+        let start = i;
+        //
+        // whitespaces
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        //
+        // comments
+        if source[i..].starts_with("//") {
+
+            i = source[i..].find('\n').map(|n| i + n).unwrap_or(bytes.len());
+            continue;
+
+        }
+
+        if source[i..].starts_with("/*") {
+
+            i = match source[i + 2..].find("*/") {
+
+                Some(n) => i + 2 + n + 2,
+                None => return Err(EParser::TOKEN(
+                    source[i..].lines().next().unwrap_or("").to_string(),
+                    "block comment is never closed".to_string()
+                ))
+
+            };
+            continue;
+
+        }
+        //
+        // identifiers and keywords
+        if c.is_ascii_alphabetic() || c == '_' {
+
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+
+            let kind = match &source[start..i] {
+
+                "true" => TokenKind::LITERAL(Literal::BOOL(true)),
+                "false" => TokenKind::LITERAL(Literal::BOOL(false)),
+                name => TokenKind::IDENTIFIER(name.to_string())
+
+            };
+
+            tokens.push(Token { kind, span: Span::new(start + offset,i + offset) });
+            continue;
+
+        }
+        //
+        // numbers. We take everything that could be part of it and let the literal parser
+        // tell if it is valid ('1.5e-3lf', '0xFFu', '.5')
+        let next_is_digit = i + 1 < bytes.len() && bytes[i + 1].is_ascii_digit();
+
+        if c.is_ascii_digit() || (c == '.' && next_is_digit) {
+
+            let is_hex = source[i..].starts_with("0x") || source[i..].starts_with("0X");
+
+            while i < bytes.len() {
+
+                let b = bytes[i];
+
+                let is_sign_of_exponent = (b == b'+' || b == b'-') && !is_hex &&
+                    matches!(bytes[i - 1], b'e' | b'E');
+
+                if !(b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || is_sign_of_exponent) {
+                    break;
+                }
+
+                i += 1;
+
+            }
+
+            let text = &source[start..i];
+            //
+            // the sign is not part of a literal, '-2147483648' is the minus of an int that
+            // doesn't fit in 32 bits. It is only allowed when the minus is not a subtraction
+            let negated = match tokens.as_slice() {
+
+                [.., before, Token { kind: TokenKind::SYMBOL("-"), .. }] => !matches!(
+                    before.kind,
+                    TokenKind::LITERAL(_) | TokenKind::IDENTIFIER(_) | TokenKind::SYMBOL(")" | "]" | "++" | "--")
+                ),
+                [Token { kind: TokenKind::SYMBOL("-"), .. }] => true,
+                _ => false
+
+            };
+
+            let lit = match parse_literal(text) {
+
+                Err(EParser::LITERAL_OVERFLOW(..)) if negated => parse_literal(&format!("-{}",text))?,
+                result => result?
+
+            };
+
+            tokens.push(Token {
+                kind: TokenKind::LITERAL(lit),
+                span: Span::new(start + offset,i + offset)
+            });
+            continue;
+
+        }
+        //
+        // symbols
+        match SYMBOLS.iter().find(|s| source[i..].starts_with(**s)) {
+
+            Some(symbol) => {
+
+                i += symbol.len();
+                tokens.push(Token {
+                    kind: TokenKind::SYMBOL(symbol),
+                    span: Span::new(start + offset,i + offset)
+                });
+
+            },
+            None => {
+
+                let unknown = source[i..].chars().next().unwrap_or(c);
+
+                return Err(EParser::TOKEN(
+                    unknown.to_string(),
+                    format!("unexpected character at byte {}",start + offset)
+                ))
+
+            }
+
+        }
+
+    }
+    //
+    Ok(tokens)
+    //
+}
+//
+//
@@ -3,12 +3,42 @@
 
 
 pub mod datatype;
+pub mod literal;
+pub mod lexer;
+pub mod expression;
+pub mod constant;
+pub mod layout;
+pub mod function;
+pub mod statement;
+pub mod semantic;
+pub mod builtin;
+pub mod version;
+pub mod pragma;
+pub mod program;
+pub mod resource;
+pub mod usage;
+pub mod locations;
+pub mod limits;
+pub mod conflicts;
+pub mod remap;
+pub mod descriptors;
+pub mod vertex;
+pub mod fragment;
+pub mod push_constants;
+pub mod specialization;
 
 use std::path::Path;
 use std::fs;
+use std::collections::HashMap;
 use thiserror::Error;
 
 use datatype::*;
+use literal::{Literal,FromLiteral};
+use expression::parse_expression;
+use constant::{ConstValue,evaluate,type_shape};
+use function::{FunctionDeclaration,parse_function};
+use lexer::Span;
+use pragma::{PragmaParser,parse_pragma};
 
 
 // TODO: finishing adding comment  
@@ -23,12 +53,37 @@ mod test {
     
     use std::env;
     use super::*;
+    use literal::parse_literal;
+    use function::ParameterQualifier;
 
     
 
     fn get_relative_path(p:&str) -> 
         String { format!("{}/{}",env::current_dir().unwrap().to_str().unwrap(),p)}
 
+    // a shader of a stage parsed from a source written in the test
+    fn shader(stage:ShaderType,source:&str) -> ShaderFileInfo {
+
+        let mut info = ShaderFileInfo::with_shader_type(stage);
+        info.parse_line(source.as_bytes()).unwrap();
+
+        info
+
+    }
+
+    // a program with a shader by stage
+    fn program(stages:&[(ShaderType,&str)]) -> program::ShaderProgram {
+
+        let mut program = program::ShaderProgram::new();
+
+        for (stage,source) in stages {
+            program.add_stage(shader(*stage,source)).unwrap();
+        }
+
+        program
+
+    }
+
     #[test]
     fn load_correctly() -> Result<(),String> {
 
@@ -64,6 +119,7 @@ mod test {
     }
     
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn broken_symlink() {
 
         let p = get_relative_path("data_test/ex_symlink/test");
@@ -81,6 +137,21 @@ mod test {
 
     }
   
+    #[test]
+    fn define_preprocessor() {
+
+        let p = get_relative_path("data_test/define_shader.frag");
+
+        match load_file(p.as_str()) {
+
+            Err(EParser::PARSING_LINE(line,_)) => assert_eq!(line.trim(),"#define LIGHT_COUNT 4"),
+            Err(e) => panic!("{}",e),
+            Ok(_) => panic!("a #define should not load")
+
+        }
+
+    }
+
     #[test]
     fn version_preprocessor(){
 
@@ -113,7 +184,7 @@ mod test {
         ];
 
         let founded = 
-            get_storage_qualifier("layout (location = 2)");
+            get_storage_qualifier("layout (location = 2)").unwrap();
 
 
         assert_eq!(expected,founded);
@@ -193,1164 +264,4090 @@ mod test {
 
     }
 
-    
+    #[test]
+    fn glsl_literals() {
 
+        use literal::Literal;
 
-}
-//
-//
-// ------------------------------------------------------------------------------------------
-// Error handling for the library
-//
-#[allow(non_camel_case_types,non_snake_case)]
-#[derive(Error,Debug,PartialEq)]
-pub enum EParser {
-    
-    #[error("Unable to loading file {0}. Reason: {1}")]
-    LOADING(String,String),
-    #[error("Unable to convert OsString to String")]
-    OS_STRING_CONVERSION,
-    #[error("The extension '{0}' is not supported")]
-    UNSUPPORTED_EXT(String),
-    #[error("The glsl language expect to have #version before anything else but found '{0}'")]
-    OMITTED_FIRST_LINE(String),
-    #[error("Unable to get index location of &str '{0}'")]
-    INDEX_PATERN(String),
-    #[error("Unable to parse string to {0}. Reason: {1}")]
-    STRING_PARSING(String,String),
-    #[error("Cant parse line {0} because of '{1}'")]
-    PARSING_LINE(String,String),
-    #[error("the line '{0}' has been marked for having a '=' but it doesn't")]
-    MARKED_VALUE_DECL(String),
-    #[error("Cant get the value of vector in line '{0}'. Reason: {1}")]
-    VEC_VALUE(String,String),
-    #[error("Cant find type of variable in line '{0}'")]
-    VARIABLE_TYPE(String),
-    #[error("Cant convert Vec<u8> to String because of {0}")]
-    U8_STRING_CONVERSION(String),
+        assert_eq!(parse_literal("0xFF").unwrap(),Literal::INT(255));
+        assert_eq!(parse_literal("017").unwrap(),Literal::INT(15));
+        assert_eq!(parse_literal("2u").unwrap(),Literal::UINT(2));
+        assert_eq!(parse_literal("0xFFFFFFFF").unwrap(),Literal::INT(-1));
+        assert_eq!(parse_literal("1.0f").unwrap(),Literal::FLOAT(1.0));
+        assert_eq!(parse_literal("1e-3").unwrap(),Literal::FLOAT(1e-3));
+        assert_eq!(parse_literal(".5E2F").unwrap(),Literal::FLOAT(50.0));
+        assert_eq!(parse_literal("1.0lf").unwrap(),Literal::DOUBLE(1.0));
+        assert_eq!(parse_literal(" 1.0").unwrap(),Literal::FLOAT(1.0));
+        assert_eq!(parse_literal("-2147483648").unwrap(),Literal::INT(i32::MIN));
 
-}
-//
-// ------------------------------------------------------------------------------------------   
-// Constant 
-// 
-/// Array of possible type that glsl accepts
-const TYPE_IN_STR: [&str;27] = [
-    //
-    // scalar
-    "bool",
-    "int",
-    "uint",
-    "float",
-    "double",
-    // unsigned integer vector
-    "uvec2",
-    "uvec3",
-    "uvec4",
-    // signed integer vector
-    "ivec2",
-    "ivec3",
-    "ivec4",
-    // boolean vector
-    "bvec2",
-    "bvec3",
-    "bvec4",
-    // float vector
-    "vec2",
-    "vec3",
-    "vec4",
-    // double precision(f64) float vector
-    "dvec2",
-    "dvec3",
-    "dvec4",
-    // float matrices
-    "mat2",
-    "mat3",
-    "mat4",
-    // double precision (f64) float matrices
-    "dmat2",
-    "dmat3",
-    "dmat4",
-    // texture 
-    "sampler2D"
-    //
-    //
-];
-//
-//
-// TODO: will probably be removed for no practical use but some part of it could be interesting
-/// Create a ShaderFileInfo struct with the path passed of a shader file
-/// 
-/// # Arguments
-/// 
-/// * 'fp' - A path to a shader file as a &str
-/// 
-/// # Error causes 
-/// 
-/// * The file passed doesn't exist or is not a file
-/// * The file passed is a broken symbolic link
-/// * The file passed caused an permission denied error
-/// * The file haven't a file extension that we take care of or cannot access it
-/// * Cant convert a the file content to a string
-/// *
-/// 
-fn load_file(fp:&str) -> Result<ShaderFileInfo,EParser> {
-    //
-    //
-    let p = Path::new(fp);
-    //
-    // check if the file exists and is allow
-    match p.try_exists()  {
+        assert!(parse_literal("018").is_err());
+        assert!(parse_literal("1f").is_err());
+        assert!(parse_literal("inf").is_err());
 
-        Ok(rep) => {
-            
-            if !rep {
+    }
 
-                if p.is_symlink()  {
-                    return Err(EParser::LOADING(fp.to_string(),"Broken symbolic link".to_string()));
-                }
+    #[test]
+    fn literal_overflow() {
 
-                return Err(EParser::LOADING(fp.to_string(),"file doesn't exist".to_string()));
-                
+        assert_eq!(
+            parse_literal("4294967296u"),
+            Err(EParser::LITERAL_OVERFLOW("4294967296u".to_string(),"uint".to_string()))
+        );
+        assert!(parse_literal("2147483648").is_err());
+        assert!(parse_literal("1e39").is_err());
+        assert!(parse_literal("1e39lf").is_ok());
 
-            }
+    }
 
+    #[test]
+    fn scalar_initializer_values() {
 
-        },
+        assert_eq!(get_var_type("int a = 0xFF;").unwrap(),VariableType::INT(Some(255)));
+        assert_eq!(get_var_type("uint a = 2u;").unwrap(),VariableType::UINT(Some(2)));
+        assert_eq!(get_var_type("float a =  1.5f ;").unwrap(),VariableType::FLOAT(Some(1.5)));
+        assert_eq!(get_var_type("float a = 1;").unwrap(),VariableType::FLOAT(Some(1.0)));
+        assert_eq!(get_var_type("double a = 1.0lf;").unwrap(),VariableType::DOUBLE(Some(1.0)));
+        assert_eq!(
+            get_var_type("ivec2 a = ivec2(0x10, 017);").unwrap(),
+            VariableType::IVEC2(Some([16,15]))
+        );
 
-        Err(e) => return Err(EParser::LOADING(fp.to_string(),e.to_string())),
+        assert!(get_var_type("int a = 1.5;").is_err());
 
     }
-    //
-    //
-    let stype:ShaderType; // determines by the file extension got
-    //
-    // Check if the file passed have a supported extension and then store it in stype
-    match p.extension() {
-        //
-        Some(_ext) => {
-            //  
-            // convert to &str
-            let ext = match _ext.to_str() {
-                Some(ex) => ex,
-                None => return Err(EParser::LOADING(fp.to_string(),EParser::OS_STRING_CONVERSION.to_string())),
 
-            };
-            //
-            // compared with the allowed extensions
-            match ext {
-                
-                "frag" => stype = ShaderType::FRAGMENT,
-                "vert" => stype = ShaderType::VERTEX,
-                _ => return Err(
-                    EParser::LOADING(
-                        fp.to_string(),
-                        EParser::UNSUPPORTED_EXT(ext.to_string()).to_string()
-                    )
-                ), 
-                
-            }
-            //
-        },
-        //
-        // weird rare case
-        None => return Err(EParser::LOADING(
-                fp.to_string(),
-                "unable to retrieve file extension. Possible causes:\n\t
-                - hadn't a file name\n\t
-                - don't have a dot\n\t
-                - have dot but nothing after".to_string()
-            ))
-        //
+    fn eval(expr:&str) -> Result<ConstValue,EParser> {
+
+        evaluate(&parse_expression(expr,0)?,&HashMap::new())
+
     }
-    //
-    //
-    // collect content of the file has a vector of string that represents each line of the file
-    // also, we filter out comment and empty lines 
-    let s:Vec<String> = match fs::read_to_string(p) {
 
-        Ok(val) => 
-            val
-            .split("\n")
-            .filter(|line| !line.to_string().trim().is_empty() )
-            .filter(|line| line.len() > 2 && &line.to_string()[0..2] != "//")
-            .map(|result| result.to_string()) .collect(),
+    #[test]
+    fn constant_expressions() {
 
-        Err(e) => return Err(EParser::LOADING(fp.to_string(),e.to_string()))
+        use literal::Literal;
 
+        assert_eq!(eval("1 + 2 * 3").unwrap(),ConstValue::SCALAR(Literal::INT(7)));
+        assert_eq!(eval("(1 + 2) * 3u").unwrap(),ConstValue::SCALAR(Literal::UINT(9)));
+        assert_eq!(eval("2 * 0.5").unwrap(),ConstValue::SCALAR(Literal::FLOAT(1.0)));
+        assert_eq!(eval("1 << 4 | 1").unwrap(),ConstValue::SCALAR(Literal::INT(17)));
+        assert_eq!(eval("true ? 1.0 : 2.0").unwrap(),ConstValue::SCALAR(Literal::FLOAT(1.0)));
+        assert_eq!(
+            eval("vec3(0.5)").unwrap(),
+            ConstValue::VECTOR(vec![Literal::FLOAT(0.5);3])
+        );
+        assert_eq!(
+            eval("vec4(1.0,2.0,3.0,4.0).wzy").unwrap(),
+            ConstValue::VECTOR(vec![Literal::FLOAT(4.0),Literal::FLOAT(3.0),Literal::FLOAT(2.0)])
+        );
+        assert_eq!(
+            eval("ivec2(vec3(1.5,2.5,3.5))").unwrap(),
+            ConstValue::VECTOR(vec![Literal::INT(1),Literal::INT(2)])
+        );
+        assert_eq!(eval("max(1,3)").unwrap(),ConstValue::SCALAR(Literal::INT(3)));
+        assert_eq!(eval("length(vec2(3.0,4.0))").unwrap(),ConstValue::SCALAR(Literal::FLOAT(5.0)));
+        assert_eq!(eval("float[](1.0,2.0)[1]").unwrap(),ConstValue::SCALAR(Literal::FLOAT(2.0)));
+
+        assert_eq!(eval("1 / 0"),Err(EParser::CONSTANT_EXPRESSION(0,"integer division by zero".to_string())));
+        assert_eq!(eval("-2147483648 / -1"),Err(EParser::CONSTANT_EXPRESSION(0,"the integer division overflows".to_string())));
+        assert!(eval("1 << 40").is_err());
+        assert!(eval("1 >> -1").is_err());
+        assert!(eval("vec2(1.0).xz").is_err());
+        assert!(eval("vec3(1.0,2.0)").is_err());
+        assert!(eval("texture(tex,uv)").is_err());
 
-    };
-    //
-    // remove void blocks
-    let mut filter_s:Vec<String> = Vec::new();
-    let mut i:usize = 0;
-    
-    while i < s.len() - 1 {
+    }
 
-        if s[i].contains("void") {
+    #[test]
+    fn int_min_literal() {
 
-            let mut found = false;
-            let mut x:usize = 0;
+        use literal::Literal;
 
-            while !found {
-                
-                if (i + x) < s.len() - 1 { 
-                    
-                    // if the void block closing bracket is on his own line or at the end of a line
-                    if s[i + x] == "}" || s[i + x].trim().chars().last() == Some('}') {
-
-                        i += x;
-                        found = true;
-                    } 
-                } else {
-                    // that mean that the last line is the bracket
-                    i += x;
-                    break;
-                }
+        let s = "#version 430 core\nconst int MIN = -2147483648;";
 
-                x += 1;
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
 
-            }
+        assert_eq!(info.variable("MIN").unwrap().var_type(),&VariableType::INT(Some(i32::MIN)));
+        assert_eq!(eval("1 + -2147483648").unwrap(),ConstValue::SCALAR(Literal::INT(i32::MIN + 1)));
+        // only the minus of the literal itself makes it fit
+        assert!(eval("2147483648").is_err());
+        assert!(eval("1 - 2147483648").is_err());
+        assert!(eval("-(2147483648)").is_err());
+        assert!(eval("-2147483649").is_err());
 
-        } else {
+    }
 
-            filter_s.push(s[i].to_string())
+    #[test]
+    fn constant_errors_in_the_source() {
 
-        }
+        let s = "#version 450 core\nconst int A = 2 / B;";
 
-        i += 1;
-        
-    }
-    //
-    //
+        assert_eq!(
+            ShaderFileInfo::new().parse_line(s.as_bytes()).err(),
+            Some(EParser::CONSTANT_EXPRESSION(s.find('B').unwrap(),"'B' is not a constant".to_string()))
+        );
 
+        let s = "#version 450 core\nfloat weights[ 2 * C];";
 
-    Ok(ShaderFileInfo::new())
-    //
-}
-//
-//
-/// search for a storage qualifier in a line of the shader file
-/// 
-/// # parameters
-/// 
-/// * line - a line of the shader file
-/// 
-fn get_storage_qualifier(line:&str) -> Vec<StorageQualifier>{
-    //
-    // will store all the qualifier found
-    let mut vstorage:Vec<StorageQualifier> = Vec::new();
-    //
-    // check if is a layout storage declaration
-    if line.contains("layout") {
-        //
-        // then parse the info of the layout storage declaration
-        match parse_layout_storage(line) {
+        assert_eq!(
+            ShaderFileInfo::new().parse_line(s.as_bytes()).err(),
+            Some(EParser::CONSTANT_EXPRESSION(s.find('C').unwrap(),"'C' is not a constant".to_string()))
+        );
 
-            Some(s) => vstorage.push(s),
-            None => {}
-    
-        }
-        //
     }
-    // 
-    // we check also for other because layout storage declarations can have more than one
-    // qualifier
-    //
-    // check if its have a uniform,input or output declaration
-    if line.contains(" uniform ") || line.contains("uniform ") {
-        
-        vstorage.push(StorageQualifier::UNIFORM);
-    
-     
-    } else if line.contains(" in ") || ( line.find("in ") == Some(0) && line.contains("in ") ) {
-        
-        vstorage.push(StorageQualifier::IN);
-    
-    } else if line.contains(" out ") || ( line.find("out ") == Some(0) && line.contains("out ") ) {
-    
-        vstorage.push(StorageQualifier::OUT)
 
-    } 
+    #[test]
+    fn layout_constant_values() {
+
+        let s = "#version 450 core\n\
+            const int L = 2;\n\
+            layout(location = L) in vec3 a;\n\
+            layout (location = L * 2 + 1, component = 0) in vec2 b;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert_eq!(info.variable("a").unwrap().location(),Some(2));
+        assert_eq!(info.variable("b").unwrap().location(),Some(5));
+
+    }
+
+    #[test]
+    fn layout_is_a_whole_word() {
+
+        let info = shader(ShaderType::FRAGMENT,"#version 450 core\n\
+            uniform float layoutScale;\n\
+            uniform layoutInfo { vec4 x[int(2)]; };\n\
+            layout(location = int(1)) out vec4 color;");
+
+        assert!(info.variable("layoutScale").unwrap().location().is_none());
+        assert_eq!(info.variable("color").unwrap().location(),Some(1));
+
+        assert!(matches!(
+            get_storage_qualifier("layout (location = 2 in vec2 a;"),
+            Err(EParser::PARSING_LINE(_,_))
+        ));
+
+    }
+
+    #[test]
+    fn expression_parser() {
+
+        use expression::{ExpressionKind as E,BinaryOperator,UnaryOperator};
+
+        // assignments are right associative and have the lowest precedence after the comma
+        match parse_expression("a = b += c * 2",0).unwrap().kind {
+
+            E::ASSIGNMENT(None,target,value) => {
+
+                assert_eq!(target.kind,E::IDENTIFIER("a".to_string()));
+                assert!(matches!(
+                    value.kind,
+                    E::ASSIGNMENT(Some(BinaryOperator::ADD),_,ref v) if matches!(v.kind,E::BINARY(BinaryOperator::MUL,_,_))
+                ));
+
+            },
+            e => panic!("{:?}",e)
+
+        }
+
+        match parse_expression("i++, --j",0).unwrap().kind {
+
+            E::SEQUENCE(list) => {
+
+                assert!(matches!(list[0].kind,E::UNARY(UnaryOperator::POST_INCREMENT,_)));
+                assert!(matches!(list[1].kind,E::UNARY(UnaryOperator::PRE_DECREMENT,_)));
+
+            },
+            e => panic!("{:?}",e)
+
+        }
+
+        // the arguments of a call are not a sequence
+        assert!(matches!(
+            parse_expression("f(a, b).xy",0).unwrap().kind,
+            E::FIELD(ref call,ref f) if f == "xy" && matches!(&call.kind,E::CALL(_,args) if args.len() == 2)
+        ));
+        assert!(matches!(
+            parse_expression("1 + 2 * 3 == 7 && !b",0).unwrap().kind,
+            E::BINARY(BinaryOperator::AND,..)
+        ));
+        assert!(matches!(
+            parse_expression("c ? x : y = 1",0).unwrap().kind,
+            E::TERNARY(..)
+        ));
+
+        let method = parse_expression("  values.length()",10).unwrap();
+
+        assert!(matches!(method.kind,E::METHOD(_,ref m,_) if m == "length"));
+        assert_eq!(method.span,lexer::Span::new(12,27));
+
+        assert_eq!(eval("float[3](1.0,2.0,3.0).length()").unwrap(),eval("3").unwrap());
+        assert!(eval("a = 1").is_err());
+        assert!(parse_expression("a +",0).is_err());
+        assert!(parse_expression("(a, b",0).is_err());
+
+    }
+
+    #[test]
+    fn constant_matrices() {
+
+        use literal::Literal;
+
+        let identity = eval("mat3(1.0)").unwrap();
+
+        assert_eq!(identity.shape(),(3,3));
+        assert_eq!(identity.components()[4],Literal::FLOAT(1.0));
+        assert_eq!(identity.components()[1],Literal::FLOAT(0.0));
+
+        // the vector is a column vector on the right
+        assert_eq!(
+            eval("mat2(1.0,2.0,3.0,4.0) * vec2(1.0,1.0)").unwrap(),
+            ConstValue::VECTOR(vec![Literal::FLOAT(4.0),Literal::FLOAT(6.0)])
+        );
+        assert_eq!(
+            eval("mat2(1.0,2.0,3.0,4.0)[1]").unwrap(),
+            ConstValue::VECTOR(vec![Literal::FLOAT(3.0),Literal::FLOAT(4.0)])
+        );
+        assert_eq!(
+            eval("determinant(mat2(1.0,2.0,3.0,4.0))").unwrap(),
+            ConstValue::SCALAR(Literal::FLOAT(-2.0))
+        );
+
+    }
+
+    #[test]
+    fn matrix_initializers() {
+
+        assert_eq!(
+            get_var_type("const mat2 m = mat2(1.0,2.0,3.0,4.0);").unwrap(),
+            VariableType::MAT2(Some([[1.0,2.0],[3.0,4.0]]))
+        );
+        // a scalar fill the diagonal
+        assert_eq!(
+            get_var_type("const mat3 m = mat3(2.0);").unwrap(),
+            VariableType::MAT3(Some([[2.0,0.0,0.0],[0.0,2.0,0.0],[0.0,0.0,2.0]]))
+        );
+        // column by column with vectors
+        assert_eq!(
+            get_var_type("const mat3 kernel = mat3(vec3(1.0,2.0,1.0),vec3(0.0),vec3(-1.0,-2.0,-1.0));").unwrap(),
+            VariableType::MAT3(Some([[1.0,2.0,1.0],[0.0,0.0,0.0],[-1.0,-2.0,-1.0]]))
+        );
+        // from a smaller matrix the rest comes from the identity
+        assert_eq!(
+            get_var_type("const mat3 m = mat3(mat2(5.0));").unwrap(),
+            VariableType::MAT3(Some([[5.0,0.0,0.0],[0.0,5.0,0.0],[0.0,0.0,1.0]]))
+        );
+        // from a non-square matrix
+        assert_eq!(
+            get_var_type("const mat2 m = mat2(mat2x3(1.0,2.0,3.0,4.0,5.0,6.0));").unwrap(),
+            VariableType::MAT2(Some([[1.0,2.0],[4.0,5.0]]))
+        );
+        assert_eq!(
+            get_var_type("const dmat2 m = dmat2(1);").unwrap(),
+            VariableType::DMAT2(Some([[1.0,0.0],[0.0,1.0]]))
+        );
+
+        assert!(get_var_type("const mat2 m = mat3(1.0);").is_err());
+        assert!(get_var_type("const mat2 m = mat2(1.0,2.0,3.0);").is_err());
+
+    }
+
+    #[test]
+    fn non_square_matrix_types() {
+
+        let s = "#version 430 core\n\
+            const int MAX_BONES = 4;\n\
+            uniform mat4x3 bones[MAX_BONES];\n\
+            const mat2x3 m = mat2x3(1.0,2.0,3.0,4.0,5.0,6.0);\n\
+            uniform dmat3x3 d;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let bones = info.variable("bones").unwrap();
+
+        assert_eq!(bones.var_type(),&VariableType::MAT4X3(None));
+        assert_eq!(bones.var_type().dimensions(),Some((4,3)));
+        assert_eq!(bones.array_size(),Some(&ArraySize::SIZED(4)));
+
+        assert_eq!(
+            info.variable("m").unwrap().var_type(),
+            &VariableType::MAT2X3(Some([[1.0,2.0,3.0],[4.0,5.0,6.0]]))
+        );
+        // 'dmat3x3' is another name of 'dmat3'
+        assert_eq!(info.variable("d").unwrap().var_type(),&VariableType::DMAT3(None));
+
+    }
+
+    #[test]
+    fn std140_and_std430_layouts() {
+
+        use layout::{type_layout,MemoryLayout,TypeLayout};
+
+        // the columns of a mat4x3 are vec3 aligned like a vec4
+        assert_eq!(
+            type_layout(&VariableType::MAT4X3(None),Some(&ArraySize::SIZED(2)),MemoryLayout::STD140),
+            Some(TypeLayout { alignment: 16, size: Some(128), array_stride: Some(64), matrix_stride: Some(16) })
+        );
+        assert_eq!(
+            type_layout(&VariableType::MAT3X2(None),None,MemoryLayout::STD140),
+            Some(TypeLayout { alignment: 16, size: Some(48), array_stride: None, matrix_stride: Some(16) })
+        );
+        assert_eq!(
+            type_layout(&VariableType::MAT3X2(None),None,MemoryLayout::STD430),
+            Some(TypeLayout { alignment: 8, size: Some(24), array_stride: None, matrix_stride: Some(8) })
+        );
+        assert_eq!(
+            type_layout(&VariableType::FLOAT(None),Some(&ArraySize::SIZED(4)),MemoryLayout::STD140).unwrap().size,
+            Some(64)
+        );
+        assert_eq!(
+            type_layout(&VariableType::FLOAT(None),Some(&ArraySize::SIZED(4)),MemoryLayout::STD430).unwrap().size,
+            Some(16)
+        );
+        assert_eq!(type_layout(&VariableType::SAMPLER2D(None),None,MemoryLayout::STD140),None);
+
+    }
+
+    #[test]
+    fn constant_declarations() {
+
+        let s = "#version 430 core\n\
+            const float PI = 3.25;\n\
+            const float TWO_PI = 2.0 * PI;\n\
+            const int N = 4;\n\
+            const vec3 GREY = vec3(0.5);\n\
+            uniform float weights[N * 2];\n\
+            uniform float unsized[];";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert_eq!(
+            info.variable("TWO_PI").unwrap().var_type(),
+            &VariableType::FLOAT(Some(6.5))
+        );
+        assert_eq!(info.variable("GREY").unwrap().var_type(),&VariableType::VEC3(Some([0.5;3])));
+        assert_eq!(info.variable("weights").unwrap().array_size(),Some(&ArraySize::SIZED(8)));
+        assert_eq!(info.variable("unsized").unwrap().array_size(),Some(&ArraySize::UNSIZED));
+
+        let bad = "#version 430 core\nuniform float f;\nconst float g = f * 2.0;";
+
+        assert!(ShaderFileInfo::new().parse_line(bad.as_bytes()).is_err());
+
+    }
+
+    #[test]
+    fn doc_comment_annotations() {
+
+        let s = "#version 430 core\n\
+            /// Surface roughness @range(0,1) @label(\"Roughness\") @widget(slider) @hidden\n\
+            uniform float roughness;\n\
+            uniform float metallic;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let annotations = info.variable("roughness").unwrap().annotations();
+
+        assert_eq!(annotations.get("range"),Some(&AnnotationValue::RANGE(0.0,1.0)));
+        assert_eq!(
+            annotations.get("label"),
+            Some(&AnnotationValue::STRING("Roughness".to_string()))
+        );
+        assert_eq!(
+            annotations.get("widget"),
+            Some(&AnnotationValue::STRING("slider".to_string()))
+        );
+        assert_eq!(annotations.get("hidden"),Some(&AnnotationValue::FLAG));
+        // the doc comment only belongs to the next declaration
+        assert!(info.variable("metallic").unwrap().annotations().is_empty());
+
+    }
+
+    #[test]
+    fn pragma_annotations() {
+
+        let s = "#version 430 core\n\
+            #pragma annotate roughness range=0..1 label=\"Surface roughness\" step=0.05 steps=20\n\
+            uniform float roughness;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let annotations = info.variable("roughness").unwrap().annotations();
+
+        assert_eq!(annotations.get("range"),Some(&AnnotationValue::RANGE(0.0,1.0)));
+        assert_eq!(
+            annotations.get("label"),
+            Some(&AnnotationValue::STRING("Surface roughness".to_string()))
+        );
+        assert_eq!(annotations.get("step"),Some(&AnnotationValue::FLOAT(0.05)));
+        assert_eq!(annotations.get("steps"),Some(&AnnotationValue::INT(20)));
+
+    }
+
+    #[test]
+    fn pragma_annotations_unknown_variable() {
+
+        let s = "#version 430 core\n#pragma annotate nothing hidden\nuniform float a;";
+
+        assert!(ShaderFileInfo::new().parse_line(s.as_bytes()).is_err());
+
+    }
+
+    #[test]
+    fn doc_comment_before_other_line() {
+
+        let s = "#version 430 core\n\
+            /// @hidden\n\
+            #extension GL_ARB_shading_language_420pack : enable\n\
+            uniform float a;\n\
+            /// @hidden\n\
+            layout(local_size_x = 8) in;\n\
+            uniform float b;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert!(info.variable("a").unwrap().annotations().is_empty());
+        assert!(info.variable("b").unwrap().annotations().is_empty());
+
+    }
+
+    #[test]
+    fn function_declarations() {
+
+        let s = "#version 430 core\n\
+            uniform vec3 light;\n\
+            vec3 shade(in vec3 n, out float d, inout vec4 c, const float k[2]);\n\
+            float helper(void) { return 1.0; }\n\
+            void main()\n\
+            {\n\
+                if (true) { float d; vec4 c; shade(light, d, c, float[2](1.0, 2.0)); }\n\
+            }\n\
+            vec3 shade(in vec3 n, out float d, inout vec4 c, const float k[2])\n\
+            {\n\
+                d = dot(n, light);\n\
+                return n * d;\n\
+            }\n\
+            uniform float after;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert_eq!(info.functions().count(),4);
+        assert!(info.variable("after").is_some());
+
+        let prototype = info.functions().next().unwrap();
+
+        assert!(prototype.is_prototype());
+        assert_eq!(prototype.signature(),"vec3 shade(vec3,float,vec4,float)");
+
+        let params = &prototype.parameters;
+
+        assert_eq!(params[0].qualifier,ParameterQualifier::IN);
+        assert_eq!(params[1].qualifier,ParameterQualifier::OUT);
+        assert_eq!(params[2].qualifier,ParameterQualifier::INOUT);
+        assert!(params[3].constant);
+        assert_eq!(params[3].array_size,Some(ArraySize::SIZED(2)));
+
+        assert!(info.function("shade").unwrap().is_definition());
+        assert!(info.function("helper").unwrap().parameters.is_empty());
+        assert_eq!(info.function("main").unwrap().return_type,"void");
+
+        let body = info.function("shade").unwrap().body.as_ref().unwrap();
+
+        assert!(s[body.span.start..body.span.end].starts_with('{'));
+        assert!(s[body.span.start..body.span.end].ends_with('}'));
+
+    }
+
+    #[test]
+    fn function_statements() {
+
+        use statement::StatementKind as S;
+
+        let s = "#version 430 core\n\
+            void main() {\n\
+                const int n = 4, m[2] = int[2](1, 2);\n\
+                float[2] pair;\n\
+                for (int i = 0; i < n; i++) { if (i == 2) continue; else if (i > 2) break; }\n\
+                while (false) ;\n\
+                do { pair[0] += 1.0; } while (pair[0] < 2.0);\n\
+                switch (n) { case 1: case 2: break; default: return; }\n\
+                barrier();\n\
+                if (pair[1] > 0.0) discard;\n\
+            }";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let body = &info.function("main").unwrap().body.as_ref().unwrap().statements;
+
+        assert_eq!(body.len(),8);
+
+        match &body[0].kind {
+
+            S::DECLARATION(d) => {
+
+                assert_eq!(d.qualifiers,vec!["const".to_string()]);
+                assert_eq!(d.declarators.len(),2);
+                assert!(d.declarators[1].array.is_some() && d.declarators[1].initializer.is_some());
+
+            },
+            k => panic!("{:?}",k)
+
+        }
+
+        assert!(matches!(&body[1].kind,S::DECLARATION(d) if d.type_array.is_some()));
+        assert!(matches!(&body[2].kind,S::FOR(Some(_),Some(_),Some(_),b) if matches!(b.kind,S::BLOCK(_))));
+        assert!(matches!(&body[3].kind,S::WHILE(_,b) if b.kind == S::EMPTY));
+        assert!(matches!(&body[4].kind,S::DO_WHILE(..)));
+        assert!(matches!(&body[5].kind,S::SWITCH(_,labels) if labels.len() == 5));
+        assert_eq!(body[6].kind,S::BARRIER);
+        assert!(matches!(&body[7].kind,S::IF(_,t,None) if t.kind == S::DISCARD));
+        assert_eq!(&s[body[6].span.start..body[6].span.end],"barrier();");
+
+        let missing = "#version 430 core\nvoid main() { float a = 1.0 }";
+
+        assert!(ShaderFileInfo::new().parse_line(missing.as_bytes()).is_err());
+
+    }
+
+    #[test]
+    fn semantic_checks_accept_valid_shaders() {
+
+        for file in ["data_test/correct_shader.vert","data_test/correct_shader.frag"] {
+
+            let info = load_file(get_relative_path(file).as_str()).unwrap();
+
+            assert_eq!(info.check_semantics(),vec![]);
+
+        }
+
+        let s = "#version 430 core\n\
+            uniform mat4 model;\n\
+            out vec3 normal;\n\
+            struct Light { vec3 color; float intensity; };\n\
+            float scale(float x) { return x * 2.0; }\n\
+            double scale(double x) { return x; }\n\
+            void shade(in vec3 n, out float d) { d = dot(n, n); }\n\
+            void main() {\n\
+                const int n = 3;\n\
+                float values[n];\n\
+                vec4 p = model * vec4(1.0, 2, 3u, 4.0);\n\
+                normal = p.xyz * scale(1);\n\
+                normal.zx = vec2(values[2], values.length());\n\
+                float d;\n\
+                shade(normal, d);\n\
+                uint u = 1;\n\
+                Light light = Light(vec3(1.0), 2.0);\n\
+                for (int i = 0; i < n; ++i) { if (d > 0.5) break; }\n\
+            }";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert_eq!(info.check_semantics(),vec![]);
+
+    }
+
+    #[test]
+    fn semantic_checks_report_errors() {
+
+        let s = "#version 330 core\n\
+            uniform float time;\n\
+            const float PI = 3.25;\n\
+            struct S { float x; };\n\
+            vec2 f(vec2 a) { return a; }\n\
+            vec2 f(vec3 a) { return a.xy; }\n\
+            void main() {\n\
+                uint u = 1;\n\
+                vec3 v = vec3(1.0, 2.0);\n\
+                time = 2.0;\n\
+                PI += 1.0;\n\
+                v.xx = vec2(0.0);\n\
+                float x = v.xw;\n\
+                f(vec4(1.0));\n\
+                if (1) { }\n\
+                bool b = true + 1;\n\
+                continue;\n\
+                float a[2];\n\
+                a[2] = 1.0;\n\
+                S s = S(1.0, 2.0);\n\
+                return 1.0;\n\
+                y = 1.0;\n\
+            }";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let errors = info.check_semantics();
+
+        let position = |text:&str| s.find(text).unwrap();
+        let at = |pos:usize| errors.iter().any(|e| matches!(e,EParser::SEMANTIC(_,p) if *p == pos));
+
+        // int => uint only exist since 4.00
+        assert!(at(position("1;")));
+        assert!(at(position("vec3(1.0, 2.0)")));
+        assert!(at(position("time = 2.0")));
+        assert!(at(position("PI += 1.0")));
+        assert!(at(position("v.xx")));
+        assert!(at(position("v.xw")));
+        assert!(at(position("f(vec4(1.0))")));
+        assert!(at(position("1) { }")));
+        assert!(at(position("true + 1")));
+        assert!(at(position("continue;")));
+        assert!(at(position("a[2] = 1.0")));
+        assert!(at(position("S(1.0, 2.0)")));
+        assert!(at(position("1.0;\ny")));
+        assert!(at(position("y = 1.0")));
+        assert_eq!(errors.len(),14);
+
+    }
+
+    #[test]
+    fn semantic_checks_follow_the_profile() {
+
+        let errors = |s:&str| {
+
+            let info = shader(ShaderType::FRAGMENT,s);
+
+            info.check_semantics()
+
+        };
+
+        let body = "void main() { float x = 1; }";
+
+        // glsl es and glsl 1.10 have no implicit conversion
+        assert_eq!(errors(&format!("#version 300 es\n{}",body)).len(),1);
+        assert_eq!(errors(&format!("#version 110\n{}",body)).len(),1);
+        assert!(errors(&format!("#version 120\n{}",body)).is_empty());
+
+        let s = "#version 430 core\nfloat f() { }\nvoid main() { f(); }";
+
+        assert_eq!(errors(s),vec![EParser::SEMANTIC(
+            "the function 'f' must return a float but has no return statement".to_string(),
+            s.find("float f").unwrap()
+        )]);
+
+    }
+
+    #[test]
+    fn loaded_shader_keeps_its_functions() {
+
+        let info = load_file(get_relative_path("data_test/correct_shader.vert").as_str()).unwrap();
+
+        assert_eq!(info.shader_type(),Some(ShaderType::VERTEX));
+        assert_eq!(info.variables().count(),8);
+        assert!(info.function("main").unwrap().is_definition());
+
+        let model = info.variable("model").unwrap();
+
+        assert_eq!(&info.source()[model.span().start..model.span().end],"uniform mat4 model;");
+
+    }
+
+    #[test]
+    fn declarations_on_several_lines() {
+
+        let s = "#version 430 core\n\
+            layout (location = 0)\n\
+                in vec3 position; /* a comment */ uniform float\n\
+                scale = 2.0;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert_eq!(info.variable("position").unwrap().var_type(),&VariableType::VEC3(None));
+        assert_eq!(info.variable("scale").unwrap().var_type(),&VariableType::FLOAT(Some(2.0)));
+
+        let unclosed = "#version 430 core\nvoid main() {";
+
+        assert!(ShaderFileInfo::new().parse_line(unclosed.as_bytes()).is_err());
+
+    }
+
+    #[test]
+    fn several_declarators() {
+
+        let s = "#version 430 core\n\
+            uniform float a, b[2];\n\
+            const float A = 1.0, B = A * 2.0;\n\
+            const int N = 4;\n\
+            float sum(float v[N]) { return v[0] + v[3]; }";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        assert_eq!(info.variable("a").unwrap().var_type(),&VariableType::FLOAT(None));
+        assert_eq!(info.variable("b").unwrap().array_size(),Some(&ArraySize::SIZED(2)));
+        assert!(info.variable("b").unwrap().has_qualifier(&StorageQualifier::UNIFORM));
+        assert_eq!(info.variable("B").unwrap().var_type(),&VariableType::FLOAT(Some(2.0)));
+        assert_eq!(info.function("sum").unwrap().parameters[0].array_size,Some(ArraySize::SIZED(4)));
+
+    }
+
+    #[test]
+    fn builtins_depend_on_stage_and_version() {
+
+        use builtin::*;
+
+        let fragment = ShaderTarget::new(Some(ShaderType::FRAGMENT),330,VersionBranch::CORE);
+        let vertex = ShaderTarget::new(Some(ShaderType::VERTEX),330,VersionBranch::CORE);
+
+        let textures:Vec<String> = available_functions("texture",&fragment).iter().map(|f| f.signature()).collect();
+
+        assert!(textures.contains(&"vec4 texture(sampler2D,vec2)".to_string()));
+        assert!(textures.contains(&"ivec4 texture(isampler2D,vec2)".to_string()));
+        assert!(textures.contains(&"uvec4 texture(usampler2D,vec2)".to_string()));
+
+        assert!(!available_functions("dFdx",&fragment).is_empty());
+        assert!(available_functions("dFdx",&vertex).is_empty());
+
+        let compatibility = ShaderTarget::new(Some(ShaderType::FRAGMENT),330,VersionBranch::COMPATIBILITY);
+
+        assert!(available_variable("gl_FragColor",&fragment).is_none());
+        assert!(available_variable("gl_FragColor",&compatibility).is_some());
+
+        let compute = ShaderTarget::new(Some(ShaderType::COMPUTE),430,VersionBranch::CORE);
+
+        assert!(available_variable("gl_GlobalInvocationID",&compute).is_some());
+        assert!(available_variable("gl_GlobalInvocationID",&fragment).is_none());
+        //
+        // es 1.00 has the derivatives only with GL_OES_standard_derivatives
+        let es100 = ShaderTarget::new(Some(ShaderType::FRAGMENT),100,VersionBranch::ES);
+        let es300 = ShaderTarget::new(Some(ShaderType::FRAGMENT),300,VersionBranch::ES);
+
+        assert!(available_functions("fwidth",&es100).is_empty());
+        assert!(!available_functions("fwidth",&es100.with_extensions(vec!["GL_OES_standard_derivatives".to_string()])).is_empty());
+        assert!(!available_functions("fwidth",&es300).is_empty());
+        //
+        // the built-ins of vulkan glsl don't exist in opengl glsl
+        let vulkan = ShaderTarget::new(Some(ShaderType::VERTEX),450,VersionBranch::CORE)
+            .with_extensions(vec!["GL_KHR_vulkan_glsl".to_string()]);
+
+        assert!(available_variable("gl_VertexIndex",&vertex).is_none());
+        assert!(available_variable("gl_VertexIndex",&vulkan).is_some());
+
+        let fetches:Vec<String> = available_functions("texelFetch",&fragment).iter().map(|f| f.signature()).collect();
+
+        assert!(fetches.contains(&"ivec4 texelFetch(isampler2DMS,ivec2,int)".to_string()));
+        assert_eq!(
+            builtin_functions("subpassLoad").iter().map(|f| f.signature()).collect::<Vec<String>>(),
+            [
+                "vec4 subpassLoad(subpassInput)","ivec4 subpassLoad(isubpassInput)",
+                "uvec4 subpassLoad(usubpassInput)","vec4 subpassLoad(subpassInputMS,int)",
+                "ivec4 subpassLoad(isubpassInputMS,int)","uvec4 subpassLoad(usubpassInputMS,int)"
+            ]
+        );
+
+    }
+
+    #[test]
+    fn version_checks() {
+
+        let s = "#version 330 core\n\
+            layout(binding = 0) uniform sampler2D tex;\n\
+            layout(std430, binding = 1) buffer Particles { vec4 positions[]; };\n\
+            attribute vec3 normal;\n\
+            uniform float sample;\n\
+            out vec4 color;\n\
+            void main() {\n\
+                color = texture(tex,vec2(sample)) + vec4(dFdx(1.0));\n\
+                double d = 1.0;\n\
+            }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        let issues = info.check_version();
+        let features:Vec<&str> = issues.iter().map(|i| i.feature.as_str()).collect();
+
+        assert_eq!(features,vec!["layout(binding)","layout(std430)","buffer","attribute","double"]);
+        assert_eq!(issues[0].minimum_version,Some(420));
+        assert_eq!(issues[0].to_string(),
+            "'layout(binding)' needs glsl 420 or later, or the extension GL_ARB_shading_language_420pack");
+        assert_eq!(issues[3].to_string(),"'attribute' was removed from the core profile in glsl 140");
+        assert_eq!(&s[issues[2].span.start..issues[2].span.end],"buffer");
+
+        let es = "#version 300 es\n\
+            layout(location = 0) out vec4 color;\n\
+            void main() { color = vec4(gl_FragCoord.x) * imageSize(img).x; }";
+
+        let info = shader(ShaderType::FRAGMENT,es);
+
+        let issues = info.check_version();
+
+        assert_eq!(issues.len(),1);
+        assert_eq!(issues[0].to_string(),"'imageSize' needs glsl 310 es or later");
+
+    }
+
+    #[test]
+    fn version_100_is_es() {
+
+        let s = "#version 100\n\
+            uniform sampler2D tex;\n\
+            void main() { gl_FragColor = texture2D(tex,vec2(0.5)) + texture(tex,vec2(0.5)); }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        assert_eq!(info.target().branch,VersionBranch::ES);
+
+        let issues = info.check_version();
+
+        assert_eq!(issues.len(),1);
+        assert_eq!(issues[0].to_string(),"'texture' needs glsl 300 es or later");
+
+        // the inputs and outputs of glsl es 1.00 are attributes and varyings
+        let vertex = "#version 100\n\
+            attribute vec4 position;\n\
+            varying vec2 uv;\n\
+            void main() { uv = position.xy; gl_Position = position; }";
+
+        let fragment = "#version 100\n\
+            uniform sampler2D tex;\n\
+            varying vec2 uv;\n\
+            void main() { gl_FragColor = texture2D(tex,uv); }";
+
+        let vertex = shader(ShaderType::VERTEX,vertex);
+        let fragment = shader(ShaderType::FRAGMENT,fragment);
+
+        assert_eq!(vertex.check_semantics(),vec![]);
+        assert_eq!(fragment.check_semantics(),vec![]);
+        assert!(vertex.variable("position").unwrap().has_qualifier(&StorageQualifier::IN));
+        assert!(vertex.variable("uv").unwrap().has_qualifier(&StorageQualifier::OUT));
+        assert!(fragment.variable("uv").unwrap().has_qualifier(&StorageQualifier::IN));
+
+    }
+
+    #[test]
+    fn extension_preprocessor() {
+
+        assert_eq!(
+            parse_preprocessor("#extension GL_ARB_separate_shader_objects : enable").unwrap(),
+            PreprocessorDeclarationType::EXTENSION("GL_ARB_separate_shader_objects".to_string(),ExtensionBehavior::ENABLE)
+        );
+        assert_eq!(
+            parse_preprocessor("# extension all:warn").unwrap(),
+            PreprocessorDeclarationType::EXTENSION("all".to_string(),ExtensionBehavior::WARN)
+        );
+        assert!(parse_preprocessor("#extension all : enable").is_err());
+        assert!(parse_preprocessor("#extension GL_EXT_foo : sometimes").is_err());
+
+    }
+
+    #[test]
+    fn extensions_change_what_is_accepted() {
+
+        let s = "#version 330 core\n\
+            #extension GL_ARB_shading_language_420pack : require\n\
+            #extension GL_EXT_nonuniform_qualifier : enable\n\
+            #extension GL_ARB_bindless_texture : enable\n\
+            #extension GL_ARB_bindless_texture : disable\n\
+            #extension GL_GOOGLE_include_directive : enable\n\
+            #include \"lights.glsl\"\n\
+            layout(binding = 0) uniform sampler2D textures;\n\
+            layout(bindless_sampler) uniform sampler2D handle;\n\
+            void main() { int i = nonuniformEXT(1); }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        assert_eq!(info.extensions(),vec![
+            "GL_ARB_shading_language_420pack",
+            "GL_EXT_nonuniform_qualifier",
+            "GL_GOOGLE_include_directive"
+        ]);
+        assert!(info.declarations().contains(
+            &DeclarationLine::PREPROCESSOR(PreprocessorDeclarationType::INCLUDE("lights.glsl".to_string()))
+        ));
+        assert!(info.check_semantics().is_empty());
+
+        let issues:Vec<String> = info.check_version().iter().map(|i| i.to_string()).collect();
+
+        assert_eq!(issues,vec!["'layout(bindless_sampler)' needs the extension GL_ARB_bindless_texture"]);
+
+        let include = "#version 450 core\n#include <common.glsl>\nvoid main() { }";
+
+        assert!(ShaderFileInfo::new().parse_line(include.as_bytes()).is_err());
+
+    }
+
+    #[test]
+    fn pragmas() {
+
+        use pragma::StandardPragma;
+
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug,PartialEq)]
+        enum Blend { ALPHA, ADD }
+
+        let s = "#version 450 core\n\
+            #pragma optimize(off)\n\
+            #pragma STDGL invariant(all)\n\
+            #pragma blend alpha\n\
+            #pragma cull none\n\
+            #pragma blend add\n\
+            void main() { }";
+
+        let mut info = ShaderFileInfo::new();
+        info.register_pragma("blend",|pragma| match pragma.arguments.first().and_then(|t| t.identifier()) {
+
+            Some("alpha") => Ok(Blend::ALPHA),
+            Some("add") => Ok(Blend::ADD),
+            _ => Err("expected alpha or add".to_string())
+
+        });
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let standard:Vec<Option<StandardPragma>> = info.pragmas().map(|p| p.standard()).collect();
+
+        assert_eq!(standard,vec![Some(StandardPragma::OPTIMIZE(false)),Some(StandardPragma::INVARIANT_ALL),None,None,None]);
+
+        let cull = info.pragmas().find(|p| p.namespace == "cull").unwrap();
+
+        assert_eq!(cull.arguments[0].identifier(),Some("none"));
+        assert_eq!(&s[cull.arguments[0].span.start..cull.arguments[0].span.end],"none");
+        assert_eq!(&s[cull.span.start..cull.span.end],"#pragma cull none");
+        assert_eq!(info.pragma_values::<Blend>("blend").collect::<Vec<&Blend>>(),vec![&Blend::ALPHA,&Blend::ADD]);
+        assert_eq!(info.pragma_value::<Blend>("blend"),Some(&Blend::ADD));
+        // the parsers and their values can be shared between threads with the shader
+        fn shareable<T:Send + Sync>(_:&T) {}
+        shareable(&info);
+        // the arguments glsl can't cut in tokens are kept as text
+        let message_shader = "#version 450 core\n#pragma message \"hello\"\nvoid main() { }";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(message_shader.as_bytes()).unwrap();
+
+        let message = info.pragmas().next().unwrap();
+
+        assert_eq!((message.namespace.as_str(),message.text.as_str()),("message","\"hello\""));
+        assert!(message.arguments.is_empty());
+
+        let mut info = ShaderFileInfo::new();
+        info.register_pragma("blend",|_| Err::<Blend,String>("expected alpha or add".to_string()));
+
+        assert!(info.parse_line(s.as_bytes()).is_err());
+
+    }
+
+    #[test]
+    fn interface_blocks_and_interpolation() {
+
+        let s = "#version 450 core\n\
+            layout (std140, binding = 2) uniform Lights {\n\
+                vec4 colors[4];\n\
+                float intensity;\n\
+            } lights;\n\
+            out VertexData { flat int id; vec2 uv; };\n\
+            layout (location = 1) noperspective centroid out vec3 normal;";
+
+        let mut info = ShaderFileInfo::new();
+        info.parse_line(s.as_bytes()).unwrap();
+
+        let lights = info.block("Lights").unwrap();
+
+        assert_eq!(lights.instance_name(),Some("lights"));
+        assert_eq!(lights.layout_value(LayoutVarType::BINDING),Some(2));
+        assert!(lights.has_qualifier(&StorageQualifier::UNIFORM));
+        assert_eq!(lights.members()[0].array_size(),Some(&ArraySize::SIZED(4)));
+        assert_eq!(lights.members()[1].var_type(),&VariableType::FLOAT(None));
+
+        let data = info.block("VertexData").unwrap();
+
+        assert_eq!(data.instance_name(),None);
+        assert_eq!(data.members()[0].interpolation(),Some(InterpolationQualifier::FLAT));
+
+        let normal = info.variable("normal").unwrap();
+
+        assert_eq!(normal.interpolation(),Some(InterpolationQualifier::NOPERSPECTIVE));
+        assert!(normal.has_qualifier(&StorageQualifier::CENTROID));
+        assert_eq!(normal.location(),Some(1));
+
+    }
+
+    #[test]
+    fn program_interfaces() {
+
+        use program::*;
+
+        let loaded = load_program(&[
+            get_relative_path("data_test/correct_shader.frag").as_str(),
+            get_relative_path("data_test/correct_shader.vert").as_str()
+        ]).unwrap();
+
+        assert_eq!(loaded.stages().next().unwrap().shader_type(),Some(ShaderType::VERTEX));
+        assert!(loaded.check_interfaces().is_empty());
+
+        let vertex = "#version 450 core\n\
+            out vec3 color;\n\
+            out float depth;\n\
+            layout (location = 3) out vec2 uv;\n\
+            out vec2 unused;\n\
+            out Data { vec3 n; } v;\n\
+            void main() { }";
+
+        let fragment = "#version 450 core\n\
+            in vec4 color;\n\
+            flat in float depth;\n\
+            layout (location = 3) in vec2 texcoord;\n\
+            in vec3 missing;\n\
+            in Data { vec2 n; } d;\n\
+            void main() { }";
+
+        let mut program = program(&[(ShaderType::VERTEX,vertex),(ShaderType::FRAGMENT,fragment)]);
+
+        assert!(program.add_stage(ShaderFileInfo::with_shader_type(ShaderType::VERTEX)).is_err());
+
+        let mismatches = program.check_interfaces();
+        let kinds:Vec<(&str,&MismatchKind)> = mismatches.iter().map(|m| (m.name.as_str(),&m.kind)).collect();
+
+        assert_eq!(kinds,vec![
+            ("color",&MismatchKind::TYPE("vec3".to_string(),"vec4".to_string())),
+            ("depth",&MismatchKind::INTERPOLATION(None,Some(InterpolationQualifier::FLAT))),
+            ("missing",&MismatchKind::UNMATCHED_INPUT),
+            ("unused",&MismatchKind::UNUSED_OUTPUT),
+            ("Data",&MismatchKind::BLOCK("the member 'n' has not the same type".to_string()))
+        ]);
+        assert!(!mismatches[3].is_error());
+        assert_eq!(mismatches[0].to_string(),
+            "'color' is a vec3 output but a vec4 input (vertex shader 2:1) (fragment shader 2:1)");
+
+    }
+
+    #[test]
+    fn merged_resources() {
+
+        use resource::*;
+
+        let vertex = "#version 450 core\n\
+            uniform mat4 model;\n\
+            uniform float time;\n\
+            layout (std140, binding = 0) uniform Camera { mat4 view; mat4 projection; };\n\
+            void main() { gl_Position = model * vec4(1.0); }";
+
+        let fragment = "#version 450 core\n\
+            uniform mat4 model;\n\
+            uniform vec2 time;\n\
+            layout (binding = 1) uniform sampler2D albedo;\n\
+            layout (std140, binding = 3) uniform Camera { mat4 view; mat4 projection; };\n\
+            layout (std430, binding = 0) buffer Lights { vec4 colors[]; };\n\
+            out vec4 color;\n\
+            void main() { color = model[0]; }";
+
+        let program = program(&[(ShaderType::VERTEX,vertex),(ShaderType::FRAGMENT,fragment)]);
+
+        let resources = program.resources();
+        let names:Vec<(&str,ResourceKind)> = resources.iter().map(|r| (r.name.as_str(),r.kind)).collect();
+
+        assert_eq!(names,vec![
+            ("model",ResourceKind::UNIFORM),
+            ("time",ResourceKind::UNIFORM),
+            ("Camera",ResourceKind::UNIFORM_BLOCK),
+            ("albedo",ResourceKind::OPAQUE),
+            ("Lights",ResourceKind::STORAGE_BLOCK)
+        ]);
+        assert_eq!(resources[0].stages,vec![ShaderType::VERTEX,ShaderType::FRAGMENT]);
+        // declared by both stages but used by none
+        assert!(resources[1].stages.is_empty());
+        assert_eq!(resources[1].declarations.len(),2);
+        assert_eq!(resources[3].binding,Some(1));
+        assert_eq!(resources[4].memory_layout,Some(layout::MemoryLayout::STD430));
+
+        let conflicts:Vec<String> = program.check_resources().iter().map(|c| c.to_string()).collect();
+
+        assert_eq!(conflicts,vec![
+            "'time' is not the same in the vertex shader (3:1) and the fragment shader (3:1): a float and a vec2",
+            "'Camera' is not the same in the vertex shader (4:1) and the fragment shader (5:1): the binding is 0 and 3"
+        ]);
+
+    }
+
+    #[test]
+    fn semantic_checks_use_the_builtins() {
+
+        let s = "#version 330 core\n\
+            void main() {\n\
+                vec4 p = gl_FragCoord;\n\
+                gl_Position = vec4(length(p.xyz));\n\
+                float n = noise_of(p);\n\
+            }";
+
+        let info = shader(ShaderType::VERTEX,s);
+
+        let errors:Vec<String> = info.check_semantics().iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(errors.len(),2);
+        assert!(errors[0].contains("the built-in 'gl_FragCoord' is not available in a vertex shader"));
+        assert!(errors[1].contains("the function 'noise_of' is not declared"));
+
+    }
+
+    #[test]
+    fn static_use_from_main() {
+
+        let s = "#version 450 core\n\
+            uniform mat4 model;\n\
+            uniform float scale;\n\
+            uniform float unused;\n\
+            uniform vec3 shadowed;\n\
+            layout (std140) uniform Light { vec3 color; float power; } light;\n\
+            uniform Fog { float density; float far; };\n\
+            in vec3 position;\n\
+            in vec3 normal;\n\
+            float scaled(float v) { return v * scale; }\n\
+            float never_called() { return unused; }\n\
+            vec3 shade() { vec3 shadowed = light.color; return shadowed * scaled(density); }\n\
+            void main() {\n\
+                gl_Position = model * vec4(position + shade(),1.0);\n\
+            }";
+
+        let info = shader(ShaderType::VERTEX,s);
+
+        let used:Vec<&str> = info.used_variables().map(|v| v.name()).collect();
+        let unused:Vec<&str> = info.unused_variables().map(|v| v.name()).collect();
+
+        assert_eq!(used,vec!["model","scale","position"]);
+        assert_eq!(unused,vec!["unused","shadowed","normal"]);
+        assert_eq!(info.reachable_functions(),vec!["main","scaled","shade"]);
+
+        let light = info.block("Light").unwrap();
+        let fog = info.block("Fog").unwrap();
+
+        assert!(light.is_used() && fog.is_used());
+        assert_eq!(light.members().iter().map(|m| m.is_used()).collect::<Vec<_>>(),vec![true,false]);
+        assert_eq!(fog.members().iter().map(|m| m.is_used()).collect::<Vec<_>>(),vec![true,false]);
+
+    }
+
+    #[test]
+    fn location_assignment() {
+
+        use locations::location_count;
+
+        assert_eq!(location_count(&VariableType::DVEC4(None),None,false),Some(2));
+        assert_eq!(location_count(&VariableType::DVEC4(None),None,true),Some(1));
+        assert_eq!(location_count(&VariableType::MAT4(None),Some(&ArraySize::SIZED(2)),false),Some(8));
+        assert_eq!(location_count(&VariableType::DMAT3(None),None,false),Some(6));
+        assert_eq!(location_count(&VariableType::MAT4(None),Some(&ArraySize::SIZED(1 << 30)),false),None);
+
+        // the last locations of a u32 don't overflow and the others still find room
+        let info = shader(ShaderType::VERTEX,"#version 450 core\n\
+            layout (location = 4294967295u) in mat4 m;\n\
+            in vec2 a;\n\
+            in float huge[4294967200u];\n\
+            void main() { }");
+
+        let assignments = info.assign_locations();
+        let given:Vec<(&str,u32)> = assignments.iter().map(|a| (a.name.as_str(),a.location)).collect();
+
+        assert_eq!(given,vec![("a",0),("huge",1)]);
+
+        let program = program::load_program(&[
+            get_relative_path("data_test/correct_shader.vert").as_str(),
+            get_relative_path("data_test/correct_shader.frag").as_str()
+        ]).unwrap();
+
+        let assignments = program.assign_locations();
+        let given:Vec<(&str,bool,u32)> = assignments.iter().map(|a| (a.name.as_str(),a.output,a.location)).collect();
+
+        assert_eq!(given,vec![
+            ("color",true,0),("TexCoord",true,1),
+            ("color",false,0),("TexCoord",false,1),("FragColor",true,0)
+        ]);
+
+        let s = "#version 450 core\n\
+            layout (location = 1, component = 0) in vec2 packed;\n\
+            in dvec4 big;\n\
+            in vec3 small;\n\
+            flat in int id[2];\n\
+            layout (location = 0) out vec4 color;\n\
+            void main() { }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        let assignments = info.assign_locations();
+        let given:Vec<(&str,u32,u32)> = assignments.iter().map(|a| (a.name.as_str(),a.location,a.count)).collect();
+
+        assert_eq!(given,vec![("big",2,2),("small",0,1),("id",4,2)]);
+        assert!(info.write_locations(&assignments).contains(
+            "layout(location = 2) in dvec4 big;\n\
+            layout(location = 0) in vec3 small;\n\
+            layout(location = 4) flat in int id[2];"
+        ));
+
+    }
+
+    #[test]
+    fn layout_conflicts() {
+
+        use limits::Limits;
+
+        let s = "#version 450 core\n\
+            layout (location = 1) in vec3 position;\n\
+            layout (location = 1) in vec3 normal;\n\
+            layout (location = 2, component = 0) in vec2 uv;\n\
+            layout (location = 2, component = 2) in vec2 uv2;\n\
+            layout (location = 14) in mat4 bones;\n\
+            layout (binding = 0) uniform sampler2D albedo;\n\
+            layout (binding = 0) uniform sampler2D normals;\n\
+            layout (binding = 0) uniform Camera { mat4 view; };\n\
+            void main() { }";
+
+        let info = shader(ShaderType::VERTEX,s);
+
+        let issues:Vec<String> = info.check_layouts(&Limits::default()).iter().map(|i| i.to_string()).collect();
+
+        assert_eq!(issues,vec![
+            "'normal' takes the location 1 that 'position' already takes (vertex shader 3:1)",
+            "'bones' takes the location 17 but there are 16 locations (vertex shader 6:1)",
+            "'normals' takes the binding 0 of the set 0 that 'albedo' already takes (vertex shader 8:1)"
+        ]);
+
+        let s = "#version 450 core\n\
+            layout (set = 0, binding = 1) uniform sampler2D albedo;\n\
+            layout (set = 0, binding = 1) uniform Material { vec4 tint; };\n\
+            layout (set = 6, binding = 0) uniform sampler2D shadow;\n\
+            void main() { }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        let kinds:Vec<conflicts::LayoutIssueKind> = info.check_layouts(&Limits::default()).into_iter().map(|i| i.kind).collect();
+
+        assert_eq!(kinds,vec![
+            conflicts::LayoutIssueKind::SET_OUT_OF_RANGE(6,4),
+            conflicts::LayoutIssueKind::BINDING_OVERLAP("albedo".to_string(),0,1)
+        ]);
+
+        // the samplers, the images and the atomic counters of opengl have their own bindings
+        let s = "#version 450 core\n\
+            layout (binding = 0) uniform sampler2D albedo;\n\
+            layout (binding = 0, rgba8) uniform writeonly image2D target;\n\
+            layout (binding = 0) uniform atomic_uint counter;\n\
+            layout (binding = 8, rgba8) uniform writeonly image2D overflow;\n\
+            void main() { }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        let issues:Vec<String> = info.check_layouts(&Limits::default()).iter().map(|i| i.to_string()).collect();
+
+        assert_eq!(issues,vec!["'overflow' takes the binding 8 but there are 8 bindings (fragment shader 5:1)"]);
+
+        // an array of vulkan glsl takes a single binding
+        let s = "#version 450 core\n\
+            layout (set = 0, binding = 0) uniform sampler2D albedo[4];\n\
+            layout (set = 0, binding = 1) uniform sampler2D shadow;\n\
+            void main() { }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        assert!(info.check_layouts(&Limits::default()).is_empty());
+
+        // the outputs of a dual-source blending share their location
+        let s = "#version 450 core\n\
+            layout (location = 0) out vec4 color;\n\
+            layout (location = 0, index = 1) out vec4 factor;\n\
+            void main() { }";
+
+        assert!(shader(ShaderType::FRAGMENT,s).check_layouts(&Limits::default()).is_empty());
+
+        // the last values of a u32 are out of the limits without overflowing
+        let s = "#version 450 core\n\
+            layout (binding = 4294967295u) uniform sampler2D albedo[2];\n\
+            layout (location = 4294967295u) in mat4 bones;\n\
+            void main() { }";
+
+        let kinds:Vec<conflicts::LayoutIssueKind> = shader(ShaderType::VERTEX,s)
+            .check_layouts(&Limits::default())
+            .into_iter()
+            .map(|i| i.kind)
+            .collect();
+
+        assert_eq!(kinds,vec![
+            conflicts::LayoutIssueKind::LOCATION_OUT_OF_RANGE(u32::MAX,16),
+            conflicts::LayoutIssueKind::BINDING_OUT_OF_RANGE(u32::MAX,80)
+        ]);
+
+    }
+
+    #[test]
+    fn binding_remap() {
+
+        use remap::*;
+        use resource::ResourceKind;
+
+        let s = "#version 450 core\n\
+            uniform sampler2D albedo;\n\
+            layout (std140, binding = 3) uniform Camera { mat4 view; };\n\
+            layout (binding = 1) uniform sampler2D shadows[2];\n\
+            layout (std430) buffer Lights { vec4 colors[]; };\n\
+            uniform float time;\n\
+            uniform sampler2D normals;\n\
+            void main() { }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        let remaps = info.remap_bindings(&BindingPolicy::PACK(vec![
+            ResourceKind::UNIFORM_BLOCK,ResourceKind::OPAQUE,ResourceKind::STORAGE_BLOCK
+        ]));
+        let given:Vec<(&str,u32,u32)> = remaps.iter().map(|r| (r.name.as_str(),r.set,r.binding)).collect();
+
+        // the array takes a single binding
+        assert_eq!(given,vec![("albedo",1,0),("Camera",0,0),("shadows",1,1),("Lights",2,0),("normals",1,2)]);
+        assert_eq!(info.write_bindings(&remaps),"#version 450 core\n\
+            layout(set = 1, binding = 0) uniform sampler2D albedo;\n\
+            layout (std140, binding = 0, set = 0) uniform Camera { mat4 view; };\n\
+            layout (binding = 1, set = 1) uniform sampler2D shadows[2];\n\
+            layout (std430, set = 2, binding = 0) buffer Lights { vec4 colors[]; };\n\
+            uniform float time;\n\
+            layout(set = 1, binding = 2) uniform sampler2D normals;\n\
+            void main() { }");
+
+        let table = std::collections::HashMap::from([("Camera".to_string(),(2,5))]);
+        let remaps = info.remap_bindings(&BindingPolicy::TABLE(table));
+
+        assert_eq!(remaps.len(),1);
+        assert!(info.write_bindings(&remaps).contains("layout (std140, binding = 5, set = 2) uniform Camera"));
+
+    }
+
+    #[test]
+    fn descriptor_set_layouts() {
+
+        use descriptors::*;
+
+        let vertex = "#version 450 core\n\
+            layout (set = 0, binding = 0) uniform Camera { mat4 view; mat4 projection; };\n\
+            layout (push_constant) uniform Push { mat4 model; vec3 tint; float time; } push;\n\
+            void main() { gl_Position = projection * view * push.model * vec4(1.0); }";
+
+        let fragment = "#version 450 core\n\
+            layout (set = 0, binding = 0) uniform Camera { mat4 view; mat4 projection; };\n\
+            layout (set = 1, binding = 1) uniform sampler2D albedo[4];\n\
+            layout (set = 1, binding = 0, rgba8) uniform writeonly image2D target;\n\
+            layout (set = 1, binding = 2) uniform utextureBuffer indices;\n\
+            layout (input_attachment_index = 0, set = 2, binding = 0) uniform subpassInput depth;\n\
+            layout (set = 2, binding = 1, std430) buffer Lights { vec4 colors[]; };\n\
+            layout (push_constant) uniform Push { mat4 model; vec3 tint; float time; } push;\n\
+            out vec4 color;\n\
+            void main() {\n\
+                color = view[0] + texture(albedo[1],vec2(0.5)) + subpassLoad(depth) + colors[0] * push.time;\n\
+                imageStore(target,ivec2(0),vec4(texelFetch(indices,0)));\n\
+            }";
+
+        let program = program(&[(ShaderType::VERTEX,vertex),(ShaderType::FRAGMENT,fragment)]);
+
+        type Bindings = Vec<(u32,DescriptorKind,Option<u32>,usize)>;
+
+        let sets:Vec<(u32,Bindings)> = program.descriptor_set_layouts().into_iter().map(|s| (
+            s.set,
+            s.bindings.iter().map(|b| (b.binding,b.kind,b.count,b.stages.len())).collect()
+        )).collect();
+
+        assert_eq!(sets,vec![
+            (0,vec![(0,DescriptorKind::UNIFORM_BUFFER,Some(1),2)]),
+            (1,vec![
+                (0,DescriptorKind::STORAGE_IMAGE,Some(1),1),
+                (1,DescriptorKind::COMBINED_IMAGE_SAMPLER,Some(4),1),
+                (2,DescriptorKind::UNIFORM_TEXEL_BUFFER,Some(1),1)
+            ]),
+            (2,vec![(0,DescriptorKind::INPUT_ATTACHMENT,Some(1),1),(1,DescriptorKind::STORAGE_BUFFER,Some(1),1)])
+        ]);
+
+        assert_eq!(program.push_constant_ranges(),vec![PushConstantRange {
+            name: "Push".to_string(),
+            stages: vec![ShaderType::VERTEX,ShaderType::FRAGMENT],
+            offset: 0,
+            size: 80
+        }]);
+
+    }
+
+    #[test]
+    fn vertex_input_layout() {
+
+        use vertex::*;
+
+        let info = load_file(get_relative_path("data_test/correct_shader.vert").as_str()).unwrap();
+        let layout = info.vertex_layout().unwrap();
+
+        let attributes:Vec<(&str,u32,u32,usize,usize)> = layout.attributes.iter()
+            .map(|a| (a.name.as_str(),a.location,a.components,a.size,a.offset))
+            .collect();
+
+        assert_eq!(attributes,vec![("aPos",0,3,12,0),("aColor",1,3,12,12),("aTexCoord",2,2,8,24)]);
+        assert_eq!(layout.stride,32);
+
+        let format = [
+            VertexBufferAttribute { location: 0, components: 3, scalar: ScalarType::FLOAT },
+            VertexBufferAttribute { location: 2, components: 3, scalar: ScalarType::FLOAT },
+            VertexBufferAttribute { location: 5, components: 4, scalar: ScalarType::UINT }
+        ];
+
+        let mismatches:Vec<String> = layout.check_format(&format).iter().map(|m| m.to_string()).collect();
+
+        assert_eq!(mismatches,vec![
+            "the buffer has no attribute at the location 1 for 'aColor'",
+            "'aTexCoord' has 2 components but the buffer gives 3 at the location 2",
+            "the shader doesn't read the attribute at the location 5"
+        ]);
+
+        let s = "#version 450 core\n\
+            in mat4 instance;\n\
+            layout (location = 0) in dvec3 position;\n\
+            in ivec2 ids;\n\
+            void main() { gl_Position = instance * vec4(position,1.0); }";
+
+        let info = shader(ShaderType::VERTEX,s);
+
+        let layout = info.vertex_layout().unwrap();
+        let attributes:Vec<(&str,u32,u32,usize,bool)> = layout.attributes.iter()
+            .map(|a| (a.name.as_str(),a.location,a.location_count,a.offset,a.used))
+            .collect();
+
+        assert_eq!(attributes,vec![("position",0,1,0,true),("instance",1,4,24,true),("ids",5,1,88,false)]);
+        assert_eq!(layout.stride,96);
+
+        // the locations after the last of a u32 are not checked
+        let info = shader(ShaderType::VERTEX,"#version 450 core\n\
+            layout (location = 4294967295u) in mat4 m;\n\
+            void main() { gl_Position = m * vec4(1.0); }");
+
+        let mismatches = info.vertex_layout().unwrap().check_format(&[]);
+        let locations:Vec<u32> = mismatches.iter().map(|m| m.location).collect();
+
+        assert_eq!(locations,vec![u32::MAX]);
+
+    }
+
+    #[test]
+    fn fragment_outputs() {
+
+        let info = load_file(get_relative_path("data_test/correct_shader.frag").as_str()).unwrap();
+        let outputs = info.fragment_outputs().unwrap();
+
+        assert_eq!(outputs.targets,vec![fragment::RenderTarget {
+            name: "FragColor".to_string(),
+            location: 0,
+            index: 0,
+            components: 4,
+            scalar: ScalarType::FLOAT
+        }]);
+        assert!(!outputs.dual_source_blending && !outputs.writes_depth && !outputs.discards);
+
+        let s = "#version 450 core\n\
+            layout (location = 0, index = 0) out vec4 color;\n\
+            layout (location = 0, index = 1) out vec4 weights;\n\
+            layout (location = 1) out uvec2 ids[2];\n\
+            in float alpha;\n\
+            void clip() { if (alpha < 0.5) { discard; } }\n\
+            void main() {\n\
+                clip();\n\
+                color = vec4(alpha);\n\
+                weights.a = 1.0;\n\
+                gl_FragDepth = alpha;\n\
+            }";
+
+        let info = shader(ShaderType::FRAGMENT,s);
+
+        let outputs = info.fragment_outputs().unwrap();
+        let targets:Vec<(&str,u32,u32,u32)> = outputs.targets.iter().map(|t| (t.name.as_str(),t.location,t.index,t.components)).collect();
+
+        assert_eq!(targets,vec![("color",0,0,4),("weights",0,1,4),("ids[0]",1,0,2),("ids[1]",2,0,2)]);
+        assert_eq!(outputs.attachment_count(),3);
+        assert!(outputs.dual_source_blending && outputs.writes_depth && outputs.discards);
+        assert!(info.writes_variable("weights") && !info.writes_variable("alpha"));
+        assert!(info.vertex_layout().is_none());
+
+    }
+
+    #[test]
+    fn push_constant_block() {
+
+        use push_constants::*;
+        use limits::Limits;
+
+        let vertex = "#version 450 core\n\
+            layout (push_constant) uniform Push { mat4 mvp; layout (offset = 80) vec3 tint; } pc;\n\
+            void main() { gl_Position = pc.mvp * vec4(1.0); }";
+
+        let fragment = "#version 450 core\n\
+            layout (push_constant) uniform Push { layout (offset = 64) vec4 color; vec4 tint; } pc;\n\
+            layout (location = 0) out vec4 FragColor;\n\
+            void main() { FragColor = pc.color; }";
+
+        let program = program(&[(ShaderType::VERTEX,vertex),(ShaderType::FRAGMENT,fragment)]);
+
+        let block = program.stage(ShaderType::FRAGMENT).unwrap().push_constants().unwrap();
+        let members:Vec<(&str,usize,usize)> = block.members.iter().map(|m| (m.name.as_str(),m.offset,m.size)).collect();
+
+        assert_eq!(members,vec![("color",64,16),("tint",80,16)]);
+        assert_eq!((block.instance_name.as_deref(),block.size),(Some("pc"),96));
+        assert_eq!(program.stage(ShaderType::VERTEX).unwrap().push_constants().unwrap().size,92);
+
+        let ranges:Vec<(usize,usize)> = program.push_constant_ranges().iter().map(|r| (r.offset,r.size)).collect();
+        assert_eq!(ranges,vec![(0,92)]);
+
+        let mut limits = Limits::default();
+
+        let issues:Vec<String> = program.check_push_constants(&limits).iter().map(|i| i.to_string()).collect();
+        assert_eq!(issues,vec!["'tint' doesn't match 'tint' of the fragment shader (vertex shader)".to_string()]);
+
+        limits.max_push_constants_size = 64;
+
+        let kinds:Vec<PushConstantIssueKind> = program.check_push_constants(&limits).into_iter().map(|i| i.kind).collect();
+
+        assert_eq!(kinds,vec![
+            PushConstantIssueKind::TOO_LARGE(92,64),
+            PushConstantIssueKind::TOO_LARGE(96,64),
+            PushConstantIssueKind::MEMBER_MISMATCH(ShaderType::FRAGMENT,"tint".to_string())
+        ]);
+
+        let info = shader(ShaderType::COMPUTE,"#version 450 core\n\
+            layout (push_constant) uniform A { float a; };\n\
+            layout (push_constant) uniform B { float b; };\n\
+            void main() { }");
+
+        assert_eq!(info.check_push_constants(&limits)[0].kind,PushConstantIssueKind::SEVERAL_BLOCKS("A".to_string()));
+
+        // the range starts at the lowest offset even when it is not the one of the first member
+        let program = self::program(&[(ShaderType::VERTEX,"#version 450 core\n\
+            layout (push_constant) uniform Push { layout (offset = 32) vec4 a; layout (offset = 16) vec4 b; } pc;\n\
+            void main() { gl_Position = pc.a + pc.b; }")]);
+
+        let block = program.stage(ShaderType::VERTEX).unwrap().push_constants().unwrap();
+        let ranges:Vec<(usize,usize)> = program.push_constant_ranges().iter().map(|r| (r.offset,r.size)).collect();
+
+        assert_eq!(ranges,vec![(16,block.size - 16)]);
+
+    }
+
+    #[test]
+    fn specialization_constants() {
+
+        use specialization::*;
+        use literal::Literal;
+
+        let s = "#version 450 core\n\
+            layout (constant_id = 3) const int SAMPLES = 4;\n\
+            layout (constant_id = 1) const float SCALE = 0.5;\n\
+            const int TAPS = SAMPLES * 2;\n\
+            layout (local_size_x = 8, local_size_y_id = 3, local_size_z_id = 5) in;\n\
+            layout (std430, binding = 0) buffer Weights { float weights[TAPS]; };\n\
+            shared float cache[SAMPLES];\n\
+            void main() { cache[0] = weights[0] * SCALE; }";
+
+        let info = shader(ShaderType::COMPUTE,s);
+
+        let constants = info.specialization_constants();
+        let summary:Vec<(u32,Option<&str>,Literal)> = constants.iter().map(|c| (c.id,c.name.as_deref(),c.default)).collect();
+
+        assert_eq!(summary,vec![
+            (1,Some("SCALE"),Literal::FLOAT(0.5)),
+            (3,Some("SAMPLES"),Literal::INT(4)),
+            (5,None,Literal::UINT(1))
+        ]);
+        assert_eq!(constants[1].uses,vec![
+            SpecializationUse::CONSTANT("TAPS".to_string()),
+            SpecializationUse::LOCAL_SIZE(1),
+            SpecializationUse::ARRAY_SIZE("weights".to_string()),
+            SpecializationUse::ARRAY_SIZE("cache".to_string())
+        ]);
+        assert!(constants[0].uses.is_empty());
+        assert_eq!(info.local_size(),Some([8,1,1]));
+
+        let values = HashMap::from([(3,Literal::UINT(16)),(1,Literal::INT(2)),(5,Literal::INT(4))]);
+        let source = info.write_specialization(&values);
+
+        assert!(source.contains("const int SAMPLES = 16;") && source.contains("const float SCALE = 2.0;"));
+        assert!(source.contains("layout (local_size_x = 8, local_size_y_id = 3, local_size_z_id = 5, local_size_y = 16, local_size_z = 4) in;"));
+
+        let specialized = info.specialize(&values).unwrap();
+
+        assert_eq!(specialized.variable("cache").unwrap().array_size(),Some(&ArraySize::SIZED(16)));
+        assert_eq!(specialized.block("Weights").unwrap().members()[0].array_size(),Some(&ArraySize::SIZED(32)));
+        assert_eq!(specialized.local_size(),Some([8,16,4]));
+        assert_eq!(info.variable("cache").unwrap().array_size(),Some(&ArraySize::SIZED(4)));
+        assert!(info.variable("cache").unwrap().has_qualifier(&StorageQualifier::SHARED));
+
+    }
+
+    #[test]
+    fn limit_checks() {
+
+        use limits::*;
+
+        let fragment = "#version 300 es\n\
+            precision mediump float;\n\
+            uniform vec4 palette[240];\n\
+            uniform vec4 unused[240];\n\
+            uniform sampler2D layers[12];\n\
+            uniform Material { mat4 transforms[300]; };\n\
+            in vec4 color;\n\
+            out vec4 FragColor;\n\
+            void main() { FragColor = color * palette[0] * texture(layers[0],vec2(0.0)) * transforms[0][0]; }";
+
+        let info = shader(ShaderType::FRAGMENT,fragment);
+
+        assert!(info.check_limits(&Limits::gl45()).iter().all(|i| matches!(i.kind,LimitKind::UNIFORM_BLOCK_SIZE(_))));
+
+        let issues:Vec<String> = info.check_limits(&Limits::gles30()).iter().map(|i| i.to_string()).collect();
+
+        assert_eq!(issues,vec![
+            "the fragment shader uses 960 uniform components but there are 896".to_string(),
+            "the fragment shader uses 19200 bytes in the uniform block 'Material' but there are 16384".to_string()
+        ]);
+
+        let targets = "#version 300 es\n\
+            precision mediump float;\n\
+            out vec4 o[5];\n\
+            void main() { o[0] = vec4(0.0); }";
+
+        let info = shader(ShaderType::FRAGMENT,targets);
+
+        assert!(info.check_limits(&Limits::gl45()).is_empty());
+        assert_eq!(
+            info.check_limits(&Limits::gles30()).iter().map(|i| i.to_string()).collect::<Vec<String>>(),
+            vec!["the fragment shader uses 5 draw buffers but there are 4".to_string()]
+        );
+
+        // the buffers go up to the last location, a dual-source pair takes a single one
+        let targets = "#version 300 es\n\
+            precision mediump float;\n\
+            layout (location = 6) out vec4 c;\n\
+            void main() { c = vec4(0.0); }";
+
+        let issues = shader(ShaderType::FRAGMENT,targets).check_limits(&Limits::gles30());
+
+        assert_eq!(issues.iter().map(|i| (i.kind.clone(),i.used)).collect::<Vec<_>>(),vec![(LimitKind::DRAW_BUFFERS,7)]);
+
+        let targets = "#version 450 core\n\
+            layout (location = 0, index = 0) out vec4 color;\n\
+            layout (location = 0, index = 1) out vec4 factor;\n\
+            void main() { color = vec4(0.0); factor = vec4(1.0); }";
+
+        let mut limits = Limits::gl45();
+        limits.max_draw_buffers = 1;
+
+        assert!(shader(ShaderType::FRAGMENT,targets).check_limits(&limits).is_empty());
+
+        let vertex = "#version 300 es\n\
+            in vec4 position;\n\
+            uniform sampler2D heights[8];\n\
+            out vec4 color;\n\
+            void main() { color = texture(heights[0],position.xy); gl_Position = position; }";
+
+        let program = program(&[(ShaderType::VERTEX,vertex),(ShaderType::FRAGMENT,fragment)]);
+
+        let mut limits = Limits::gles30();
+        limits.max_combined_texture_image_units = 16;
+
+        let last = program.check_limits(&limits).pop().unwrap();
+
+        assert_eq!((last.kind,last.stage,last.used,last.max),(LimitKind::COMBINED_TEXTURE_IMAGE_UNITS,None,20,16));
+
+        let compute = "#version 450 core\n\
+            layout (local_size_x = 32, local_size_y = 32, local_size_z = 128) in;\n\
+            shared vec4 tile[4096];\n\
+            void main() { tile[0] = vec4(0.0); }";
+
+        let info = shader(ShaderType::COMPUTE,compute);
+
+        let kinds:Vec<(LimitKind,u64)> = info.check_limits(&Limits::default()).into_iter().map(|i| (i.kind,i.used)).collect();
+
+        assert_eq!(kinds,vec![
+            (LimitKind::COMPUTE_SHARED_MEMORY_SIZE,65536),
+            (LimitKind::COMPUTE_WORK_GROUP_INVOCATIONS,131072),
+            (LimitKind::COMPUTE_WORK_GROUP_SIZE(2),128)
+        ]);
+        assert_eq!(info.check_limits(&Limits::gl33()).len(),5);
+
+    }
+
+    
+
+
+}
+//
+//
+// ------------------------------------------------------------------------------------------
+// Error handling for the library
+//
+#[allow(non_camel_case_types,non_snake_case)]
+#[derive(Error,Debug,PartialEq)]
+pub enum EParser {
+    
+    #[error("Unable to loading file {0}. Reason: {1}")]
+    LOADING(String,String),
+    #[error("Unable to convert OsString to String")]
+    OS_STRING_CONVERSION,
+    #[error("The extension '{0}' is not supported")]
+    UNSUPPORTED_EXT(String),
+    #[error("The glsl language expect to have #version before anything else but found '{0}'")]
+    OMITTED_FIRST_LINE(String),
+    #[error("Unable to get index location of &str '{0}'")]
+    INDEX_PATERN(String),
+    #[error("Unable to parse string to {0}. Reason: {1}")]
+    STRING_PARSING(String,String),
+    #[error("Cant parse line {0} because of '{1}'")]
+    PARSING_LINE(String,String),
+    #[error("the line '{0}' has been marked for having a '=' but it doesn't")]
+    MARKED_VALUE_DECL(String),
+    #[error("Cant get the value of vector in line '{0}'. Reason: {1}")]
+    VEC_VALUE(String,String),
+    #[error("Cant find type of variable in line '{0}'")]
+    VARIABLE_TYPE(String),
+    #[error("Cant convert Vec<u8> to String because of {0}")]
+    U8_STRING_CONVERSION(String),
+    #[error("Cant parse annotation '{0}'. Reason: {1}")]
+    ANNOTATION(String,String),
+    #[error("Cant parse literal '{0}'. Reason: {1}")]
+    LITERAL(String,String),
+    #[error("The literal '{0}' is too big for the type {1}")]
+    LITERAL_OVERFLOW(String,String),
+    #[error("Cant initialize a {1} with '{0}' that is a {2}")]
+    LITERAL_CONVERSION(String,String,String),
+    #[error("Cant read token '{0}'. Reason: {1}")]
+    TOKEN(String,String),
+    #[error("Syntax error at byte {1}: {0}")]
+    SYNTAX(String,usize),
+    #[error("Cant evaluate the constant expression at byte {0}. Reason: {1}")]
+    CONSTANT_EXPRESSION(usize,String),
+    #[error("The size of the array '{0}' must be a positive constant integer")]
+    ARRAY_SIZE(String),
+    #[error("Cant use the extension '{0}'. Reason: {1}")]
+    EXTENSION(String,String),
+    #[error("Cant parse the pragma '{0}'. Reason: {1}")]
+    PRAGMA(String,String),
+    #[error("Cant build the program. Reason: {0}")]
+    PROGRAM(String),
+    #[error("Semantic error at byte {1}: {0}")]
+    SEMANTIC(String,usize),
+
+}
+//
+// ------------------------------------------------------------------------------------------   
+// Constant 
+// 
+/// Array of possible type that glsl accepts
+const TYPE_IN_STR: [&str;45] = [
+    //
+    // scalar
+    "bool",
+    "int",
+    "uint",
+    "float",
+    "double",
+    // unsigned integer vector
+    "uvec2",
+    "uvec3",
+    "uvec4",
+    // signed integer vector
+    "ivec2",
+    "ivec3",
+    "ivec4",
+    // boolean vector
+    "bvec2",
+    "bvec3",
+    "bvec4",
+    // float vector
+    "vec2",
+    "vec3",
+    "vec4",
+    // double precision(f64) float vector
+    "dvec2",
+    "dvec3",
+    "dvec4",
+    // float matrices
+    "mat2",
+    "mat3",
+    "mat4",
+    // double precision (f64) float matrices
+    "dmat2",
+    "dmat3",
+    "dmat4",
+    // float matrices with the number of columns and rows
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    // double precision (f64) float matrices with the number of columns and rows
+    "dmat2x2",
+    "dmat2x3",
+    "dmat2x4",
+    "dmat3x2",
+    "dmat3x3",
+    "dmat3x4",
+    "dmat4x2",
+    "dmat4x3",
+    "dmat4x4",
+    // texture 
+    "sampler2D"
+    //
+    //
+];
+//
+//
+/// The dimensions of the samplers, textures and images ('sampler2DArray', 'image3D')
+const OPAQUE_DIMENSIONS: [&str;11] = [
+    "1D", "2D", "3D", "Cube", "2DRect", "1DArray", "2DArray", "CubeArray", "Buffer", "2DMS", "2DMSArray",
+];
+//
+//
+/// The opaque type of a name, None if it is not one. The samplers of the shadows end
+/// with 'Shadow' and the types of integers start with 'i' or 'u'
+fn opaque_type(name:&str) -> Option<VariableType> {
+
+    match name {
+
+        "sampler2D" => return Some(VariableType::SAMPLER2D(None)),
+        "isampler2D" => return Some(VariableType::ISAMPLER2D(None)),
+        "usampler2D" => return Some(VariableType::USAMPLER2D(None)),
+        "sampler" | "samplerShadow" | "atomic_uint" => return Some(VariableType::OPAQUE(name.to_string())),
+        _ => {}
+
+    }
+
+    let base = opaque_family(name);
+
+    if matches!(base,"subpassInput" | "subpassInputMS") {
+        return Some(VariableType::OPAQUE(name.to_string()));
+    }
+
+    let dimension = ["sampler","texture","image"].iter().find_map(|family| base.strip_prefix(family))?;
+    let dimension = match base.starts_with("sampler") && base.len() == name.len() {
+        //
+        // only the float samplers have a shadow version
+        true => dimension.strip_suffix("Shadow").unwrap_or(dimension),
+        false => dimension
+
+    };
+
+    match OPAQUE_DIMENSIONS.contains(&dimension) {
+
+        true => Some(VariableType::OPAQUE(name.to_string())),
+        false => None
+
+    }
+
+}
+//
+//
+/// Create a ShaderFileInfo struct with the path passed of a shader file
+/// 
+/// # Arguments
+/// 
+/// * 'fp' - A path to a shader file as a &str
+/// 
+/// # Error causes 
+/// 
+/// * The file passed doesn't exist or is not a file
+/// * The file passed is a broken symbolic link
+/// * The file passed caused an permission denied error
+/// * The file haven't a file extension that we take care of or cannot access it
+/// * Cant convert a the file content to a string
+/// * The content of the file cant be parsed
+/// 
+#[allow(clippy::needless_late_init)]
+pub fn load_file(fp:&str) -> Result<ShaderFileInfo,EParser> {
+    //
+    //
+    let p = Path::new(fp);
+    //
+    // check if the file exists and is allow
+    match p.try_exists()  {
+
+        Ok(rep) => {
+            
+            if !rep {
+
+                if p.is_symlink()  {
+                    return Err(EParser::LOADING(fp.to_string(),"Broken symbolic link".to_string()));
+                }
+
+                return Err(EParser::LOADING(fp.to_string(),"file doesn't exist".to_string()));
+                
+
+            }
+
+
+        },
+
+        Err(e) => return Err(EParser::LOADING(fp.to_string(),e.to_string())),
+
+    }
+    //
+    //
+    let stype:ShaderType; // determines by the file extension got
+    //
+    // Check if the file passed have a supported extension and then store it in stype
+    match p.extension() {
+        //
+        Some(_ext) => {
+            //  
+            // convert to &str
+            let ext = match _ext.to_str() {
+                Some(ex) => ex,
+                None => return Err(EParser::LOADING(fp.to_string(),EParser::OS_STRING_CONVERSION.to_string())),
+
+            };
+            //
+            // compared with the allowed extensions
+            match ext {
+                
+                "frag" => stype = ShaderType::FRAGMENT,
+                "vert" => stype = ShaderType::VERTEX,
+                "tesc" => stype = ShaderType::TESSCONTROL,
+                "tese" => stype = ShaderType::TESSEVALUATION,
+                "geom" => stype = ShaderType::GEOMETRY,
+                "comp" => stype = ShaderType::COMPUTE,
+                _ => return Err(
+                    EParser::LOADING(
+                        fp.to_string(),
+                        EParser::UNSUPPORTED_EXT(ext.to_string()).to_string()
+                    )
+                ), 
+                
+            }
+            //
+        },
+        //
+        // weird rare case
+        None => return Err(EParser::LOADING(
+                fp.to_string(),
+                "unable to retrieve file extension. Possible causes:\n\t
+                - hadn't a file name\n\t
+                - don't have a dot\n\t
+                - have dot but nothing after".to_string()
+            ))
+        //
+    }
+    //
+    //
+    let content = match fs::read(p) {
+
+        Ok(val) => val,
+        Err(e) => return Err(EParser::LOADING(fp.to_string(),e.to_string()))
+
+    };
+    //
+    let mut info = ShaderFileInfo::with_shader_type(stype);
+    info.parse_line(&content)?;
+    //
+    Ok(info)
+    //
+}
+//
+//
+/// search for a storage qualifier in a line of the shader file
+/// 
+/// # parameters
+/// 
+/// * line - a line of the shader file
+/// 
+/// # Error causes
+/// 
+/// * The layout of the line is not valid
+/// 
+fn get_storage_qualifier(line:&str) -> Result<Vec<StorageQualifier>,EParser> {
+
+    get_storage_qualifier_with_constants(line,&HashMap::new())
+
+}
+//
+//
+/// Same as get_storage_qualifier but the values of the layout can use the constants passed
+/// (ex: 'layout (location = BASE + 1) in vec3 normal;')
+#[allow(clippy::single_match)]
+fn get_storage_qualifier_with_constants(line:&str,constants:&HashMap<String,ConstValue>) -> Result<Vec<StorageQualifier>,EParser> {
+    //
+    // will store all the qualifier found
+    let mut vstorage:Vec<StorageQualifier> = Vec::new();
+    //
+    // check if is a layout storage declaration
+    if find_layout(line).is_some() {
+        //
+        // then parse the info of the layout storage declaration
+        match parse_layout_storage(line,constants)? {
+
+            Some(s) => vstorage.push(s),
+            None => {}
+    
+        }
+        //
+    }
+    // 
+    // we check also for other because layout storage declarations can have more than one
+    // qualifier
+    //
+    // check if its a constant. Only before the value because 'const' could be in it. The
+    // '=' of the layout are not the one of the value
+    let mut depth:usize = 0;
+
+    let value_start = line.find(|c:char| {
+
+        match c {
+
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+
+        }
+
+        c == '=' && depth == 0
+
+    });
+
+    let declaration = &line[..value_start.unwrap_or(line.len())];
+
+    if declaration.split(|c:char| !(c.is_alphanumeric() || c == '_')).any(|w| w == "const") {
+
+        vstorage.push(StorageQualifier::CONST);
+
+    }
+    //
+    // check if its have a uniform,input or output declaration
+    if line.contains(" uniform ") || line.contains("uniform ") {
+        
+        vstorage.push(StorageQualifier::UNIFORM);
+    
+     
+    } else if line.contains(" in ") || ( line.find("in ") == Some(0) && line.contains("in ") ) {
+        
+        vstorage.push(StorageQualifier::IN);
+    
+    } else if line.contains(" out ") || ( line.find("out ") == Some(0) && line.contains("out ") ) {
+    
+        vstorage.push(StorageQualifier::OUT)
+
+    } else if is_qualifier_word(declaration,"buffer") {
+
+        vstorage.push(StorageQualifier::BUFFER)
+
+    } else if is_qualifier_word(declaration,"shared") {
+
+        vstorage.push(StorageQualifier::SHARED)
+
+    }
+    //
+    // the auxiliary qualifiers of the inputs and outputs
+    for (word,qualifier) in [
+        ("patch",StorageQualifier::PATCH),
+        ("centroid",StorageQualifier::CENTROID),
+        ("sample",StorageQualifier::SAMPLE)] {
+
+        if is_qualifier_word(declaration,word) {
+            vstorage.push(qualifier);
+        }
+
+    }
+    //
+    //
+    Ok(vstorage)
+    //
+}
+//
+//
+/// Return info on the content of layout storage declarations
+/// 
+/// # parameters
+/// 
+/// * line - a line of a shader file that have been confirmed
+/// to have a layout declaration
+/// * constants - the constants the values can use
+/// 
+/// # Error causes
+/// 
+/// * The parentheses of the layout are not closed
+/// * A value is not a constant integer of 0 or more
+/// 
+#[allow(clippy::needless_late_init,clippy::doc_lazy_continuation)]
+fn parse_layout_storage(line:&str,constants:&HashMap<String,ConstValue>) -> Result<Option<StorageQualifier>,EParser> {
+    //
+    // find where the layout declaration parentheses start
+    //
+    //  layout (location = 1) in
+    //         ^ 
+    //         |
+    //         × —— what we looking for
+    //
+    let open_par_index = match find_layout(line) {
+
+        Some(i) => i,
+        None => return Ok(None)
+
+    };
+    //
+    // the parenthesis that closes the layout, the values can have parentheses
+    let mut depth:usize = 0;
+
+    let close = line[open_par_index..].char_indices().find(|(_,c)| {
+
+        match c {
+
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+
+        }
+
+        depth == 0
+
+    });
+
+    let close_par_index = match close {
+
+        Some((i,_)) => open_par_index + i + 1,
+        None => return Err(EParser::PARSING_LINE(line.to_string(),"the layout qualifier is never closed".to_string()))
+
+    };
+    // get the content of the parentheses
+    let par_content:Vec<String> = line.to_string()[open_par_index + 1 .. close_par_index - 1]
+        .split(',')
+        .map(|f| f.to_string())
+        .collect();
+    //
+    // 
+    // we need to make sure that the close parentheses is not out range of the string slice
+    let last_pos:usize;
+
+    if close_par_index >=  line.len() - 1 {
+
+        last_pos = close_par_index;
+
+    } else {
+
+        last_pos = close_par_index + 1;
+
+    }
+    //
+    //  
+    let mut layout_var = LayoutDeclaration::init(&line[..last_pos]);   
+    //
+    // check for every declaration in parentheses
+    for content in par_content.iter() {
+
+        // the layout qualifiers without value ('std140') are only kept in the raw text
+        let type_ = match content.split('=').next().map(|k| k.trim().trim_start_matches('(').trim()) {
+
+            Some("location") if content.contains('=') =>   LayoutVarType::LOCATION,
+            Some("binding") if content.contains('=') =>    LayoutVarType::BINDING,
+            Some("component") if content.contains('=') =>  LayoutVarType::COMPONENT,
+            Some("set") if content.contains('=') =>        LayoutVarType::SET,
+            Some("index") if content.contains('=') =>      LayoutVarType::INDEX,
+            Some("offset") if content.contains('=') =>     LayoutVarType::OFFSET,
+            Some("constant_id") if content.contains('=') => LayoutVarType::CONSTANT_ID,
+            Some("local_size_x") if content.contains('=') =>    LayoutVarType::LOCAL_SIZE_X,
+            Some("local_size_y") if content.contains('=') =>    LayoutVarType::LOCAL_SIZE_Y,
+            Some("local_size_z") if content.contains('=') =>    LayoutVarType::LOCAL_SIZE_Z,
+            Some("local_size_x_id") if content.contains('=') => LayoutVarType::LOCAL_SIZE_X_ID,
+            Some("local_size_y_id") if content.contains('=') => LayoutVarType::LOCAL_SIZE_Y_ID,
+            Some("local_size_z_id") if content.contains('=') => LayoutVarType::LOCAL_SIZE_Z_ID,
+            _ => continue
+
+        };
+
+        let value = content.split_once('=').map_or("",|(_,v)| v).trim();
+
+        let value_num:u32 = match layout_number(value,constants) {
+
+            Some(v) => v,
+            None => return Err(EParser::PARSING_LINE(
+                line.to_string(),
+                format!("'{}' is not a constant integer of 0 or more",value)
+            ))
+
+        };
+
+        layout_var.push((type_,value_num))
+
+
+    }
+    //    
+    //  
+    Ok(Some(StorageQualifier::LAYOUT(layout_var)))
+
+}
+//
+//
+/// Where the parenthesis of the layout qualifier of a line is, the word 'layout' alone
+/// followed by a '(' ('layoutScale' is a name)
+fn find_layout(line:&str) -> Option<usize> {
+
+    let is_ident = |c:char| c.is_alphanumeric() || c == '_';
+
+    line.match_indices("layout").find_map(|(i,_)| {
+
+        let after = line[i + "layout".len()..].trim_start();
+
+        match (line[..i].ends_with(is_ident),after.starts_with('(')) {
+
+            (false,true) => Some(line.len() - after.len()),
+            _ => None
+
+        }
+
+    })
+
+}
+//
+//
+/// The value of a layout qualifier, a constant expression that is not negative
+///     example: 'BASE + 1' => 3 if BASE is 2
+/// 
+fn layout_number(text:&str,constants:&HashMap<String,ConstValue>) -> Option<u32> {
+
+    match evaluate(&parse_expression(text,0).ok()?,constants).ok()? {
+
+        ConstValue::SCALAR(Literal::INT(i)) if i >= 0 => Some(i as u32),
+        ConstValue::SCALAR(Literal::UINT(u)) => Some(u),
+        _ => None
+
+    }
+
+}
+
+               
+fn parse_preprocessor(line:&str) -> Result<PreprocessorDeclarationType,EParser> {
+
+    // the directive can be separated from the '#' ('# extension')
+    let directive = line.trim_start().trim_start_matches('#').trim_start();
+
+    if let Some(extension) = directive.strip_prefix("extension") {
+        return parse_extension(extension);
+    }
+
+    if let Some(path) = directive.strip_prefix("include") {
+
+        let path = path.trim();
+
+        return match path.len() > 1 && (path.starts_with('"') && path.ends_with('"') || path.starts_with('<') && path.ends_with('>')) {
+
+            true => Ok(PreprocessorDeclarationType::INCLUDE(path[1..path.len() - 1].to_string())),
+            false => Err(EParser::PARSING_LINE(line.to_string(),"expected a path in quotes or in '<>'".to_string()))
+
+        };
+
+    }
+
+    if line.contains("version") {
+
+        
+
+        let ver:String = line
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect();
+        
+        let ver_num:u16 = match ver.parse::<u16>() {
+
+            Ok(num) => num,
+            Err(e) => return Err(EParser::STRING_PARSING("U16".to_string(),e.to_string()))
+
+        };
+
+
+        let branch:String = line
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .collect();
+
+        let index = match branch.find("version") {
+
+            Some(index) => index,
+            None => return Err(EParser::INDEX_PATERN(branch.to_string()))
+
+        };
+
+    
+
+        let version_branch = match &branch[index + 7 ..] {
+            
+            "core"  =>   VersionBranch::CORE,
+            "compatibility" => VersionBranch::COMPATIBILITY,
+            "es"    =>   VersionBranch::ES,
+            // glsl es 1.00 is the only version of es without the profile written
+            ""  if ver_num == 100 => VersionBranch::ES,
+            _       =>   VersionBranch::UNKNOWN
+
+        };
+
+        return Ok(PreprocessorDeclarationType::VERSION(ver_num,version_branch));
+
+        
+
+        
+
+    }
+    //
+    // the macros and the conditional compilation need a preprocessor this parser doesn't have
+    Err(EParser::PARSING_LINE(
+        line.to_string(),
+        format!("the directive '#{}' is not supported",directive.split_whitespace().next().unwrap_or(""))
+    ))
+
+}
+
+/// Return the extension and the behavior of an '#extension' line
+///
+/// # parameters
+///
+/// * line - what follows '#extension'
+///   example: ' GL_ARB_separate_shader_objects : enable'
+///
+/// # Error causes
+///
+/// * The ':' or the name is missing
+/// * The behavior is not require, enable, warn or disable
+/// * 'all' is required or enabled, it can only be warned or disabled
+///
+fn parse_extension(line:&str) -> Result<PreprocessorDeclarationType,EParser> {
+
+    let (name,behavior) = match line.split_once(':') {
+
+        Some((name,behavior)) => (name.trim(),behavior.trim()),
+        None => return Err(EParser::EXTENSION(line.trim().to_string(),"expected 'name : behavior'".to_string()))
+
+    };
+
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(EParser::EXTENSION(name.to_string(),"expected one extension name".to_string()));
+    }
+
+    let behavior = match behavior {
+
+        "require" => ExtensionBehavior::REQUIRE,
+        "enable" => ExtensionBehavior::ENABLE,
+        "warn" => ExtensionBehavior::WARN,
+        "disable" => ExtensionBehavior::DISABLE,
+        _ => return Err(EParser::EXTENSION(name.to_string(),format!("unknown behavior '{}'",behavior)))
+
+    };
+
+    if name == "all" && matches!(behavior,ExtensionBehavior::REQUIRE | ExtensionBehavior::ENABLE) {
+        return Err(EParser::EXTENSION(name.to_string(),"'all' can only be warned or disabled".to_string()));
+    }
+
+    Ok(PreprocessorDeclarationType::EXTENSION(name.to_string(),behavior))
+
+}
+
+/// Return the annotations found in a doc comment line
+/// 
+/// # parameters
+/// 
+/// * line - a doc comment line of a shader file that start with '///'
+///   example: '/// @range(0,1) @label("Roughness") @hidden'
+/// 
+fn parse_doc_annotations(line:&str) -> Result<Annotations,EParser> {
+    //
+    let content = line.trim_start().trim_start_matches('/');
+    let chars:Vec<char> = content.chars().collect();
+    //
+    let mut annotations = Annotations::init();
+    let mut i:usize = 0;
+    //
+    // everything that is not after a '@' is considered as a normal comment and is ignored
+    while i < chars.len() {
+
+        if chars[i] != '@' {
+            i += 1;
+            continue;
+        }
+        //
+        // get the name of the annotation
+        //
+        //  @range(0,1)
+        //   ^^^^^
+        i += 1;
+        let start = i;
+
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+            i += 1;
+        }
+
+        let key:String = chars[start..i].iter().collect();
+
+        if key.is_empty() {
+            return Err(EParser::ANNOTATION(line.to_string(),"'@' without a name".to_string()));
+        }
+        //
+        // an annotation without parentheses is a flag
+        if i >= chars.len() || chars[i] != '(' {
+
+            annotations.push(&key, AnnotationValue::FLAG);
+            continue;
+
+        }
+        //
+        // collect the arguments in parentheses. The comma inside a string dont count
+        let mut args:Vec<String> = Vec::new();
+        let mut arg = String::new();
+        let mut in_string = false;
+        let mut closed = false;
+
+        i += 1;
+
+        while i < chars.len() {
+
+            let c = chars[i];
+            i += 1;
+
+            match c {
+
+                '"' => { in_string = !in_string; arg.push(c) },
+                ',' if !in_string => args.push(std::mem::take(&mut arg)),
+                ')' if !in_string => { closed = true; break },
+                _ => arg.push(c)
+
+            }
+
+        }
+
+        if !closed {
+            return Err(
+                EParser::ANNOTATION(line.to_string(),format!("'@{}' is missing a ')'",key))
+            );
+        }
+
+        if !arg.trim().is_empty() || !args.is_empty() {
+            args.push(arg);
+        }
+
+        let mut values:Vec<AnnotationValue> = Vec::with_capacity(args.len());
+
+        for a in args.iter() {
+            values.push(parse_annotation_value(a).map_err(|e| EParser::ANNOTATION(line.to_string(),e))?);
+        }
+        //
+        // '@range(min,max)' is the function form of 'range=min..max'
+        let value = match (key.as_str(), values.as_slice()) {
+
+            (_, []) => AnnotationValue::FLAG,
+            ("range", [min,max]) => match (annotation_as_f64(min),annotation_as_f64(max)) {
+
+                (Some(min),Some(max)) => AnnotationValue::RANGE(min,max),
+                _ => return Err(
+                    EParser::ANNOTATION(line.to_string(),"range expect two numbers".to_string())
+                )
+
+            },
+            (_, [_]) => values.remove(0),
+            _ => AnnotationValue::LIST(values)
+
+        };
+
+        annotations.push(&key, value);
+
+    }
+    //
+    Ok(annotations)
+    //
+}
+//
+//
+/// Return the name of the variable and its annotations declared in a pragma line
+/// 
+/// # parameters
+/// 
+/// * line - a line of a shader file that start with '#pragma annotate'
+///   example: '#pragma annotate roughness range=0..1 label="Roughness"'
+/// 
+fn parse_pragma_annotations(line:&str) -> Result<(String,Annotations),EParser> {
+    //
+    // split on whitespace but keep quoted string in one piece
+    let mut words:Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut in_string = false;
+
+    for c in line.trim().chars() {
+
+        if c == '"' {
+            in_string = !in_string;
+        }
+
+        if c.is_whitespace() && !in_string {
+
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+
+        } else {
+            word.push(c);
+        }
+
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+    //
+    //  #pragma annotate roughness range=0..1
+    //                   ^^^^^^^^^ 
+    let name = match words.get(2) {
+
+        Some(n) => n.to_string(),
+        None => return Err(
+            EParser::ANNOTATION(line.to_string(),"no variable name after annotate".to_string())
+        )
+
+    };
+
+    let mut annotations = Annotations::init();
+
+    for w in words.iter().skip(3) {
+
+        match w.split_once('=') {
+
+            Some((key,value)) => annotations.push(
+                key,
+                parse_annotation_value(value).map_err(|e| EParser::ANNOTATION(line.to_string(),e))?
+            ),
+            None => annotations.push(w, AnnotationValue::FLAG)
+
+        }
+
+    }
+
+    Ok((name,annotations))
+    //
+}
+//
+//
+/// Convert the text of one annotation value to its type. A value that is not a number, 
+/// a bool, a range or a quoted string is stored as a string (ex: 'widget=slider')
+fn parse_annotation_value(value:&str) -> Result<AnnotationValue,String> {
+
+    let v = value.trim();
+
+    if v.is_empty() {
+        return Err("empty value".to_string());
+    }
+
+    if v.len() >= 2 && v.starts_with('"') && v.ends_with('"') {
+        return Ok(AnnotationValue::STRING(v[1..v.len() - 1].to_string()));
+    }
+
+    match v {
+
+        "true" => return Ok(AnnotationValue::BOOL(true)),
+        "false" => return Ok(AnnotationValue::BOOL(false)),
+        _ => {}
+
+    }
+
+    if let Some((min,max)) = v.split_once("..") {
+
+        return match (min.trim().parse::<f64>(),max.trim().parse::<f64>()) {
+
+            (Ok(min),Ok(max)) => Ok(AnnotationValue::RANGE(min,max)),
+            _ => Err(format!("'{}' is not a range of numbers",v))
+
+        };
+
+    }
+
+    if let Ok(i) = v.parse::<i64>() {
+        return Ok(AnnotationValue::INT(i));
+    }
+
+    if let Ok(f) = v.parse::<f64>() {
+        return Ok(AnnotationValue::FLOAT(f));
+    }
+
+    Ok(AnnotationValue::STRING(v.to_string()))
+
+}
+//
+//
+fn annotation_as_f64(value:&AnnotationValue) -> Option<f64> {
+
+    match value {
+
+        AnnotationValue::INT(i) => Some(*i as f64),
+        AnnotationValue::FLOAT(f) => Some(*f),
+        _ => None
+
+    }
+
+}
+//
+//
+fn get_var_type(line:&str) -> Result<VariableType,EParser> {
+
+    let offset = line.find('=').map_or(0,|n| n + 1);
+
+    get_var_type_with_constants(line,&HashMap::new(),offset).map(|(data_type,_)| data_type)
+
+}
+//
+//
+/// Same as get_var_type but the value declared can use the constants passed
+/// (ex: 'const float TWO_PI = 2.0 * PI;'). The value is evaluated once and returned with
+/// the type
+/// 
+/// # parameters
+/// 
+/// * line - the declaration line without its storage qualifiers
+/// * constants - the constants declared before this line
+/// * offset - where the value after the '=' starts in the source, the errors in the value
+///   are reported from it
+/// 
+fn get_var_type_with_constants(
+    line:       &str,
+    constants:  &HashMap<String,ConstValue>,
+    offset:     usize) -> Result<(VariableType,Option<ConstValue>),EParser> {
+    
+    
+    let split_line = line.to_string();
+    
+    for l in split_line.split(" ") {
+
+        if let Some(opaque) = opaque_type(l) {
+            return Ok((opaque,None));
+        }
+        
+        if TYPE_IN_STR.contains(&l) {
+
+            let value = get_initializer_value(line,l,constants,offset)?;
+            let value_ref = value.as_ref();
+
+            let data_type = match l {
+
+                "bool"  => Ok(VariableType::BOOL(get_scalar_value::<bool>(value_ref))),
+
+                "int"   => Ok(VariableType::INT(get_scalar_value::<i32>(value_ref))),
+
+                "uint"  => Ok(VariableType::UINT(get_scalar_value::<u32>(value_ref))),
+
+                "float" => Ok(VariableType::FLOAT(get_scalar_value::<f32>(value_ref))),
+
+                "double"=> Ok(VariableType::DOUBLE(get_scalar_value::<f64>(value_ref))),
+
+                "uvec2" => Ok(VariableType::UVEC2(format_vec_value::<u32,2>(value_ref))),
+
+                "uvec3" => Ok(VariableType::UVEC3(format_vec_value::<u32,3>(value_ref))),
+
+                "uvec4" => Ok(VariableType::UVEC4(format_vec_value::<u32,4>(value_ref))),
+
+                "ivec2" => Ok(VariableType::IVEC2(format_vec_value::<i32,2>(value_ref))),
+
+                "ivec3" => Ok(VariableType::IVEC3(format_vec_value::<i32,3>(value_ref))),
+
+                "ivec4" => Ok(VariableType::IVEC4(format_vec_value::<i32,4>(value_ref))),
+
+                "bvec2" => Ok(VariableType::BVEC2(format_vec_value::<bool,2>(value_ref))),
+
+                "bvec3" => Ok(VariableType::BVEC3(format_vec_value::<bool,3>(value_ref))),
+
+                "bvec4" => Ok(VariableType::BVEC4(format_vec_value::<bool,4>(value_ref))),
+
+                "vec2"  => Ok(VariableType::VEC2(format_vec_value::<f32,2>(value_ref))),
+
+                "vec3"  => Ok(VariableType::VEC3(format_vec_value::<f32,3>(value_ref))),
+
+                "vec4"  => Ok(VariableType::VEC4(format_vec_value::<f32,4>(value_ref))),
+
+                "dvec2" => Ok(VariableType::DVEC2(format_vec_value::<f64,2>(value_ref))),
+
+                "dvec3" => Ok(VariableType::DVEC3(format_vec_value::<f64,3>(value_ref))),
+
+                "dvec4" => Ok(VariableType::DVEC4(format_vec_value::<f64,4>(value_ref))),
+
+                "mat2" => Ok(VariableType::MAT2(
+                    format_mat_value::<f32,2,2>(value_ref)
+                )),
+
+                "mat3" => Ok(VariableType::MAT3(
+                    format_mat_value::<f32,3,3>(value_ref)
+                )),
+
+                "mat4" => Ok(VariableType::MAT4(
+                    format_mat_value::<f32,4,4>(value_ref)
+                )),
+
+                "dmat2" => Ok(VariableType::DMAT2(
+                    format_mat_value::<f64,2,2>(value_ref)
+                )),
+
+                "dmat3" => Ok(VariableType::DMAT3(
+                    format_mat_value::<f64,3,3>(value_ref)
+                )),
+
+                "dmat4" => Ok(VariableType::DMAT4(
+                    format_mat_value::<f64,4,4>(value_ref)
+                )),
+
+                "mat2x2" => Ok(VariableType::MAT2(
+                    format_mat_value::<f32,2,2>(value_ref)
+                )),
+
+                "mat2x3" => Ok(VariableType::MAT2X3(
+                    format_mat_value::<f32,2,3>(value_ref)
+                )),
+
+                "mat2x4" => Ok(VariableType::MAT2X4(
+                    format_mat_value::<f32,2,4>(value_ref)
+                )),
+
+                "mat3x2" => Ok(VariableType::MAT3X2(
+                    format_mat_value::<f32,3,2>(value_ref)
+                )),
+
+                "mat3x3" => Ok(VariableType::MAT3(
+                    format_mat_value::<f32,3,3>(value_ref)
+                )),
+
+                "mat3x4" => Ok(VariableType::MAT3X4(
+                    format_mat_value::<f32,3,4>(value_ref)
+                )),
+
+                "mat4x2" => Ok(VariableType::MAT4X2(
+                    format_mat_value::<f32,4,2>(value_ref)
+                )),
+
+                "mat4x3" => Ok(VariableType::MAT4X3(
+                    format_mat_value::<f32,4,3>(value_ref)
+                )),
+
+                "mat4x4" => Ok(VariableType::MAT4(
+                    format_mat_value::<f32,4,4>(value_ref)
+                )),
+
+                "dmat2x2" => Ok(VariableType::DMAT2(
+                    format_mat_value::<f64,2,2>(value_ref)
+                )),
+
+                "dmat2x3" => Ok(VariableType::DMAT2X3(
+                    format_mat_value::<f64,2,3>(value_ref)
+                )),
+
+                "dmat2x4" => Ok(VariableType::DMAT2X4(
+                    format_mat_value::<f64,2,4>(value_ref)
+                )),
+
+                "dmat3x2" => Ok(VariableType::DMAT3X2(
+                    format_mat_value::<f64,3,2>(value_ref)
+                )),
+
+                "dmat3x3" => Ok(VariableType::DMAT3(
+                    format_mat_value::<f64,3,3>(value_ref)
+                )),
+
+                "dmat3x4" => Ok(VariableType::DMAT3X4(
+                    format_mat_value::<f64,3,4>(value_ref)
+                )),
+
+                "dmat4x2" => Ok(VariableType::DMAT4X2(
+                    format_mat_value::<f64,4,2>(value_ref)
+                )),
+
+                "dmat4x3" => Ok(VariableType::DMAT4X3(
+                    format_mat_value::<f64,4,3>(value_ref)
+                )),
+
+                "dmat4x4" => Ok(VariableType::DMAT4(
+                    format_mat_value::<f64,4,4>(value_ref)
+                )),
+
+                "sampler2D" => {
+
+                    if have_declared_value(line) {
+
+                        unimplemented!()    
+                    }
+
+                    Ok(VariableType::SAMPLER2D(None))
+
+                },
+
+                _ => return Err(EParser::VARIABLE_TYPE(line.to_string())),
+
+            };
+
+            return Ok((data_type?,value));
+   
+        }
+
+    }
+
+    Err(EParser::VARIABLE_TYPE(line.to_string()))
+
+}
+
+
+/// Return the value declared for a variable if there is one. The value can be any constant
+/// expression and is converted to the type of the variable
+/// 
+/// # parameters
+/// 
+/// * line - the declaration line without its storage qualifiers
+/// * type_name - the glsl name of the type declared
+/// * constants - the constants declared before this line
+/// * offset - where the value after the '=' starts in the source
+/// 
+/// # Error causes
+/// 
+/// * The value is not a valid constant expression
+/// * The value cant be converted implicitly to the type declared (ex: 'int a = 1.5;')
+/// 
+fn get_initializer_value(
+    line:       &str,
+    type_name:  &str,
+    constants:  &HashMap<String,ConstValue>,
+    offset:     usize) -> Result<Option<ConstValue>,EParser> {
     //
+    // the value keeps the spaces before it so that the offsets of its errors are right
+    let (declaration,written) = match line.split_once('=') {
+
+        Some((d,v)) => (d,v.trim_end().trim_end_matches(';')),
+        None => return Ok(None)
+
+    };
+    let value = written.trim();
+
+    let (scalar,columns,rows) = match type_shape(type_name) {
+
+        Some(shape) => shape,
+        None => return Ok(None)
+
+    };
+
+    let result = evaluate(&parse_expression(written,offset)?,constants)?;
     //
-    vstorage
+    // an array must be initialized with an array and a variable with a single value
+    let is_array = declaration.contains('[');
+
+    let elements = match (&result,is_array) {
+
+        (ConstValue::ARRAY(elements),true) => elements.iter().collect(),
+        (ConstValue::ARRAY(_),false) | (_,true) => Vec::new(),
+        (v,false) => vec![v]
+
+    };
+
+    let valid = !elements.is_empty() && elements.iter().all(|e| 
+        e.shape() == (columns,rows) && 
+        e.scalar_type().is_some_and(|s| s.converts_implicitly_to(scalar))
+    );
+
+    if !valid {
+
+        return Err(EParser::LITERAL_CONVERSION(
+            value.to_string(),
+            if is_array { format!("{}[]",type_name) } else { type_name.to_string() },
+            result.type_name()
+        ));
+
+    }
+
+    Ok(Some(result.convert(scalar)))
     //
 }
 //
 //
-/// Return info on the content of layout storage declarations
-/// 
-/// # parameters
-/// 
-/// * line - a line of a shader file that have been confirmed
-/// to have a layout declaration
-/// 
-fn parse_layout_storage(line:&str) -> Option<StorageQualifier> {
-    //
-    // find where the layout declaration parentheses start
-    //
-    //  layout (location = 1) in
-    //         ^ 
-    //         |
-    //         × —— what we looking for
-    //
-    let open_par_index = match line.find("layout") {
+/// Return the value declared for a scalar variable if there is one 
+fn get_scalar_value<T:FromLiteral>(value:Option<&ConstValue>) -> Option<T> {
+
+    match value {
+
+        Some(ConstValue::SCALAR(lit)) => Some(T::from_literal(*lit)),
+        _ => None
+
+    }
+
+}
+//
+//
+/// Return the value declared for a vector variable if there is one 
+#[allow(clippy::extra_unused_lifetimes)]
+fn format_vec_value<'a ,T,const N:usize>(value:Option<&ConstValue>) -> Option<[T;N]>
+where T: FromLiteral + Copy + Default
+{
+
+    match value {
+
+        Some(ConstValue::VECTOR(v)) if v.len() == N => {
+
+            let mut arr:[T;N] = [T::default();N];
+
+            for (place, element) in arr.iter_mut().zip(v.iter()) {
+
+                *place = T::from_literal(*element)
+
+            }
+
+            Some(arr)
+
+        },
+        _ => None
+
+    }
+
+}
+//
+//
+/// Return the value declared for a matrix variable if there is one. The value is stored 
+/// column by column, 'value[column][row]'
+/// 
+/// # generic
+/// 
+/// * C - the number of columns of the matrix
+/// * R - the number of rows of the matrix
+/// 
+fn format_mat_value<T,const C:usize,const R:usize>(value:Option<&ConstValue>) -> Option<[[T;R];C]>
+where T: FromLiteral + Copy + Default
+{
+
+    match value {
+
+        Some(ConstValue::MATRIX { columns, rows, components }) if *columns == C && *rows == R => {
+
+            let mut arr:[[T;R];C] = [[T::default();R];C];
+
+            for (i,element) in components.iter().enumerate() {
+
+                arr[i / R][i % R] = T::from_literal(*element)
+
+            }
+
+            Some(arr)
+
+        },
+        _ => None
+
+    }
+
+}
+//
+//
+/// Separate the name of a variable from its array size if it have one
+///     example: 'weights[N * 2]' => ('weights',Some(SIZED(8))) if N is 4
+/// 
+/// # parameters
+/// 
+/// * name - the name and the array size
+/// * constants - the constants declared before
+/// * offset - where the size starts in the source, the errors in it are reported from it
+/// 
+fn get_array_declaration(
+    name:       &str,
+    constants:  &HashMap<String,ConstValue>,
+    offset:     usize) -> Result<(String,Option<ArraySize>),EParser> {
+
+    let (open,close) = match (name.find('['),name.rfind(']')) {
+
+        (Some(o),Some(c)) if o < c => (o,c),
+        (None,None) => return Ok((name.to_string(),None)),
+        _ => return Err(EParser::ARRAY_SIZE(name.to_string()))
+
+    };
+
+    let var_name = name[..open].trim().to_string();
+    let size = name[open + 1..close].trim();
+
+    if size.is_empty() {
+        return Ok((var_name,Some(ArraySize::UNSIZED)));
+    }
+
+    match evaluate(&parse_expression(size,offset)?,constants)?.as_array_size() {
+
+        Some(n) => Ok((var_name,Some(ArraySize::SIZED(n)))),
+        None => Err(EParser::ARRAY_SIZE(name.to_string()))
+
+    }
+
+}
+//
+//
+/// Parse a layout qualifier declared alone for the inputs or the outputs of the stage.
+/// None when the declaration declares a variable
+///     example:
+///         'layout (local_size_x = 8) in;' => [LAYOUT(local_size_x = 8),IN]
+///         'layout (triangles) in;'        => [LAYOUT(triangles),IN]
+///
+fn parse_default_qualifiers(text:&str,constants:&HashMap<String,ConstValue>) -> Option<Vec<StorageQualifier>> {
+
+    let line = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let line = line.trim_end_matches(';').trim_end();
+
+    let close = line.strip_prefix("layout")?.find(')')? + "layout".len();
+
+    let storage = match line[close + 1..].trim() {
+
+        "in" => StorageQualifier::IN,
+        "out" => StorageQualifier::OUT,
+        "uniform" => StorageQualifier::UNIFORM,
+        "buffer" => StorageQualifier::BUFFER,
+        _ => return None
+
+    };
+
+    Some(vec![parse_layout_storage(line,constants).ok().flatten()?,storage])
+
+}
+//
+//
+fn get_vec_content_declaration(line:&str) -> Option<String> {
+
+
+    let equal_split:String = match line.split("=").last() {
 
-        Some(i) => i + 6,
+        Some(v) => v.to_string(),
         None => return None
 
-    };
-    //
-    // init the close parentheses at the same position of the open on
-    let mut close_par_index = open_par_index;
-    let mut found = false;
-    //
-    while !found {
-        
-        if line.chars().nth(close_par_index).unwrap() == ')' {
-            found = true;
-        }
-        
-        close_par_index += 1;
 
-    }
-    // get the content of the parentheses
-    let par_content:Vec<String> = line.to_string()[open_par_index + 1 .. close_par_index ]
-        .split(',')
-        .map(|f| f.to_string())
-        .collect();
-    //
-    // 
-    // we need to make sure that the close parentheses is not out range of the string slice
-    let last_pos:usize;
+    };        
 
-    if close_par_index >=  line.len() - 1 {
+    let mut op_par_index = match equal_split.find("(") {
 
-        last_pos = close_par_index;
+        Some(i) => i + 1,
+        None => return None,
 
-    } else {
+    };
 
-        last_pos = close_par_index + 1;
+    let mut value:String = String::new();
+    let mut found = false;
 
-    }
-    //
-    //  
-    let mut layout_var = LayoutDeclaration::init(&line[..last_pos]);   
-    //
-    // check for every declaration in parentheses
-    for content in par_content.iter() {
+    while !found {
+
+        match line.chars().nth(op_par_index){
 
-        if content.contains("location") {
+            Some(c) => {
 
-            let type_ = LayoutVarType::LOCATION;
+                if op_par_index < equal_split.chars().count() && 
+                c == ')' {
 
+                    found = true;
 
-            let value:String = content
-                .chars()
-                .filter(|c| c.is_ascii_digit())
-                .collect();
+                }
 
-            let value_num:u32 = match value.parse::<u32>() {
-                Ok(v) => v,
-                Err(_) => return None
+                value = format!("{}{}",value,c);
 
 
-            };
+            },
 
-            layout_var.push((type_,value_num))
+            None => return None
 
 
         }
 
 
+        op_par_index += 1;
+
+    
     }
-    //    
-    //  
-    Some(StorageQualifier::LAYOUT(layout_var))
+    
+
+    Some(value)
 
 }
 
-               
-fn parse_preprocessor(line:&str) -> Result<PreprocessorDeclarationType,EParser> {
+fn have_declared_value(line:&str) -> bool { line.contains("=") }
+//
+//
+/// The position of the first character out of the parentheses, the brackets and the
+/// braces of a text
+///     example: 'layout (location = 0) in float a = 1.0' => the second '='
+/// 
+fn find_top_level(text:&str,target:char) -> Option<usize> {
 
-    if line.contains("version") {
+    let mut depth:usize = 0;
 
-        
+    for (i,c) in text.char_indices() {
 
-        let ver:String = line
-            .chars()
-            .filter(|c| c.is_ascii_digit())
-            .collect();
-        
-        let ver_num:u16 = match ver.parse::<u16>() {
+        match c {
 
-            Ok(num) => num,
-            Err(e) => return Err(EParser::STRING_PARSING("U16".to_string(),e.to_string()))
+            '(' | '[' | '{' => depth += 1,
 
-        };
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
 
+            _ if c == target && depth == 0 => return Some(i),
 
-        let branch:String = line
-            .chars()
-            .filter(|c| c.is_alphabetic())
-            .collect();
+            _ => {}
 
-        let index = match branch.find("version") {
+        }
 
-            Some(index) => index,
-            None => return Err(EParser::INDEX_PATERN(branch.to_string()))
+    }
 
-        };
+    None
 
-    
+}
+//
+//
+/// Where the array size of a declaration starts in the source, after its '[' and the spaces
+/// that follow it
+/// 
+/// # parameters
+/// 
+/// * declaration - the declaration as written, without its value
+/// * offset - where the declaration starts in the source
+/// 
+fn array_size_offset(declaration:&str,offset:usize) -> usize {
 
-        let version_branch = match &branch[index + 7 ..] {
-            
-            "core"  =>   VersionBranch::CORE,
-            _       =>   VersionBranch::UNKNOWN
+    match declaration.find('[') {
 
-        };
+        Some(n) => {
 
-        return Ok(PreprocessorDeclarationType::VERSION(ver_num,version_branch));
+            let size = &declaration[n + 1..];
 
-        
+            offset + n + 1 + size.len() - size.trim_start().len()
 
-        
+        },
+        None => offset
 
     }
 
-    unimplemented!()
-
 }
 
-fn get_var_type(line:&str) -> Result<VariableType,EParser> {
-    
-    
-    let split_line = line.to_string();
-    
-    for l in split_line.split(" ") {
-        
-        if TYPE_IN_STR.contains(&l) {
 
-            return match l {
+#[allow(clippy::extra_unused_lifetimes,clippy::ptr_arg)]
+fn remove_storage_type<'a>(line:&str,vstorage:&Vec<StorageQualifier>) -> String {
+
+    let mut filter_line = line.to_string();
 
-                "bool"  => {
+    for store in vstorage.iter() {
+        
+        filter_line = remove_word(&filter_line, store.as_str());
 
+    }
 
-                    if have_declared_value(line) {
+    filter_line
 
+}
 
+/// Check if a word is used as a qualifier in a declaration, it is followed by another
+/// word then. It keeps the variables named like a qualifier ('uniform float sample;')
+fn is_qualifier_word(line:&str,word:&str) -> bool {
 
-                        let equal_split = match line.split("=").last() {
+    let words:Vec<&str> = line.split_whitespace().collect();
 
-                            Some(v) => v,
-                            None => return Err(EParser::MARKED_VALUE_DECL(line.to_string()))
+    words.windows(2).any(|w| w[0] == word && w[1].starts_with(|c:char| c.is_alphabetic() || c == '_'))
 
+}
+//
+//
+/// The qualifiers that don't change what the parser stores about a variable: precision,
+/// memory and invariance qualifiers
+const IGNORED_QUALIFIERS: [&str;10] = [
+    "highp", "mediump", "lowp", "invariant", "precise",
+    "coherent", "volatile", "restrict", "readonly", "writeonly"
+];
+//
+//
+/// Find the interpolation qualifier of a declaration and remove the interpolation and the
+/// ignored qualifiers from it
+fn take_interpolation(line:&str) -> (Option<InterpolationQualifier>,String) {
 
-                        };
+    let mut interpolation:Option<InterpolationQualifier> = None;
+    let mut filter_line = line.to_string();
 
-                        if equal_split.contains("false") {
-                            
-                            return Ok(VariableType::BOOL(Some(false)));
+    for word in ["smooth","flat","noperspective"].iter().chain(IGNORED_QUALIFIERS.iter()) {
 
-                        } else if equal_split.contains("true") {
+        if is_qualifier_word(&filter_line,word) {
 
-                            return Ok(VariableType::BOOL(Some(true)));
+            interpolation = interpolation.or(InterpolationQualifier::from_name(word));
+            filter_line = remove_word(&filter_line,word);
 
-                        }
+        }
 
+    }
 
-                    }
+    (interpolation,filter_line)
 
-                    Ok(VariableType::BOOL(None))
+}
+//
+//
+/// Find the 'attribute' or 'varying' qualifier of glsl 1.xx and glsl es 1.00 and remove it
+/// from a declaration. An attribute is an input of the vertex shader, a varying an output
+/// of the vertex shader and an input of the fragment shader
+/// 
+/// # parameters
+/// 
+/// * line - the declaration
+/// * stage - the stage of the shader, a varying of a shader without stage is neither
+/// 
+fn take_legacy_interface(line:&str,stage:Option<ShaderType>) -> (Option<StorageQualifier>,String) {
 
+    if is_qualifier_word(line,"attribute") {
+        return (Some(StorageQualifier::IN),remove_word(line,"attribute"));
+    }
 
-                },
-                "int"   => {
-                    if have_declared_value(line) {
+    if is_qualifier_word(line,"varying") {
 
-                        let equal_split:String = match line.split("=").last() {
+        let qualifier = match stage {
 
-                            Some(v) => v.to_string(),
-                            None => return Err(EParser::MARKED_VALUE_DECL(line.to_string()))
+            Some(ShaderType::VERTEX) => Some(StorageQualifier::OUT),
+            Some(ShaderType::FRAGMENT) => Some(StorageQualifier::IN),
+            _ => None
 
+        };
 
-                        };
+        return (qualifier,remove_word(line,"varying"));
 
-                        match equal_split.parse::<i32>() {
+    }
 
-                            Ok(value) => VariableType::INT(Some(value)),
-                            Err(_) => return Err(EParser::MARKED_VALUE_DECL(line.to_string()))
+    (None,line.to_string())
 
-                        };
+}
+//
+//
+/// Remove the first time a word is in a line but not when it is part of another word
+/// (ex: removing 'in' from 'in vec3 pointLight;' dont touch 'pointLight')
+fn remove_word(line:&str,word:&str) -> String {
 
-                        
+    if word.is_empty() {
+        return line.to_string();
+    }
 
+    let is_ident = |c:Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut start:usize = 0;
 
-                    }
+    while let Some(i) = line[start..].find(word) {
 
-                    Ok(VariableType::INT(None))
+        let at = start + i;
+        let before = line[..at].chars().next_back();
+        let after = line[at + word.len()..].chars().next();
+        //
+        // a layout declaration end with a parentheses so only its start is checked
+        let word_ends_ident = is_ident(word.chars().last());
 
-                },
-                "uint"  => {
+        if !(is_ident(before) || word_ends_ident && is_ident(after)) {
+            return format!("{}{}",&line[..at],&line[at + word.len()..]);
+        }
 
-                    if have_declared_value(line) {
+        start = at + word.len();
 
-                        let equal_split:String = match line.split("=").last() {
+    }
 
-                            Some(v) => v.to_string(),
-                            None => return Err(EParser::MARKED_VALUE_DECL(line.to_string()))
+    line.to_string()
 
+}
 
-                        };
+#[allow(clippy::extra_unused_lifetimes,clippy::iter_nth_zero)]
+fn remove_data_type<'a>(line:&str,dtype:&VariableType) -> String {
 
-                        match equal_split.parse::<u32>() {
+    // the type as written because some have more than one name ('mat2' and 'mat2x2')
+    let type_word = line
+        .split(' ')
+        .find(|w| TYPE_IN_STR.contains(w))
+        .map(|w| w.to_string())
+        .unwrap_or(dtype.to_string());
 
-                            Ok(value) => VariableType::UINT(Some(value)),
-                            Err(_) => VariableType::UINT(None)
 
-                        };
+    if have_declared_value(line) {
 
-                    }
+        let split_line = match line.split("=").nth(0) {
 
-                    Ok(VariableType::UINT(None))
+            Some(val) => val,
+            None => return remove_word(line, &type_word).trim().to_string()
 
-                },
-                "float" => {
-                    if have_declared_value(line) {
+        };
 
-                        let equal_split:String = match line.split("=").last() {
+        return remove_word(split_line, &type_word).trim().to_string()
 
-                            Some(v) => v.to_string(),
-                            None => return Err(EParser::MARKED_VALUE_DECL(line.to_string()))
+    }
 
+    remove_word(line, &type_word).trim().trim_end_matches(';').trim().to_string()
 
-                        };
+    
 
-                        match equal_split.parse::<f32>() {
 
-                            Ok(value) => VariableType::FLOAT(Some(value)),
-                            Err(_) => VariableType::FLOAT(None)
+} 
+//
+//
+/// A top level part of a shader source
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq)]
+enum SourceItem {
+
+    // a line starting with '#' and the lines it continues on with '\'
+    PREPROCESSOR(String,Span),
+    // a '///' line between two declarations
+    DOC_COMMENT(String,Span),
+    // everything that ends with a ';' outside of a function. The comments are spaces
+    DECLARATION(String,Span),
+    // a function prototype or definition as written in the source
+    FUNCTION(String,Span),
 
-                        };
+}
+//
+impl SourceItem {
 
-                    }
+    fn text(&self) -> &str {
 
-                   Ok(VariableType::FLOAT(None))
+        match self {
 
+            SourceItem::PREPROCESSOR(t,_) |
+            SourceItem::DOC_COMMENT(t,_) |
+            SourceItem::DECLARATION(t,_) |
+            SourceItem::FUNCTION(t,_) => t.as_str()
 
-                },
-                "double"=> {
+        }
 
-                    if have_declared_value(line) {
+    }
 
-                        let equal_split:String = match line.split("=").last() {
+}
+//
+//
+/// Cut a shader source in preprocessor lines, doc comments, declarations and functions
+/// 
+/// # parameters
+/// 
+/// * source - the whole shader source
+/// 
+/// # Error causes
+/// 
+/// * A block comment is never closed
+/// * The braces are not balanced
+/// * The source ends in the middle of a declaration
+/// 
+fn split_source(source:&str) -> Result<Vec<SourceItem>,EParser> {
+    //
+    let mut items:Vec<SourceItem> = Vec::new();
+    //
+    // start and text of the item being read, None between two items
+    let mut start:Option<usize> = None;
+    let mut text = String::new();
+    let mut depth:usize = 0;
+    let mut i:usize = 0;
+    //
+    let line_end = |from:usize| source[from..].find('\n').map(|n| from + n).unwrap_or(source.len());
+    //
+    while i < source.len() {
 
-                            Some(v) => v.to_string(),
-                            None => return Err(EParser::MARKED_VALUE_DECL(line.to_string()))
+        let rest = &source[i..];
+        let c = rest.chars().next().unwrap_or(' ');
 
+        if start.is_none() {
 
-                        };
+            if c.is_whitespace() {
+                i += c.len_utf8();
+                continue;
+            }
 
-                        match equal_split.parse::<f64>() {
+            if rest.starts_with("///") {
 
-                            Ok(value) => VariableType::DOUBLE(Some(value)),
-                            Err(_) => VariableType::DOUBLE(None)
+                let end = line_end(i);
+                items.push(SourceItem::DOC_COMMENT(source[i..end].to_string(),Span::new(i,end)));
+                i = end;
+                continue;
 
-                        };
+            }
 
-                    }
+            if c == '#' {
 
-                    Ok(VariableType::DOUBLE(None))
+                let mut end = line_end(i);
 
-                },
-            
-            
-                "uvec2" => {
+                while source[i..end].trim_end().ends_with('\\') && end < source.len() {
+                    end = line_end(end + 1);
+                }
 
-                    if have_declared_value(line) {
+                items.push(SourceItem::PREPROCESSOR(source[i..end].to_string(),Span::new(i,end)));
+                i = end;
+                continue;
 
-                        return match format_vec_value::<u32,2>(line) {
+            }
 
-                            Ok(v) => Ok(VariableType::UVEC2(Some(v))),
-                            Err(e) => Err(e) 
-                            
-                        }
+        }
+        //
+        // comments are spaces in the declarations, as many as their bytes so that a position
+        // in a declaration is the same from its start in the source
+        if rest.starts_with("//") {
 
-                    }
+            let end = line_end(i);
 
-                    Ok(VariableType::UVEC2(None))
+            if start.is_some() {
+                text.push_str(&" ".repeat(end - i));
+            }
+            i = end;
+            continue;
 
-                },
-                "uvec3" => {
-                    
-                    if have_declared_value(line) {
+        }
 
-                        return match format_vec_value::<u32,3>(line) {
+        if rest.starts_with("/*") {
 
-                            Ok(v) => Ok(VariableType::UVEC3(Some(v))),
-                            Err(e) => Err(e) 
-                            
-                        }
+            let end = match source[i + 2..].find("*/") {
 
-                    }
+                Some(n) => i + 2 + n + 2,
+                None => return Err(EParser::TOKEN(
+                    rest.lines().next().unwrap_or("").to_string(),
+                    "block comment is never closed".to_string()
+                ))
 
-                    Ok(VariableType::UVEC3(None))
+            };
 
-                },
-                "uvec4" => {
-                 
-                    if have_declared_value(line) {
+            if start.is_some() {
+                text.push_str(&" ".repeat(end - i));
+            }
+            i = end;
+            continue;
 
-                        return match format_vec_value::<u32,4>(line) {
+        }
+        //
+        let item_start = *start.get_or_insert(i);
+        text.push(c);
+        i += c.len_utf8();
 
-                            Ok(v) => Ok(VariableType::UVEC4(Some(v))),
-                            Err(e) => Err(e)
-                            
-                        }
+        match c {
 
-                    }
+            '{' => depth += 1,
 
-                    Ok(VariableType::UVEC4(None))
+            '}' => {
 
-                },
-            
-                "ivec2" => {
+                if depth == 0 {
+                    return Err(EParser::SYNTAX("unexpected '}'".to_string(),i - 1));
+                }
 
-                    if have_declared_value(line) {
+                depth -= 1;
 
-                        return match format_vec_value::<i32,2>(line) {
+                // a function definition ends with its closing brace
+                let header = text.split('{').next().unwrap_or("");
 
-                            Ok(v) => Ok(VariableType::IVEC2(Some(v))),
-                            Err(e) => Err(e)
-                            
-                        }
-                        
+                if depth == 0 && is_function_header(header) {
 
-                    }
+                    items.push(SourceItem::FUNCTION(
+                        source[item_start..i].to_string(),
+                        Span::new(item_start,i)
+                    ));
+                    start = None;
+                    text.clear();
 
-                    Ok(VariableType::IVEC2(None))
+                }
 
-                },
+            },
 
-                "ivec3" => {
+            ';' if depth == 0 => {
 
-                    if have_declared_value(line) {
+                let span = Span::new(item_start,i);
 
-                        return match format_vec_value::<i32,3>(line) {
+                if is_function_header(&text[..text.len() - 1]) {
+                    items.push(SourceItem::FUNCTION(source[item_start..i].to_string(),span));
+                } else {
+                    items.push(SourceItem::DECLARATION(std::mem::take(&mut text),span));
+                }
 
-                            Ok(v) => Ok(VariableType::IVEC3(Some(v))),
-                            Err(e) => Err(e) 
-                            
-                        }
+                start = None;
+                text.clear();
 
+            },
 
-                    }
+            _ => {}
 
-                    Ok(VariableType::IVEC3(None))
+        }
 
-                },
-                "ivec4" => {
+    }
+    //
+    if let Some(s) = start {
+        return Err(EParser::SYNTAX("expected ';' or '}' before the end of the source".to_string(),s));
+    }
+    //
+    Ok(items)
+    //
+}
+//
+//
+/// Check if the text before a ';' or a '{' is the start of a function
+///     example:
+///         'vec3 shade(in vec3 n)'         => true
+///         'layout (location = 0) in vec3 a' => false
+///         'vec3 a = vec3(1.0)'            => false
+/// 
+fn is_function_header(text:&str) -> bool {
+    //
+    let text = text.trim();
+    //
+    let open = match text.find('(') {
 
-                    if have_declared_value(line) {
+        Some(o) => o,
+        None => return false
 
-                        return match format_vec_value::<i32,4>(line) {
+    };
+    //
+    // the word before the parenthesis is the name and there must be a type before it
+    let before = text[..open].trim_end();
+    let name_start = before
+        .rfind(|c:char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map(|n| n + 1)
+        .unwrap_or(0);
 
-                            Ok(v) => Ok(VariableType::IVEC4(Some(v))),
-                            Err(e) => Err(e) 
+    let name = &before[name_start..];
 
-                        }
+    if name.is_empty() || name == "layout" || before.contains('=') || before[..name_start].trim().is_empty() {
+        return false;
+    }
+    //
+    // nothing can follow the parameters
+    let mut depth:usize = 0;
 
-                    }
+    for (n,c) in text[open..].char_indices() {
 
-                    Ok(VariableType::IVEC4(None))
+        match c {
 
-                },
+            '(' => depth += 1,
+            ')' => {
 
-                "bvec2" => {
-                    if have_declared_value(line) {
+                depth -= 1;
 
-                        unimplemented!()
+                if depth == 0 {
+                    return text[open + n + 1..].trim().is_empty();
+                }
 
-                    }
+            },
+            _ => {}
 
-                    Ok(VariableType::BVEC2(None))
+        }
 
-                },
-                "bvec3" => {
-                    if have_declared_value(line) {
+    }
 
-                        unimplemented!()
+    false
+    //
+}
+//
+//
+pub struct ShaderFileInfo {
 
-                    }
+    // the stage of the shader when it is known (from the file extension)
+    shader_type:    Option<ShaderType>,
+    declarations:   Vec<DeclarationLine>,
+    // value of the 'const' variables, used to evaluate the declarations after them
+    constants:      HashMap<String,ConstValue>,
+    // the source parsed, the spans of the declarations point in it
+    source:         String,
+    // the parsers registered for the pragmas of the application and what they returned
+    pragma_parsers: HashMap<String,PragmaParser>,
+    pragma_values:  Vec<(String,Box<dyn std::any::Any + Send + Sync>)>,
 
-                    Ok(VariableType::BVEC3(None))
+}
 
-                },
-                "bvec4" => {
-                    if have_declared_value(line) {
+impl Default for ShaderFileInfo {
 
-                        unimplemented!()
+    fn default() -> Self { Self::new() }
 
-                    }
+}
 
-                    Ok(VariableType::BVEC4(None))
+impl ShaderFileInfo{
 
-                },
-                "vec2"  => {
+    pub fn new() -> ShaderFileInfo { 
+        
+        ShaderFileInfo { 
+            shader_type:    None,
+            declarations:   Vec::new(),
+            constants:      HashMap::new(),
+            source:         String::new(),
+            pragma_parsers: HashMap::new(),
+            pragma_values:  Vec::new()
+        } 
+    
+    }
 
-                    if have_declared_value(line) {
+    /// Create an empty ShaderFileInfo for a shader of the stage passed
+    pub fn with_shader_type(shader_type:ShaderType) -> ShaderFileInfo {
 
-                        return match format_vec_value::<f32,2>(line) {
+        ShaderFileInfo { shader_type: Some(shader_type), ..Self::new() }
 
-                            Ok(v) => Ok(VariableType::VEC2(Some(v))),
-                            Err(e) => Err(e)
-                        }
-                        
+    }
 
-                    }
+    pub fn push_declaration(&mut self, declaration:DeclarationLine) { self.declarations.push(declaration) }
 
-                    Ok(VariableType::VEC2(None))
+    /// The stage of the shader if it is known
+    pub fn shader_type(&self) -> Option<ShaderType> { self.shader_type }
 
-                },
-                "vec3"  => {
+    /// The source that was parsed
+    pub fn source(&self) -> &str { self.source.as_str() }
 
-                    if have_declared_value(line) {
+    /// What the shader is compiled for: its stage, version and profile. A shader
+    /// without '#version' is a 1.10 one
+    pub fn target(&self) -> builtin::ShaderTarget {
 
-                        return match format_vec_value::<f32,3>(line) {
+        match self.version() {
 
-                            Ok(v) => Ok(VariableType::VEC3(Some(v))),
-                            Err(e) => Err(e) 
-                        }
+            Some((version,branch)) => builtin::ShaderTarget::new(self.shader_type,version,branch),
+            None => builtin::ShaderTarget::new(self.shader_type,110,VersionBranch::UNKNOWN)
 
-                    }
+        }.with_extensions(self.extensions())
 
-                    Ok(VariableType::VEC3(None))
+    }
 
-                },
-                "vec4"  => {
+    /// The extensions enabled by the '#extension' lines, in the order they are enabled.
+    /// '#extension all : warn' is kept as 'all'
+    pub fn extensions(&self) -> Vec<String> {
 
-                    if have_declared_value(line) {
+        let mut extensions:Vec<String> = Vec::new();
 
-                        return match format_vec_value::<f32,4>(line) {
+        for declaration in self.declarations.iter() {
 
-                            Ok(v) => Ok(VariableType::VEC4(Some(v))),
-                            Err(e) => Err(e)
-                        }
+            let DeclarationLine::PREPROCESSOR(PreprocessorDeclarationType::EXTENSION(name,behavior)) = declaration else {
+                continue;
+            };
 
-                    }
+            match (name.as_str(),behavior.enables()) {
 
-                    Ok(VariableType::VEC4(None))
+                ("all",false) => extensions.clear(),
+                (_,false) => extensions.retain(|e| e != name),
+                (_,true) if !extensions.contains(name) => extensions.push(name.clone()),
+                _ => {}
 
+            }
 
-                },
-                "dvec2" => {
-                    if have_declared_value(line) {
+        }
 
-                        return match format_vec_value::<f64,2>(line) {
+        extensions
 
-                            Ok(v) => Ok(VariableType::DVEC2(Some(v))),
-                            Err(e) => Err(e) 
-                        }
+    }
 
-                    }
+    /// Check if an extension of the '#include' directive is enabled
+    fn includes_enabled(&self) -> bool {
 
-                    Ok(VariableType::DVEC2(None))
+        self.extensions().iter().any(|e| e == "GL_GOOGLE_include_directive" || e == "GL_ARB_shading_language_include")
 
-                },
-                "dvec3" => {
+    }
 
-                    if have_declared_value(line) {
+    /// The version declared by the '#version' line
+    pub fn version(&self) -> Option<(u16,VersionBranch)> {
 
-                        return match format_vec_value::<f64,3>(line) {
+        self.declarations.iter().find_map(|d| match d {
 
-                            Ok(v) => Ok(VariableType::DVEC3(Some(v))),
-                            Err(e) => Err(e) 
-                        }
+            DeclarationLine::PREPROCESSOR(PreprocessorDeclarationType::VERSION(v,branch)) => Some((*v,*branch)),
+            _ => None
 
-                    }
+        })
 
-                    Ok(VariableType::DVEC3(None))
+    }
 
-                },
-                "dvec4" => {
+    /// All the declarations found in the order they appear in the shader
+    pub fn declarations(&self) -> &[DeclarationLine] { &self.declarations }
 
-                    if have_declared_value(line) {
+    /// All the variables declared in the shader
+    pub fn variables(&self) -> impl Iterator<Item = &ShaderVariables> {
 
-                        return match format_vec_value::<f64,4>(line) {
+        self.declarations.iter().filter_map(|d| match d {
 
-                            Ok(v) => Ok(VariableType::DVEC4(Some(v))),
-                            Err(e) => Err(e) 
-                        }
-                    }
+            DeclarationLine::VARIABLE(v) => Some(v),
+            _ => None
 
-                    Ok(VariableType::DVEC4(None))
+        })
 
-                },
+    }
 
-                "mat2"  =>  Ok(VariableType::MAT2(None)),
+    /// Find a variable by its name
+    pub fn variable(&self, name:&str) -> Option<&ShaderVariables> {
 
-                "mat3"  =>  Ok(VariableType::MAT3(None)),
-                
-                "mat4"  =>  Ok(VariableType::MAT4(None)),
-             
-                "dmat2" =>  Ok(VariableType::DMAT2(None)),
-                
-                "dmat3" =>  Ok(VariableType::DMAT3(None)),
+        self.variables().find(|v| v.name() == name)
 
-                "dmat4" =>  Ok(VariableType::DMAT4(None)),
+    }
 
-                "sampler2D" => {
+    /// All the blocks declared in the shader
+    pub fn blocks(&self) -> impl Iterator<Item = &InterfaceBlock> {
 
-                    if have_declared_value(line) {
+        self.declarations.iter().filter_map(|d| match d {
 
-                        unimplemented!()    
-                    }
+            DeclarationLine::BLOCK(b) => Some(b),
+            _ => None
 
-                    Ok(VariableType::SAMPLER2D(None))
+        })
 
-                },
+    }
 
-                _ => return Err(EParser::VARIABLE_TYPE(line.to_string())),
+    /// Find a block by its name (not the name of its instance)
+    pub fn block(&self, name:&str) -> Option<&InterfaceBlock> {
 
-            };
-   
-        }
+        self.blocks().find(|b| b.name() == name)
 
     }
 
-    return Err(EParser::VARIABLE_TYPE(line.to_string()))
-
-}
+    /// All the function prototypes and definitions of the shader
+    pub fn functions(&self) -> impl Iterator<Item = &FunctionDeclaration> {
 
+        self.declarations.iter().filter_map(|d| match d {
 
-fn format_vec_value<'a ,T,const N:usize>(content:&str) -> Result<[T;N],EParser>
-where T: std::str::FromStr + Copy + Default
-{
+            DeclarationLine::FUNCTION(f) => Some(f),
+            _ => None
 
-    let line_split = match content.split("=").last() {
+        })
 
-        Some(v) => v,
-        None => return Err(EParser::VEC_VALUE(content.to_string(),"Not supposed to arrived here".to_string()))
+    }
 
-    };
+    /// Find a function by its name. The definition is returned before a prototype and 
+    /// the first one is returned when the function is overloaded
+    pub fn function(&self, name:&str) -> Option<&FunctionDeclaration> {
 
-    let mut par_content:String = String::new();
+        self.functions()
+            .find(|f| f.name == name && f.is_definition())
+            .or_else(|| self.functions().find(|f| f.name == name))
 
-    
-    let mut open_par_index:usize = match line_split.find("(") {
-        
-        Some(i) => i + 1,
-        None => return Err(
-            EParser::VEC_VALUE(
-                content.to_string(),
-                "Expected to find an open bracket but found nothing".to_string()
-            )
-        )
+    }
 
-    };
+    pub fn parse_line(&mut self,content:&[u8]) -> Result<(),EParser> {
 
-    while open_par_index <= line_split.len() - 1 {
-        
-        let c = match line_split.chars().nth(open_par_index) {
 
-            Some(_c) => _c,
-            None => return Err(
-                EParser::VEC_VALUE(
-                    content.to_string(),
-                    format!("Cant access char at position {}",open_par_index)
-                )
-            )
+        let source = Self::convert_content(content)?;
 
-        };
+        let items = split_source(&source)?;
 
-        if open_par_index < line_split.len() - 1  { 
-            
-            if c == ')' {
-                break;
-            }
 
-        } else {
+        // check if the first line is a preprocessor declaration for the glsl version
+        match items.first() {
 
-            return Err(
-                EParser::VEC_VALUE(
-                    content.to_string(),
-                    "Expected to find close bracket but found nothing".to_string()
-                )
-            );
+            Some(SourceItem::PREPROCESSOR(line,_)) if line.contains("#version") => {},
+            Some(item) => return Err(EParser::OMITTED_FIRST_LINE(item.text().to_string())),
+            None => return Err(EParser::OMITTED_FIRST_LINE(String::new()))
 
         }
 
+        // annotations of the doc comments waiting for the next variable declaration
+        let mut doc_annotations = Annotations::init();
+        // annotations declared with a pragma. They are applied at the end because the 
+        // pragma can be before or after the variable declaration
+        let mut pragma_annotations:Vec<(String,Annotations)> = Vec::new();
+        
+        for item in items.iter() {
 
+            match item {
 
-        par_content = format!("{}{}",par_content,c);
-
-
-        open_par_index += 1;
+                SourceItem::DOC_COMMENT(line,_) => {
 
-    }
+                    doc_annotations.extend(parse_doc_annotations(line)?);
 
+                },
 
-    let vec_split:Vec<&str> = par_content.split(",").collect();
+                SourceItem::PREPROCESSOR(line,span) => {
 
-    if vec_split.len() != N {
+                    // the doc comments only belong to the declaration right after them
+                    doc_annotations = Annotations::init();
 
-        return Err(
-            EParser::VEC_VALUE(
-                content.to_string(),
-                format!("expected having {} value but found {}",N,vec_split.len())
-            )
-        );
+                    if line.trim_start().trim_start_matches('#').trim_start().starts_with("pragma") {
 
-    }
+                        if line.split_whitespace().nth(1) == Some("annotate") {
+                            pragma_annotations.push(parse_pragma_annotations(line)?);
+                        }
 
+                        self.push_pragma(parse_pragma(line,*span))?;
+                        continue;
 
-    let mut values:Vec<T> = Vec::with_capacity(N);
+                    }
+        
+                    match parse_preprocessor(line) {
+
+                        // an include is a preprocessor extension, glsl doesn't have it
+                        Ok(PreprocessorDeclarationType::INCLUDE(_)) if !self.includes_enabled() => return Err(
+                            EParser::EXTENSION(
+                                "GL_GOOGLE_include_directive".to_string(),
+                                format!("'{}' needs the extension to be enabled",line.trim())
+                            )
+                        ),
+        
+                        Ok(decl) => self.declarations.push(DeclarationLine::PREPROCESSOR(decl)),
 
-    for i in 0..N {
+                        Err(e @ EParser::PARSING_LINE(..)) => return Err(e),
+             
+                        Err(e) => return Err(EParser::PARSING_LINE(line.to_string(),e.to_string()))
+        
+                    }
 
-        match vec_split[i].to_string().parse::<T>() {
+                },
 
-            Ok(v) => values.push(v),
-            Err(_) => return Err(
-                EParser::VEC_VALUE(
-                    content.to_string(),
-                    format!("unable to parse value '{}'",vec_split[i])
-                )
-            ),
+                SourceItem::FUNCTION(text,span) => {
 
-        };
+                    // the doc comments only belong to variables
+                    doc_annotations = Annotations::init();
 
-    }
+                    let function = parse_function(text,span.start,&self.constants)?;
 
-    let mut arr:[T;N] = [T::default();N];
+                    self.declarations.push(DeclarationLine::FUNCTION(function));
 
-    for (place, element) in arr.iter_mut().zip(values.iter()) {
+                },
 
-        *place = *element
+                SourceItem::DECLARATION(text,span) if text.contains('{') => {
 
-    }
+                    let mut block = self.parse_block(text,span.start)?;
 
+                    block.annotate(std::mem::take(&mut doc_annotations));
+                    block.set_span(*span);
 
-    Ok(arr)
+                    self.declarations.push(DeclarationLine::BLOCK(block));
 
-    
-}
+                },
 
-fn get_vec_content_declaration(line:&str) -> Option<String> {
+                // a default precision ('precision mediump float;') changes nothing the parser
+                // stores about the variables
+                SourceItem::DECLARATION(text,_) if text.split_whitespace().next() == Some("precision") => {
 
+                    doc_annotations = Annotations::init();
 
-    let equal_split:String = match line.split("=").last() {
+                },
 
-        Some(v) => v.to_string(),
-        None => return None
+                SourceItem::DECLARATION(text,span) if parse_default_qualifiers(text,&self.constants).is_some() => {
 
+                    doc_annotations = Annotations::init();
 
-    };        
+                    if let Some(qualifiers) = parse_default_qualifiers(text,&self.constants) {
+                        self.declarations.push(DeclarationLine::DEFAULT_QUALIFIER(qualifiers,*span));
+                    }
 
-    let mut op_par_index = match equal_split.find("(") {
+                },
 
-        Some(i) => i + 1,
-        None => return None,
+                SourceItem::DECLARATION(text,span) => {
 
-    };
+                    let annotations = std::mem::take(&mut doc_annotations);
 
-    let mut value:String = String::new();
-    let mut found = false;
+                    for mut var in self.parse_declarators(text,span.start)? {
 
-    while !found {
+                        var.annotate(annotations.clone());
+                        var.set_span(*span);
+        
+                        self.declarations.push(DeclarationLine::VARIABLE(var));
 
-        match line.chars().nth(op_par_index){
+                    }
 
-            Some(c) => {
+                }
 
-                if op_par_index <= equal_split.chars().count() - 1 && 
-                c == ')' {
+            }
+    
+        }
 
-                    found = true;
+        for (name,annotations) in pragma_annotations {
 
-                }
+            let var = self.declarations.iter_mut().find_map(|d| match d {
 
-                value = format!("{}{}",value,c);
+                DeclarationLine::VARIABLE(v) if v.name() == name => Some(v),
+                _ => None
 
+            });
 
-            },
+            match var {
 
-            None => return None
+                Some(v) => v.annotate(annotations),
+                None => return Err(
+                    EParser::ANNOTATION(name,"no variable declared with this name".to_string())
+                )
 
+            }
 
         }
 
+        self.source = source;
 
-        op_par_index += 1;
-
+        self.mark_static_use();
     
-    }
+        Ok(())
     
+    
+    }
 
-    Some(value)
-
-}
+    /// Parse the declaration of a variable. The value of a 'const' variable is kept to
+    /// evaluate the declarations after it
+    /// 
+    /// # parameters
+    /// 
+    /// * text - the declaration, it can be written on several lines
+    ///   example: 'layout (location = 0) flat in ivec2 id;'
+    /// * offset - where the text starts in the source
+    /// 
+    fn parse_variable(&mut self,text:&str,offset:usize) -> Result<ShaderVariables,EParser> {
 
-fn have_declared_value(line:&str) -> bool { line.contains("=") }
+        // the value is kept as written so that its errors give their place in the source
+        let (declaration,written) = match find_top_level(text,'=') {
 
+            Some(n) => (&text[..n],Some(&text[n..])),
+            None => (text,None)
 
-fn remove_storage_type<'a>(line:&str,vstorage:&Vec<StorageQualifier>) -> String {
+        };
 
-    let mut filter_line = line.to_string();
+        // a declaration can be written on several lines
+        let line = declaration
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ");
 
-    for store in vstorage.iter() {
+        let (legacy,line) = take_legacy_interface(&line,self.shader_type());
+        let line = line.as_str();
         
-        println!("{}",store.as_str());
-        filter_line = filter_line.replace(&store.as_str(), "");
+        let mut squalifier:Vec<StorageQualifier> =  get_storage_qualifier_with_constants(line,&self.constants)?;
 
-    }
+        squalifier.extend(legacy);
 
-    filter_line
+        let (interpolation,line_without_squalifiers) = take_interpolation(&remove_storage_type(line, &squalifier));
+        
+        let (data_type,value) = get_var_type_with_constants(
+            format!("{}{}",line_without_squalifiers,written.unwrap_or("")).as_str(),
+            &self.constants,
+            offset + declaration.len() + 1
+        )?;
 
-}
 
-fn remove_data_type<'a>(line:&str,dtype:&VariableType) -> String {
+        let (var_name,array_size) = get_array_declaration(
+            remove_data_type(line_without_squalifiers.as_str(), &data_type).as_str(),
+            &self.constants,
+            array_size_offset(declaration,offset)
+        )?;
 
+        if let (true,Some(v)) = (squalifier.contains(&StorageQualifier::CONST),&value) {
 
-    if have_declared_value(line) {
+            self.constants.insert(var_name.to_string(),v.clone());
 
-        let split_line = match line.split("=").nth(0) {
+        }
 
-            Some(val) => val,
-            None => return line.replace(dtype.to_string().as_str(), "").trim().to_string()
 
-        };
+        let mut var = ShaderVariables::new(
+            var_name.as_str(), 
+            squalifier, 
+            data_type
+        );
 
-        return split_line.replace(dtype.to_string().as_str(), "").trim().to_string()
+        var.set_array_size(array_size);
+        var.set_value(value);
+        var.set_interpolation(interpolation);
 
-    }
+        Ok(var)
 
-    line.replace(dtype.to_string().as_str(), "").trim().to_string()
+    }
 
-    
+    /// Parse a declaration with several declarators as the declarations of each of them,
+    /// they share the qualifiers and the type
+    ///     example: 'uniform float a, b[2];' => 'uniform float a' and 'uniform float b[2];'
+    /// 
+    /// # parameters
+    /// 
+    /// * text - the declaration
+    /// * offset - where the text starts in the source
+    /// 
+    fn parse_declarators(&mut self,text:&str,offset:usize) -> Result<Vec<ShaderVariables>,EParser> {
 
+        let mut parts:Vec<(usize,&str)> = Vec::new();
+        let mut start:usize = 0;
 
-} 
-//
-//
-pub struct ShaderFileInfo {
+        while let Some(n) = find_top_level(&text[start..],',') {
 
-    declarations: Vec<DeclarationLine>
+            parts.push((start,&text[start..start + n]));
+            start += n + 1;
 
-}
+        }
 
-impl ShaderFileInfo{
+        parts.push((start,&text[start..]));
 
-    pub fn new() -> ShaderFileInfo { ShaderFileInfo { declarations: Vec::new() } }
+        if parts.len() == 1 {
+            return Ok(vec![self.parse_variable(text,offset)?]);
+        }
+        //
+        //  uniform float a[2] = ..., b;
+        //  ^^^^^^^^^^^^^^ the qualifiers and the type, before the name of the first declarator
+        let first = parts[0].1;
+        let mut declarator = first[..find_top_level(first,'=').unwrap_or(first.len())].trim_end();
 
-    pub fn push_declaration(&mut self, declaration:DeclarationLine) { self.declarations.push(declaration) }
+        while let (true,Some(n)) = (declarator.ends_with(']'),declarator.rfind('[')) {
+            declarator = declarator[..n].trim_end();
+        }
 
-    pub fn parse_line(&mut self,content:&[u8]) -> Result<(),EParser> {
+        let head = declarator.trim_end_matches(|c:char| c.is_ascii_alphanumeric() || c == '_');
 
+        let mut variables:Vec<ShaderVariables> = vec![self.parse_variable(first,offset)?];
 
-        let scontent = Self::convert_content(content)?;
+        for (position,part) in parts[1..].iter() {
+            //
+            // the head is put right before the declarator so that the values and the array
+            // sizes keep their place in the source
+            let declaration = format!("{}{}",head,part);
 
+            variables.push(self.parse_variable(&declaration,offset + position - head.len())?);
 
-        // check if the first line is a preprocessor declaration for the glsl version
-        if !scontent[0].contains("#version") {
-            return Err(EParser::OMITTED_FIRST_LINE(scontent[0].to_string())); 
         }
-        
-        for line in scontent.iter() {
-    
-            let non_whitespace_chars:Vec<char> = line
-                .chars()
-                .filter(|c| c != &' ')
-                .collect();
-    
-            if non_whitespace_chars[0] == '#' {
-    
-                match parse_preprocessor(line) {
-    
-                    Ok(decl) => self.declarations.push(DeclarationLine::PREPROCESSOR(decl)),
-         
-                    Err(e) => return Err(EParser::PARSING_LINE(line.to_string(),e.to_string()))
-    
-                }
-    
-    
-            } else {
 
-                println!("{}",line);
-                
-                let squalifier:Vec<StorageQualifier> =  get_storage_qualifier(line);
-    
-                let line_without_squalifiers = remove_storage_type(line, &squalifier);
-                
-                println!("{}",line_without_squalifiers);
-                
-                let data_type = match get_var_type(line_without_squalifiers.as_str()) {
-    
-                    Ok(val) => val,
-                    Err(e) => return Err(e)
-    
-                };
-    
-    
-                let var_name = remove_data_type(line_without_squalifiers.as_str(), &data_type);
-    
-    
-    
-                let var = ShaderVariables::new(
-                    var_name.as_str(), 
-                    squalifier, 
-                    data_type
-                );
-    
-                self.declarations.push(DeclarationLine::VARIABLE(var));
-    
-            }
-    
-        }
-                
-    
-        Ok(())
-    
-    
-    }
+        Ok(variables)
 
-    fn convert_content(to_convert: &[u8]) -> Result<Vec<String>,EParser> {
+    }
 
+    /// Parse the declaration of a block and its members
+    /// 
+    /// # parameters
+    /// 
+    /// * text - the declaration from its qualifiers to its ';'
+    ///   example: 'layout (std140) uniform Lights { vec4 colors[4]; } lights;'
+    /// * offset - where the text starts in the source
+    /// 
+    /// # Error causes
+    /// 
+    /// * The block has no name
+    /// * A member can't be parsed like a variable
+    /// 
+    fn parse_block(&mut self,text:&str,offset:usize) -> Result<InterfaceBlock,EParser> {
+
+        let (open,close) = match (text.find('{'),text.rfind('}')) {
+
+            (Some(o),Some(c)) if o < c => (o,c),
+            _ => return Err(EParser::PARSING_LINE(text.to_string(),"the braces of the block".to_string()))
 
-        let scontent = match String::from_utf8(to_convert.to_owned()) {
+        };
+        //
+        //  layout (std140) uniform Lights { vec4 colors[4]; } lights;
+        //  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+        let header = text[..open].split_whitespace().collect::<Vec<&str>>().join(" ");
+        let squalifier = get_storage_qualifier_with_constants(&header,&self.constants)?;
 
-            Ok(s) => s,
-            Err(e) => return Err(EParser::U8_STRING_CONVERSION(e.to_string())),
+        let name = match remove_storage_type(&header,&squalifier).split_whitespace().last() {
 
+            Some(name) => name.to_string(),
+            None => return Err(EParser::PARSING_LINE(text.to_string(),"the block has no name".to_string()))
 
         };
 
+        let mut members:Vec<ShaderVariables> = Vec::new();
+        // where the member starts in the text
+        let mut start = open + 1;
 
-        Ok(scontent
-            .split("\n")
-            .filter(|line| !line.to_string().trim().is_empty() )
-            .filter(|line| line.len() > 2 && &line.to_string()[0..2] != "//") // remove comment line 
-            .map(|result| result.to_string())
-            .collect()
-        )
+        for member in text[open + 1..close].split(';') {
 
+            if !member.trim().is_empty() {
+                members.extend(self.parse_declarators(member,offset + start)?);
+            }
 
+            start += member.len() + 1;
 
-    }
+        }
+        //
+        //  layout (std140) uniform Lights { vec4 colors[4]; } lights;
+        //                                                     ^^^^^^
+        let instance = text[close + 1..].trim().trim_end_matches(';').trim();
 
-}
+        let mut block = InterfaceBlock::new(&name,squalifier,members);
+
+        if !instance.is_empty() {
 
+            let (instance_name,array_size) = get_array_declaration(
+                instance,
+                &self.constants,
+                array_size_offset(&text[close + 1..],offset + close + 1)
+            )?;
 
+            block.set_instance(Some(instance_name),array_size);
+
+        }
 
+        Ok(block)
 
+    }
 
+    fn convert_content(to_convert: &[u8]) -> Result<String,EParser> {
 
 
+        match String::from_utf8(to_convert.to_owned()) {
 
+            Ok(s) => Ok(s),
+            Err(e) => Err(EParser::U8_STRING_CONVERSION(e.to_string())),
 
 
+        }
 
 
+    }
 
+}
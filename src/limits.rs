@@ -0,0 +1,443 @@
+//
+// ------------------------------------------------------------------------------------------
+// Limits
+//
+// The limits of an implementation that a shader must stay within. The default values are
+// the minimums that every OpenGL 4.5 implementation gives, and the ones of vulkan for the
+// limits only vulkan has. The presets give the minimums of other versions, a stage that a
+// version doesn't have has a limit of 0
+//
+use std::fmt;
+use crate::ShaderFileInfo;
+use crate::program::{ShaderProgram,interface_array};
+use crate::locations::location_count;
+use crate::layout::{MemoryLayout,type_layout,block_size};
+use crate::datatype::{ShaderType,ScalarType,StorageQualifier,LayoutVarType,VariableType,ArraySize,opaque_family};
+//
+//
+/// The limits of the implementation a shader runs on
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct Limits {
+
+    // GL_MAX_VERTEX_ATTRIBS, the locations of the vertex inputs
+    pub max_vertex_attribs:                     u32,
+    // GL_MAX_VARYING_VECTORS, the locations of the inputs and outputs between stages
+    pub max_varying_vectors:                    u32,
+    // GL_MAX_VARYING_COMPONENTS, the components of the inputs or the outputs of a stage
+    pub max_varying_components:                 u32,
+    // GL_MAX_DRAW_BUFFERS, the locations of the fragment outputs
+    pub max_draw_buffers:                       u32,
+    // GL_MAX_UNIFORM_LOCATIONS
+    pub max_uniform_locations:                  u32,
+    // GL_MAX_*_UNIFORM_COMPONENTS, the components of the uniforms of the default block
+    pub max_vertex_uniform_components:          u32,
+    pub max_tess_control_uniform_components:    u32,
+    pub max_tess_evaluation_uniform_components: u32,
+    pub max_geometry_uniform_components:        u32,
+    pub max_fragment_uniform_components:        u32,
+    pub max_compute_uniform_components:         u32,
+    // GL_MAX_TEXTURE_IMAGE_UNITS, the samplers of a stage
+    pub max_texture_image_units:                u32,
+    // GL_MAX_COMBINED_TEXTURE_IMAGE_UNITS, the bindings of the samplers and the samplers
+    // of all the stages
+    pub max_combined_texture_image_units:       u32,
+    // GL_MAX_IMAGE_UNITS, the bindings of the images
+    pub max_image_units:                        u32,
+    // GL_MAX_ATOMIC_COUNTER_BUFFER_BINDINGS, the bindings of the atomic counters
+    pub max_atomic_counter_buffer_bindings:     u32,
+    // GL_MAX_UNIFORM_BLOCK_SIZE, the bytes of a uniform block
+    pub max_uniform_block_size:                 u32,
+    // GL_MAX_UNIFORM_BUFFER_BINDINGS
+    pub max_uniform_buffer_bindings:            u32,
+    // GL_MAX_SHADER_STORAGE_BUFFER_BINDINGS
+    pub max_shader_storage_buffer_bindings:     u32,
+    // GL_MAX_COMPUTE_SHARED_MEMORY_SIZE, the bytes of the shared variables
+    pub max_compute_shared_memory_size:         u32,
+    // GL_MAX_COMPUTE_WORK_GROUP_INVOCATIONS, the invocations of a work group
+    pub max_compute_work_group_invocations:     u32,
+    // GL_MAX_COMPUTE_WORK_GROUP_SIZE, the size of a work group on each axis
+    pub max_compute_work_group_size:            [u32;3],
+    // maxBoundDescriptorSets of vulkan, the sets a resource can be in
+    pub max_bound_descriptor_sets:              u32,
+    // maxPushConstantsSize of vulkan, the bytes of the push constants of a program
+    pub max_push_constants_size:                u32,
+
+}
+//
+impl Default for Limits {
+
+    fn default() -> Self { Self::gl45() }
+
+}
+//
+impl Limits {
+    //
+    /// The minimums of OpenGL 3.3, it has no tessellation and no compute shader
+    pub fn gl33() -> Self {
+
+        Limits {
+            max_combined_texture_image_units:       48,
+            max_uniform_buffer_bindings:            36,
+            max_tess_control_uniform_components:    0,
+            max_tess_evaluation_uniform_components: 0,
+            max_compute_uniform_components:         0,
+            max_image_units:                        0,
+            max_atomic_counter_buffer_bindings:     0,
+            max_shader_storage_buffer_bindings:     0,
+            max_compute_shared_memory_size:         0,
+            max_compute_work_group_invocations:     0,
+            max_compute_work_group_size:            [0,0,0],
+            ..Self::gl45()
+        }
+
+    }
+    //
+    /// The minimums of OpenGL 4.5
+    pub fn gl45() -> Self {
+
+        Limits {
+            max_vertex_attribs:                     16,
+            max_varying_vectors:                    15,
+            max_varying_components:                 60,
+            max_draw_buffers:                       8,
+            max_uniform_locations:                  1024,
+            max_vertex_uniform_components:          1024,
+            max_tess_control_uniform_components:    1024,
+            max_tess_evaluation_uniform_components: 1024,
+            max_geometry_uniform_components:        1024,
+            max_fragment_uniform_components:        1024,
+            max_compute_uniform_components:         1024,
+            max_texture_image_units:                16,
+            max_combined_texture_image_units:       80,
+            max_image_units:                        8,
+            max_atomic_counter_buffer_bindings:     1,
+            max_uniform_block_size:                 16384,
+            max_uniform_buffer_bindings:            84,
+            max_shader_storage_buffer_bindings:     8,
+            max_compute_shared_memory_size:         32768,
+            max_compute_work_group_invocations:     1024,
+            max_compute_work_group_size:            [1024,1024,64],
+            max_bound_descriptor_sets:              4,
+            max_push_constants_size:                128
+        }
+
+    }
+    //
+    /// The minimums of OpenGL ES 3.0, it has only the vertex and the fragment shaders
+    pub fn gles30() -> Self {
+
+        Limits {
+            max_draw_buffers:                       4,
+            max_fragment_uniform_components:        896,
+            max_geometry_uniform_components:        0,
+            max_combined_texture_image_units:       32,
+            max_uniform_buffer_bindings:            24,
+            ..Self::gl33()
+        }
+
+    }
+    //
+    /// The components of the uniforms of the default block a stage can have
+    pub fn max_uniform_components(&self,stage:ShaderType) -> u32 {
+
+        match stage {
+
+            ShaderType::VERTEX => self.max_vertex_uniform_components,
+            ShaderType::TESSCONTROL => self.max_tess_control_uniform_components,
+            ShaderType::TESSEVALUATION => self.max_tess_evaluation_uniform_components,
+            ShaderType::GEOMETRY => self.max_geometry_uniform_components,
+            ShaderType::FRAGMENT => self.max_fragment_uniform_components,
+            ShaderType::COMPUTE => self.max_compute_uniform_components
+
+        }
+
+    }
+    //
+}
+//
+//
+/// The limit a shader goes over
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum LimitKind {
+
+    VERTEX_ATTRIBS,
+    // the locations of the outputs of a fragment shader
+    DRAW_BUFFERS,
+    // the components of the inputs and of the outputs of a stage
+    INPUT_COMPONENTS,
+    OUTPUT_COMPONENTS,
+    UNIFORM_COMPONENTS,
+    TEXTURE_IMAGE_UNITS,
+    // the samplers of all the stages of a program
+    COMBINED_TEXTURE_IMAGE_UNITS,
+    // the name of the block
+    UNIFORM_BLOCK_SIZE(String),
+    COMPUTE_SHARED_MEMORY_SIZE,
+    COMPUTE_WORK_GROUP_INVOCATIONS,
+    // the axis, 0 for x
+    COMPUTE_WORK_GROUP_SIZE(usize),
+
+}
+//
+//
+/// A limit a shader or a program goes over
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct LimitIssue {
+
+    pub kind:   LimitKind,
+    // None for the limits of a program
+    pub stage:  Option<ShaderType>,
+    // what the shader uses and what the limit is
+    pub used:   u64,
+    pub max:    u32,
+
+}
+//
+impl fmt::Display for LimitIssue {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        let what = match &self.kind {
+
+            LimitKind::VERTEX_ATTRIBS => "vertex attributes".to_string(),
+            LimitKind::DRAW_BUFFERS => "draw buffers".to_string(),
+            LimitKind::INPUT_COMPONENTS => "components of inputs".to_string(),
+            LimitKind::OUTPUT_COMPONENTS => "components of outputs".to_string(),
+            LimitKind::UNIFORM_COMPONENTS => "uniform components".to_string(),
+            LimitKind::TEXTURE_IMAGE_UNITS => "texture image units".to_string(),
+            LimitKind::COMBINED_TEXTURE_IMAGE_UNITS => "combined texture image units".to_string(),
+            LimitKind::UNIFORM_BLOCK_SIZE(name) => format!("bytes in the uniform block '{}'",name),
+            LimitKind::COMPUTE_SHARED_MEMORY_SIZE => "bytes of shared memory".to_string(),
+            LimitKind::COMPUTE_WORK_GROUP_INVOCATIONS => "invocations by work group".to_string(),
+            LimitKind::COMPUTE_WORK_GROUP_SIZE(axis) => format!("invocations on the axis {} of the work group",["x","y","z"][*axis])
+
+        };
+
+        match self.stage {
+
+            Some(stage) => write!(f,"the {} shader uses {} {} but there are {}",stage.as_str(),self.used,what,self.max),
+            None => write!(f,"the program uses {} {} but there are {}",self.used,what,self.max)
+
+        }
+
+    }
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Sum what the shader uses and find the limits it goes over. The inputs and outputs
+    /// are counted when they are declared, the vertex attributes, the uniforms and the
+    /// samplers only when main uses them since the drivers remove the others. A double
+    /// takes two components
+    pub fn check_limits(&self,limits:&Limits) -> Vec<LimitIssue> {
+
+        let Some(stage) = self.shader_type() else { return Vec::new() };
+
+        let mut issues:Vec<LimitIssue> = Vec::new();
+
+        let mut check = |kind:LimitKind,used:u64,max:u32| if used > max as u64 {
+            issues.push(LimitIssue { kind, stage: Some(stage), used, max });
+        };
+
+        if stage == ShaderType::VERTEX {
+
+            let attributes = self.variables()
+                .filter(|v| v.has_qualifier(&StorageQualifier::IN) && v.is_used() && !v.name().starts_with("gl_"))
+                .filter_map(|v| location_count(v.var_type(),v.array_size(),true))
+                .map(u64::from)
+                .sum::<u64>();
+
+            check(LimitKind::VERTEX_ATTRIBS,attributes,limits.max_vertex_attribs);
+
+        }
+
+        if stage == ShaderType::FRAGMENT {
+
+            //
+            // the buffers go up to the last location written, the second output of a
+            // dual-source blending shares the location of the first
+            let assignments = self.assign_locations();
+
+            let buffers = self.variables()
+                .filter(|v| v.has_qualifier(&StorageQualifier::OUT) && !v.name().starts_with("gl_"))
+                .filter(|v| v.layout_value(LayoutVarType::INDEX) != Some(1))
+                .filter_map(|v| {
+
+                    let location = v.location().or_else(|| {
+                        assignments.iter().find(|a| a.output && a.span == v.span()).map(|a| a.location)
+                    })?;
+
+                    Some(location as u64 + location_count(v.var_type(),v.array_size(),false)? as u64)
+
+                })
+                .max()
+                .unwrap_or(0);
+
+            check(LimitKind::DRAW_BUFFERS,buffers,limits.max_draw_buffers);
+
+        }
+
+        for (storage,kind) in [(StorageQualifier::IN,LimitKind::INPUT_COMPONENTS),(StorageQualifier::OUT,LimitKind::OUTPUT_COMPONENTS)] {
+
+            let vertex_input = stage == ShaderType::VERTEX && storage == StorageQualifier::IN;
+            let fragment_output = stage == ShaderType::FRAGMENT && storage == StorageQualifier::OUT;
+
+            if vertex_input || fragment_output || stage == ShaderType::COMPUTE {
+                continue;
+            }
+
+            check(kind,self.varying_components(&storage),limits.max_varying_components);
+
+        }
+
+        let uniforms = self.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::UNIFORM) && v.is_used())
+            .map(|v| component_count(v.var_type(),v.array_size()))
+            .sum::<u64>();
+
+        check(LimitKind::UNIFORM_COMPONENTS,uniforms,limits.max_uniform_components(stage));
+        check(LimitKind::TEXTURE_IMAGE_UNITS,self.texture_units(),limits.max_texture_image_units);
+
+        for block in self.blocks().filter(|b| b.has_qualifier(&StorageQualifier::UNIFORM) && !b.is_push_constant()) {
+
+            let Some(size) = block_size(block.members(),block.memory_layout().unwrap_or(MemoryLayout::STD140)) else { continue };
+
+            check(LimitKind::UNIFORM_BLOCK_SIZE(block.name().to_string()),size as u64,limits.max_uniform_block_size);
+
+        }
+
+        if stage == ShaderType::COMPUTE {
+
+            let shared = self.variables()
+                .filter(|v| v.has_qualifier(&StorageQualifier::SHARED))
+                .filter_map(|v| type_layout(v.var_type(),v.array_size(),MemoryLayout::STD430)?.size)
+                .sum::<usize>();
+
+            check(LimitKind::COMPUTE_SHARED_MEMORY_SIZE,shared as u64,limits.max_compute_shared_memory_size);
+
+            if let Some(size) = self.local_size() {
+
+                check(
+                    LimitKind::COMPUTE_WORK_GROUP_INVOCATIONS,
+                    size.iter().map(|s| *s as u64).product(),
+                    limits.max_compute_work_group_invocations
+                );
+
+                for (axis,(size,max)) in size.iter().zip(limits.max_compute_work_group_size).enumerate() {
+                    check(LimitKind::COMPUTE_WORK_GROUP_SIZE(axis),*size as u64,max);
+                }
+
+            }
+
+        }
+
+        issues
+
+    }
+    //
+    /// The components of the inputs or of the outputs with the members of their blocks. The
+    /// array of the vertices of the tessellation and geometry stages is not counted
+    fn varying_components(&self,storage:&StorageQualifier) -> u64 {
+
+        let patch = |qualifiers:&[StorageQualifier]| qualifiers.contains(&StorageQualifier::PATCH);
+
+        let variables = self.variables()
+            .filter(|v| v.has_qualifier(storage) && !v.name().starts_with("gl_"))
+            .map(|v| component_count(v.var_type(),interface_array(self,storage,v.array_size(),patch(v.storage_qualifiers())).as_ref()))
+            .sum::<u64>();
+
+        let blocks = self.blocks()
+            .filter(|b| b.has_qualifier(storage) && !b.name().starts_with("gl_"))
+            .map(|b| {
+
+                let members = b.members().iter().map(|m| component_count(m.var_type(),m.array_size())).sum::<u64>();
+
+                match interface_array(self,storage,b.array_size(),patch(b.storage_qualifiers())) {
+
+                    Some(ArraySize::SIZED(n)) => members * n as u64,
+                    _ => members
+
+                }
+
+            })
+            .sum::<u64>();
+
+        variables + blocks
+
+    }
+    //
+    /// The samplers and textures used, an array takes a unit by element
+    fn texture_units(&self) -> u64 {
+
+        self.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::UNIFORM) && v.is_used() && is_texture(v.var_type()))
+            .map(|v| match v.array_size() { Some(ArraySize::SIZED(n)) => *n as u64, _ => 1 })
+            .sum()
+
+    }
+    //
+}
+//
+//
+impl ShaderProgram {
+    //
+    /// The limits every stage goes over, then the ones the stages go over together
+    pub fn check_limits(&self,limits:&Limits) -> Vec<LimitIssue> {
+
+        let mut issues:Vec<LimitIssue> = self.stages().flat_map(|s| s.check_limits(limits)).collect();
+
+        let units = self.stages().map(|s| s.texture_units()).sum::<u64>();
+
+        if units > limits.max_combined_texture_image_units as u64 {
+
+            issues.push(LimitIssue {
+                kind: LimitKind::COMBINED_TEXTURE_IMAGE_UNITS,
+                stage: None,
+                used: units,
+                max: limits.max_combined_texture_image_units
+            });
+
+        }
+
+        issues
+
+    }
+    //
+}
+//
+//
+/// The components of a variable, 0 for the opaque types and the arrays without size
+fn component_count(var_type:&VariableType,array_size:Option<&ArraySize>) -> u64 {
+
+    let (Some((columns,rows)),Some(scalar)) = (var_type.dimensions(),var_type.scalar_type()) else { return 0 };
+
+    let size = match scalar { ScalarType::DOUBLE => 2, _ => 1 };
+
+    let elements = match array_size {
+
+        Some(ArraySize::SIZED(n)) => *n as u64,
+        Some(ArraySize::UNSIZED) => 0,
+        None => 1
+
+    };
+
+    (columns * rows * size) as u64 * elements
+
+}
+//
+//
+/// Check if an opaque type takes a texture image unit: the samplers with a texture and the
+/// textures of vulkan glsl
+fn is_texture(var_type:&VariableType) -> bool {
+
+    let name = var_type.to_string();
+    let family = opaque_family(&name);
+
+    var_type.is_opaque() && (family.starts_with("texture") || (family.starts_with("sampler") && family != "sampler" && family != "samplerShadow"))
+
+}
+//
+//
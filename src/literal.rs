@@ -0,0 +1,381 @@
+//
+// ------------------------------------------------------------------------------------------
+// Literal parsing
+//
+// Follow the rules of the section 4.1.3 (Integers) and 4.1.4 (Floats) of the GLSL
+// specification
+//
+use crate::EParser;
+//
+//
+/// A literal value as written in a shader source with the type that glsl give it
+///     example:
+///         '1'     => INT(1)
+///         '0xFFu' => UINT(255)
+///         '1e-3'  => FLOAT(0.001)
+///         '1.0lf' => DOUBLE(1.0)
+///
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub enum Literal {
+
+    BOOL    (bool),
+    INT     (i32),
+    UINT    (u32),
+    FLOAT   (f32),
+    DOUBLE  (f64),
+
+}
+//
+impl Literal {
+    //
+    /// The name of the glsl type of the literal
+    pub fn type_name(&self) -> &str {
+
+        match self {
+
+            Self::BOOL(_) =>    "bool",
+            Self::INT(_) =>     "int",
+            Self::UINT(_) =>    "uint",
+            Self::FLOAT(_) =>   "float",
+            Self::DOUBLE(_) =>  "double",
+
+        }
+
+    }
+    //
+    /// Check if glsl would implicitly convert the literal to the type passed
+    /// (int -> uint -> float -> double). A bool is never converted implicitly
+    pub fn converts_implicitly_to(&self, type_name:&str) -> bool {
+
+        let rank = |t:&str| match t {
+
+            "int" =>    Some(0),
+            "uint" =>   Some(1),
+            "float" =>  Some(2),
+            "double" => Some(3),
+            _ =>        None
+
+        };
+
+        if self.type_name() == type_name {
+            return true;
+        }
+
+        match (rank(self.type_name()),rank(type_name)) {
+
+            (Some(from),Some(to)) => from < to,
+            _ => false
+
+        }
+
+    }
+    //
+}
+//
+//
+/// Convert a literal to a rust type following the conversion rules of the glsl constructors
+/// (ex: 'int(2.7)' => 2, 'bool(0)' => false, 'float(true)' => 1.0)
+pub(crate) trait FromLiteral: Sized {
+
+    fn from_literal(lit:Literal) -> Self;
+
+}
+//
+impl FromLiteral for bool {
+
+    fn from_literal(lit:Literal) -> Self {
+
+        match lit {
+
+            Literal::BOOL(b) =>     b,
+            Literal::INT(i) =>      i != 0,
+            Literal::UINT(u) =>     u != 0,
+            Literal::FLOAT(f) =>    f != 0.0,
+            Literal::DOUBLE(d) =>   d != 0.0,
+
+        }
+
+    }
+
+}
+//
+impl FromLiteral for i32 {
+
+    fn from_literal(lit:Literal) -> Self {
+
+        match lit {
+
+            Literal::BOOL(b) =>     b as i32,
+            Literal::INT(i) =>      i,
+            Literal::UINT(u) =>     u as i32,
+            Literal::FLOAT(f) =>    f as i32,
+            Literal::DOUBLE(d) =>   d as i32,
+
+        }
+
+    }
+
+}
+//
+impl FromLiteral for u32 {
+
+    fn from_literal(lit:Literal) -> Self {
+
+        match lit {
+
+            Literal::BOOL(b) =>     b as u32,
+            Literal::INT(i) =>      i as u32,
+            Literal::UINT(u) =>     u,
+            Literal::FLOAT(f) =>    f as u32,
+            Literal::DOUBLE(d) =>   d as u32,
+
+        }
+
+    }
+
+}
+//
+impl FromLiteral for f32 {
+
+    fn from_literal(lit:Literal) -> Self {
+
+        match lit {
+
+            Literal::BOOL(b) =>     b as u8 as f32,
+            Literal::INT(i) =>      i as f32,
+            Literal::UINT(u) =>     u as f32,
+            Literal::FLOAT(f) =>    f,
+            Literal::DOUBLE(d) =>   d as f32,
+
+        }
+
+    }
+
+}
+//
+impl FromLiteral for f64 {
+
+    fn from_literal(lit:Literal) -> Self {
+
+        match lit {
+
+            Literal::BOOL(b) =>     b as u8 as f64,
+            Literal::INT(i) =>      i as f64,
+            Literal::UINT(u) =>     u as f64,
+            Literal::FLOAT(f) =>    f as f64,
+            Literal::DOUBLE(d) =>   d,
+
+        }
+
+    }
+
+}
+//
+//
+/// Parse a glsl literal. The whitespaces around it are ignored and a leading sign is
+/// accepted so the value of an initializer like 'int a = -1;' can be parsed directly
+///
+/// # parameters
+///
+/// * text - the literal as written in the shader (ex: '0xFFu', '1.5e3f', '2.0lf')
+///
+/// # Error causes
+///
+/// * The text is not a bool, an integer or a floating point literal
+/// * An octal literal have a digit of 8 or 9
+/// * The value doesn't fit in 32 bits for an integer or is infinite for a float
+///
+pub fn parse_literal(text:&str) -> Result<Literal,EParser> {
+    //
+    let trimmed = text.trim();
+    //
+    // GLSL literals don't have sign but an initializer does
+    let (negative,literal) = match trimmed.strip_prefix('-') {
+
+        Some(l) => (true,l.trim_start()),
+        None => (false,trimmed.strip_prefix('+').unwrap_or(trimmed).trim_start())
+
+    };
+
+    if literal.is_empty() {
+        return Err(EParser::LITERAL(text.to_string(),"empty literal".to_string()));
+    }
+
+    match literal {
+
+        "true" if !negative => return Ok(Literal::BOOL(true)),
+        "false" if !negative => return Ok(Literal::BOOL(false)),
+        _ => {}
+
+    }
+
+    let is_hex = literal.starts_with("0x") || literal.starts_with("0X");
+    let is_float = !is_hex && (
+        literal.contains('.') || literal.contains('e') || literal.contains('E') ||
+        literal.ends_with('f') || literal.ends_with('F')
+    );
+
+    let value = if is_float {
+        parse_float(text,literal)?
+    } else {
+        parse_integer(text,literal,is_hex,negative)?
+    };
+
+    if !negative {
+        return Ok(value);
+    }
+
+    Ok(match value {
+
+        Literal::INT(i) =>      Literal::INT(i.wrapping_neg()),
+        Literal::UINT(u) =>     Literal::UINT(u.wrapping_neg()),
+        Literal::FLOAT(f) =>    Literal::FLOAT(-f),
+        Literal::DOUBLE(d) =>   Literal::DOUBLE(-d),
+        Literal::BOOL(_) =>     unreachable!()
+
+    })
+    //
+}
+//
+//
+/// Parse an integer literal in decimal, octal (leading 0) or hexadecimal (leading 0x) with
+/// an optional 'u' suffix
+fn parse_integer(text:&str,literal:&str,is_hex:bool,negative:bool) -> Result<Literal,EParser> {
+    //
+    let (digits,unsigned) = match literal.strip_suffix(['u','U']) {
+
+        Some(d) => (d,true),
+        None => (literal,false)
+
+    };
+
+    let (digits,radix) = if is_hex {
+
+        (&digits[2..],16)
+
+    } else if digits.len() > 1 && digits.starts_with('0') {
+
+        (&digits[1..],8)
+
+    } else {
+
+        (digits,10)
+
+    };
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+
+        let reason = match radix {
+
+            8 if digits.chars().all(|c| c.is_ascii_digit()) =>
+                "an octal literal can only have digits from 0 to 7".to_string(),
+            _ => format!("'{}' is not a valid integer in base {}",digits,radix)
+
+        };
+
+        return Err(EParser::LITERAL(text.to_string(),reason));
+
+    }
+    //
+    // every literal must fit in 32 bits
+    let value = match u64::from_str_radix(digits,radix) {
+
+        Ok(v) if v <= u32::MAX as u64 => v as u32,
+        _ => return Err(EParser::LITERAL_OVERFLOW(
+            text.to_string(),
+            if unsigned { "uint" } else { "int" }.to_string()
+        ))
+
+    };
+
+    if unsigned {
+        return Ok(Literal::UINT(value));
+    }
+    //
+    // an hexadecimal or octal literal is a bit pattern so '0xFFFFFFFF' is -1 but a decimal
+    // literal need to fit in a signed integer ('-2147483648' is allowed)
+    if radix == 10 && value > i32::MAX as u32 && !(negative && value == 1 << 31) {
+
+        return Err(EParser::LITERAL_OVERFLOW(text.to_string(),"int".to_string()));
+
+    }
+
+    Ok(Literal::INT(value as i32))
+    //
+}
+//
+//
+/// Parse a floating point literal with an optional exponent and 'f/F' (float) or
+/// 'lf/LF' (double) suffix
+fn parse_float(text:&str,literal:&str) -> Result<Literal,EParser> {
+    //
+    let (digits,double) = if let Some(d) = literal.strip_suffix("lf").or(literal.strip_suffix("LF")) {
+
+        (d,true)
+
+    } else {
+
+        (literal.strip_suffix(['f','F']).unwrap_or(literal),false)
+
+    };
+    //
+    // rust accept things like 'inf' or 'NaN' so we check the form ourself
+    //
+    //  digits . digits (e|E) (+|-) digits
+    //
+    let (mantissa,exponent) = match digits.find(['e','E']) {
+
+        Some(i) => (&digits[..i],Some(&digits[i + 1..])),
+        None => (digits,None)
+
+    };
+
+    let mut parts = mantissa.splitn(2,'.');
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next();
+
+    let valid_mantissa =
+        whole.chars().all(|c| c.is_ascii_digit()) &&
+        fraction.is_none_or(|f| f.chars().all(|c| c.is_ascii_digit())) &&
+        (!whole.is_empty() || fraction.is_some_and(|f| !f.is_empty())) &&
+        // without a dot, the exponent is mandatory ('1f' is not a literal)
+        (fraction.is_some() || exponent.is_some());
+
+    let valid_exponent = exponent.is_none_or(|e| {
+
+        let e = e.strip_prefix(['+','-']).unwrap_or(e);
+        !e.is_empty() && e.chars().all(|c| c.is_ascii_digit())
+
+    });
+
+    if !valid_mantissa || !valid_exponent {
+
+        return Err(
+            EParser::LITERAL(text.to_string(),"not a valid floating point literal".to_string())
+        );
+
+    }
+
+    if double {
+
+        return match digits.parse::<f64>() {
+
+            Ok(d) if d.is_finite() => Ok(Literal::DOUBLE(d)),
+            Ok(_) => Err(EParser::LITERAL_OVERFLOW(text.to_string(),"double".to_string())),
+            Err(e) => Err(EParser::LITERAL(text.to_string(),e.to_string()))
+
+        };
+
+    }
+
+    match digits.parse::<f32>() {
+
+        Ok(f) if f.is_finite() => Ok(Literal::FLOAT(f)),
+        Ok(_) => Err(EParser::LITERAL_OVERFLOW(text.to_string(),"float".to_string())),
+        Err(e) => Err(EParser::LITERAL(text.to_string(),e.to_string()))
+
+    }
+    //
+}
+//
+//
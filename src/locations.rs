@@ -0,0 +1,343 @@
+//
+// ------------------------------------------------------------------------------------------
+// Locations
+//
+// Give a location to the inputs and the outputs declared without 'layout(location = N)'.
+// The number of locations a variable takes follows the section 4.4.1 of the GLSL
+// specification and the locations already given are kept
+//
+use crate::ShaderFileInfo;
+use crate::lexer::Span;
+use crate::program::{ShaderProgram,interface_array};
+use crate::datatype::{ShaderType,ShaderVariables,InterfaceBlock,VariableType,ScalarType,StorageQualifier,LayoutVarType,ArraySize};
+//
+//
+/// A location given to an input or an output without one
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct LocationAssignment {
+
+    pub name:       String,
+    pub stage:      Option<ShaderType>,
+    // true for an output, false for an input
+    pub output:     bool,
+    pub location:   u32,
+    // the number of locations used from 'location'
+    pub count:      u32,
+    // the declaration of the variable
+    pub span:       Span,
+
+}
+//
+//
+/// The number of locations an input or an output takes
+///
+/// # parameters
+///
+/// * var_type - the type of the variable
+/// * array_size - the size of the array if the variable is one, without the array of the
+///   vertices of the per-vertex inputs and outputs
+/// * vertex_input - the inputs of a vertex shader take one location for a dvec3 or a dvec4
+///
+/// Return None for the opaque types, the arrays without size and the arrays that take
+/// more locations than a u32 can count
+///
+pub fn location_count(var_type:&VariableType,array_size:Option<&ArraySize>,vertex_input:bool) -> Option<u32> {
+
+    let (columns,rows) = var_type.dimensions()?;
+    let double = var_type.scalar_type()? == ScalarType::DOUBLE;
+    //
+    // a matrix takes a location by column, a dvec3 and a dvec4 take two
+    let by_column = match double && rows > 2 && !vertex_input {
+
+        true => 2,
+        false => 1
+
+    };
+
+    let count = columns as u32 * by_column;
+
+    match array_size {
+
+        None => Some(count),
+        Some(ArraySize::SIZED(n)) => n.checked_mul(count),
+        Some(ArraySize::UNSIZED) => None
+
+    }
+
+}
+//
+//
+/// The locations that are used, by ranges so a large array doesn't take an entry by
+/// location. The ends are u64 so the last location of a u32 can be used
+#[derive(Default)]
+struct Slots {
+
+    // the first location and the location after the last
+    used: Vec<(u64,u64)>,
+
+}
+//
+impl Slots {
+    //
+    /// A range that uses no component doesn't take its locations
+    fn take(&mut self,location:u32,count:u32,components:u8) {
+
+        if components != 0 && count != 0 {
+            self.used.push((location as u64,location as u64 + count as u64));
+        }
+
+    }
+    //
+    fn is_free(&self,location:u32,count:u32) -> bool {
+
+        let (start,end) = (location as u64,location as u64 + count as u64);
+
+        end <= 1 << 32 && self.used.iter().all(|(s,e)| end <= *s || *e <= start)
+
+    }
+    //
+    /// The first free location is the 0 or the end of a used range, None when the
+    /// locations don't have room for the count
+    fn first_free(&self,count:u32) -> Option<u32> {
+
+        std::iter::once(0).chain(self.used.iter().map(|(_,e)| *e))
+            .filter_map(|l| u32::try_from(l).ok())
+            .filter(|l| self.is_free(*l,count))
+            .min()
+
+    }
+    //
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Give a location to every input and output declared without one. The first free
+    /// locations are used in the order of the declarations
+    pub fn assign_locations(&self) -> Vec<LocationAssignment> {
+
+        let mut assignments = assign(self,false,|_| None);
+        assignments.extend(assign(self,true,|_| None));
+
+        assignments
+
+    }
+    //
+    /// The source with a 'layout(location = N)' written in the declarations of the
+    /// assignments of this stage. The other declarations are kept as they are
+    ///     example:
+    ///         'in vec3 color;'                    => 'layout(location = 1) in vec3 color;'
+    ///         'layout (component = 2) out float a;' => 'layout (location = 0, component = 2) out float a;'
+    ///
+    pub fn write_locations(&self,assignments:&[LocationAssignment]) -> String {
+
+        let mut source = self.source().to_string();
+
+        let mut mine:Vec<&LocationAssignment> = assignments.iter().filter(|a| a.stage == self.shader_type()).collect();
+        //
+        // from the end so the spans before stay right
+        mine.sort_by_key(|a| std::cmp::Reverse(a.span.start));
+
+        for assignment in mine {
+
+            let declaration = &source[assignment.span.start..assignment.span.end];
+
+            let layout = declaration
+                .find("layout")
+                .and_then(|i| declaration[i..].find('(').map(|p| i + p + 1))
+                .filter(|_| self.declares_layout(assignment));
+
+            match layout {
+
+                Some(open) => source.insert_str(
+                    assignment.span.start + open,
+                    &format!("location = {}, ",assignment.location)
+                ),
+                None => source.insert_str(
+                    assignment.span.start,
+                    &format!("layout(location = {}) ",assignment.location)
+                )
+
+            }
+
+        }
+
+        source
+
+    }
+    //
+    /// Check if the variable of an assignment already has a layout qualifier
+    fn declares_layout(&self,assignment:&LocationAssignment) -> bool {
+
+        self.variables()
+            .find(|v| v.span() == assignment.span)
+            .is_some_and(|v| v.storage_qualifiers().iter().any(|q| matches!(q,StorageQualifier::LAYOUT(_))))
+
+    }
+    //
+}
+//
+//
+impl ShaderProgram {
+    //
+    /// Give a location to every input and output of the stages declared without one. An
+    /// input takes the location of the output of the same name in the previous stage and
+    /// an output the location of the input of the same name in the next stage, so the
+    /// stages still match. The blocks without location are not given one
+    pub fn assign_locations(&self) -> Vec<LocationAssignment> {
+
+        let stages:Vec<&ShaderFileInfo> = self.stages().collect();
+        let mut assignments:Vec<LocationAssignment> = Vec::new();
+
+        for (index,shader) in stages.iter().enumerate() {
+
+            let previous = index.checked_sub(1).map(|i| stages[i]);
+            let next = stages.get(index + 1);
+
+            let inputs = assign(shader,false,|name| {
+
+                let output = previous?.variables().find(|v| v.has_qualifier(&StorageQualifier::OUT) && v.name() == name)?;
+
+                output.location().or_else(|| {
+                    assignments.iter()
+                        .find(|a| a.output && a.stage == previous.and_then(|p| p.shader_type()) && a.name == name)
+                        .map(|a| a.location)
+                })
+
+            });
+
+            let outputs = assign(shader,true,|name| {
+
+                next?.variables()
+                    .find(|v| v.has_qualifier(&StorageQualifier::IN) && v.name() == name)?
+                    .location()
+
+            });
+
+            assignments.extend(inputs);
+            assignments.extend(outputs);
+
+        }
+
+        assignments
+
+    }
+    //
+}
+//
+//
+/// Give a location to the inputs or the outputs of a shader declared without one
+///
+/// # parameters
+///
+/// * shader - the shader
+/// * output - the outputs when true, the inputs otherwise
+/// * preferred - the location a variable should have to match the other stage, it is
+///   used when it is free
+///
+fn assign<F>(shader:&ShaderFileInfo,output:bool,preferred:F) -> Vec<LocationAssignment>
+where
+    F: Fn(&str) -> Option<u32>
+{
+
+    let storage = match output { true => StorageQualifier::OUT, false => StorageQualifier::IN };
+    let vertex_input = !output && shader.shader_type() == Some(ShaderType::VERTEX);
+
+    let count = |var:&ShaderVariables| {
+
+        let array = interface_array(shader,&storage,var.array_size(),var.has_qualifier(&StorageQualifier::PATCH));
+
+        location_count(var.var_type(),array.as_ref(),vertex_input)
+
+    };
+
+    let variables:Vec<&ShaderVariables> = shader.variables()
+        .filter(|v| v.has_qualifier(&storage) && !v.name().starts_with("gl_"))
+        .collect();
+
+    let mut slots = Slots::default();
+    //
+    // the locations already given, a variable with a component only takes its components
+    for var in variables.iter() {
+
+        let (Some(location),Some(n)) = (var.location(),count(var)) else { continue };
+
+        slots.take(location,n,components(var));
+
+    }
+
+    for block in shader.blocks().filter(|b| b.has_qualifier(&storage)) {
+
+        let Some(location) = block.layout_value(LayoutVarType::LOCATION) else { continue };
+
+        slots.take(location,block_location_count(block),0b1111);
+
+    }
+    //
+    // the variables that match the other stage first so the others don't take their place
+    let mut pending:Vec<(&ShaderVariables,u32,Option<u32>)> = variables.iter()
+        .filter(|v| v.location().is_none())
+        .filter_map(|v| count(v).map(|n| (*v,n,preferred(v.name()))))
+        .collect();
+
+    pending.sort_by_key(|(_,_,preferred)| preferred.is_none());
+
+    let mut assignments:Vec<LocationAssignment> = Vec::new();
+
+    for (var,n,preferred) in pending {
+
+        let Some(location) = preferred
+            .filter(|l| slots.is_free(*l,n))
+            .or_else(|| slots.first_free(n)) else { continue };
+
+        slots.take(location,n,0b1111);
+
+        assignments.push(LocationAssignment {
+            name: var.name().to_string(),
+            stage: shader.shader_type(),
+            output,
+            location,
+            count: n,
+            span: var.span()
+        });
+
+    }
+
+    assignments.sort_by_key(|a| a.span.start);
+
+    assignments
+
+}
+//
+//
+/// The number of locations the members of a block take
+pub(crate) fn block_location_count(block:&InterfaceBlock) -> u32 {
+
+    block.members().iter()
+        .filter_map(|m| location_count(m.var_type(),m.array_size(),false))
+        .fold(0,u32::saturating_add)
+
+}
+//
+//
+/// The components of its locations a variable uses
+pub(crate) fn components(var:&ShaderVariables) -> u8 {
+
+    let Some(component) = var.layout_value(LayoutVarType::COMPONENT) else { return 0b1111 };
+
+    match var.var_type().dimensions() {
+
+        Some((1,rows)) if var.array_size().is_none() && component < 4 => {
+
+            let size = match var.var_type().scalar_type() == Some(ScalarType::DOUBLE) { true => 2 * rows, false => rows };
+
+            (((1u32 << size.min(4)) - 1) << component) as u8 & 0b1111
+
+        },
+        _ => 0b1111
+
+    }
+
+}
+//
+//
@@ -0,0 +1,191 @@
+//
+// ------------------------------------------------------------------------------------------
+// Pragmas
+//
+// Every '#pragma' line is kept with its arguments cut in tokens. The pragmas of the GLSL
+// specification are understood and an application can register a parser for the pragmas
+// of its own namespaces ('#pragma blend alpha', '#pragma cull none')
+//
+use std::any::Any;
+use crate::{EParser,ShaderFileInfo};
+use crate::lexer::{Span,Token,TokenKind,tokenize};
+use crate::datatype::{DeclarationLine,PreprocessorDeclarationType};
+//
+//
+/// A '#pragma' line
+///     example:
+///         '#pragma blend alpha'
+///             - namespace => 'blend'
+///             - text => 'alpha'
+///             - arguments => [IDENTIFIER(alpha)]
+///
+#[derive(Debug,PartialEq,Clone)]
+pub struct Pragma {
+
+    // the first word, empty for a '#pragma' alone
+    pub namespace:  String,
+    // what follows the namespace as written
+    pub text:       String,
+    // the text cut in tokens. Empty when glsl can't cut it ('"a string"', '0..1')
+    pub arguments:  Vec<Token>,
+    pub span:       Span,
+
+}
+//
+impl Pragma {
+    //
+    /// How the GLSL specification understands the pragma, None if it is not one of the
+    /// standard pragmas or if it is written wrong (the compilers ignore it then)
+    pub fn standard(&self) -> Option<StandardPragma> {
+
+        let words:Vec<&str> = self.arguments.iter().map(|t| match &t.kind {
+
+            TokenKind::IDENTIFIER(name) => name.as_str(),
+            TokenKind::SYMBOL(symbol) => symbol,
+            TokenKind::LITERAL(_) => ""
+
+        }).collect();
+
+        match (self.namespace.as_str(),words.as_slice()) {
+
+            ("optimize",["(","on",")"]) => Some(StandardPragma::OPTIMIZE(true)),
+            ("optimize",["(","off",")"]) => Some(StandardPragma::OPTIMIZE(false)),
+            ("debug",["(","on",")"]) => Some(StandardPragma::DEBUG(true)),
+            ("debug",["(","off",")"]) => Some(StandardPragma::DEBUG(false)),
+            // the specification writes it in the STDGL namespace, most shaders without
+            ("STDGL",["invariant","(","all",")"]) | ("invariant",["(","all",")"]) => Some(StandardPragma::INVARIANT_ALL),
+            _ => None
+
+        }
+
+    }
+    //
+}
+//
+//
+/// The pragmas that the GLSL specification defines
+#[allow(non_camel_case_types)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum StandardPragma {
+
+    // '#pragma optimize(on)'
+    OPTIMIZE(bool),
+    // '#pragma debug(off)'
+    DEBUG(bool),
+    // '#pragma STDGL invariant(all)', every output is invariant
+    INVARIANT_ALL,
+
+}
+//
+//
+/// A parser registered by an application for the pragmas of a namespace
+pub(crate) type PragmaParser = Box<dyn Fn(&Pragma) -> Result<Box<dyn Any + Send + Sync>,String> + Send + Sync>;
+//
+//
+/// Cut a pragma line in its namespace and its arguments. The compilers ignore the pragmas
+/// they don't know so a pragma is never an error
+///
+/// # parameters
+///
+/// * line - the whole line, example: '#pragma cull none'
+/// * span - where the line is in the source
+///
+pub(crate) fn parse_pragma(line:&str,span:Span) -> Pragma {
+
+    let start = line.find("pragma").map(|i| i + "pragma".len()).unwrap_or(line.len());
+    let content = line[start..].trim_start();
+    let content_start = span.start + line.len() - content.len();
+
+    let name_end = content
+        .find(|c:char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(content.len());
+
+    let namespace = content[..name_end].to_string();
+    let text = content[name_end..].trim();
+    //
+    // the offset of the text in the source so the spans of the tokens point in it
+    let text_start = content_start + name_end + (content[name_end..].len() - content[name_end..].trim_start().len());
+
+    Pragma {
+        namespace,
+        text: text.to_string(),
+        arguments: tokenize(text,text_start).unwrap_or_default(),
+        span
+    }
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Give a parser for the pragmas of a namespace. Every pragma of the namespace found by
+    /// 'parse_line' after it is parsed, and an error of the parser stops the parsing
+    ///
+    /// # parameters
+    ///
+    /// * namespace - the first word of the pragmas ('blend' for '#pragma blend alpha')
+    /// * parser - create the value of the application from the text or the tokens of
+    ///   the pragma
+    ///
+    pub fn register_pragma<T,F>(&mut self,namespace:&str,parser:F)
+    where
+        T: Any + Send + Sync,
+        F: Fn(&Pragma) -> Result<T,String> + Send + Sync + 'static
+    {
+
+        let parser:PragmaParser = Box::new(move |pragma| parser(pragma).map(|v| Box::new(v) as Box<dyn Any + Send + Sync>));
+
+        self.pragma_parsers.insert(namespace.to_string(),parser);
+
+    }
+    //
+    /// Every pragma of the shader in the order they are written
+    pub fn pragmas(&self) -> impl Iterator<Item = &Pragma> {
+
+        self.declarations().iter().filter_map(|d| match d {
+
+            DeclarationLine::PREPROCESSOR(PreprocessorDeclarationType::PRAGMA(pragma)) => Some(pragma),
+            _ => None
+
+        })
+
+    }
+    //
+    /// The values created by the parser registered for a namespace, in the order the
+    /// pragmas are written. Nothing if the values are not of the type asked
+    pub fn pragma_values<'a,T:Any>(&'a self,namespace:&'a str) -> impl Iterator<Item = &'a T> {
+
+        self.pragma_values
+            .iter()
+            .filter(move |(n,_)| n == namespace)
+            .filter_map(|(_,value)| value.downcast_ref::<T>())
+
+    }
+    //
+    /// The value of the last pragma of a namespace, it is the one that apply
+    pub fn pragma_value<T:Any>(&self,namespace:&str) -> Option<&T> {
+
+        self.pragma_values.iter().rev().find(|(n,_)| n == namespace).and_then(|(_,value)| value.downcast_ref::<T>())
+
+    }
+    //
+    /// Record a pragma and give it to the parser of its namespace
+    pub(crate) fn push_pragma(&mut self,pragma:Pragma) -> Result<(),EParser> {
+
+        if let Some(parser) = self.pragma_parsers.get(&pragma.namespace) {
+
+            let value = parser(&pragma).map_err(|e| EParser::PRAGMA(pragma.namespace.clone(),e))?;
+
+            self.pragma_values.push((pragma.namespace.clone(),value));
+
+        }
+
+        self.push_declaration(DeclarationLine::PREPROCESSOR(PreprocessorDeclarationType::PRAGMA(pragma)));
+
+        Ok(())
+
+    }
+    //
+}
+//
+//
@@ -0,0 +1,455 @@
+//
+// ------------------------------------------------------------------------------------------
+// Programs
+//
+// The stages of a pipeline linked together. The outputs of a stage are the inputs of the
+// next one and a driver refuses to link them when they don't match
+//
+use std::fmt;
+use crate::{EParser,ShaderFileInfo,load_file};
+use crate::lexer::Span;
+use crate::datatype::{ShaderType,ShaderVariables,InterfaceBlock,StorageQualifier,InterpolationQualifier,ArraySize};
+//
+//
+/// Several shaders of different stages used together
+pub struct ShaderProgram {
+
+    // sorted in the order of the pipeline
+    stages: Vec<ShaderFileInfo>,
+
+}
+//
+//
+/// Where an input or an output is declared
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub struct InterfaceLocation {
+
+    pub stage:  ShaderType,
+    pub span:   Span,
+    // both start at 1
+    pub line:   usize,
+    pub column: usize,
+
+}
+//
+//
+/// Why an output and an input don't match
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Clone)]
+pub enum MismatchKind {
+
+    // an input that no output of the previous stage writes
+    UNMATCHED_INPUT,
+    // an output that the next stage doesn't read. The drivers accept it
+    UNUSED_OUTPUT,
+    // the type of the output and of the input
+    TYPE(String,String),
+    ARRAY(Option<ArraySize>,Option<ArraySize>),
+    // None is the default interpolation
+    INTERPOLATION(Option<InterpolationQualifier>,Option<InterpolationQualifier>),
+    // matched by name but with two different locations
+    LOCATION(u32,u32),
+    // the members of two blocks of the same name are not the same
+    BLOCK(String),
+
+}
+//
+//
+/// An input or an output of a stage that doesn't match the other side
+#[derive(Debug,PartialEq,Clone)]
+pub struct InterfaceMismatch {
+
+    // the name of the variable or of the block
+    pub name:   String,
+    pub kind:   MismatchKind,
+    pub output: Option<InterfaceLocation>,
+    pub input:  Option<InterfaceLocation>,
+
+}
+//
+impl InterfaceMismatch {
+    //
+    /// Check if the driver refuses to link the program because of it
+    pub fn is_error(&self) -> bool { self.kind != MismatchKind::UNUSED_OUTPUT }
+    //
+}
+//
+impl fmt::Display for InterfaceMismatch {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        let array = |size:&Option<ArraySize>| match size {
+
+            Some(ArraySize::SIZED(n)) => format!("an array of {}",n),
+            Some(ArraySize::UNSIZED) => "an array without size".to_string(),
+            None => "not an array".to_string()
+
+        };
+
+        let interpolation = |q:&Option<InterpolationQualifier>| q.map(|q| q.as_str().to_string()).unwrap_or("smooth".to_string());
+
+        match &self.kind {
+
+            MismatchKind::UNMATCHED_INPUT => write!(f,"no output of the previous stage for the input '{}'",self.name)?,
+
+            MismatchKind::UNUSED_OUTPUT => write!(f,"the output '{}' is not read by the next stage",self.name)?,
+
+            MismatchKind::TYPE(output,input) =>
+                write!(f,"'{}' is a {} output but a {} input",self.name,output,input)?,
+
+            MismatchKind::ARRAY(output,input) =>
+                write!(f,"'{}' is {} as output but {} as input",self.name,array(output),array(input))?,
+
+            MismatchKind::INTERPOLATION(output,input) =>
+                write!(f,"'{}' is {} as output but {} as input",self.name,interpolation(output),interpolation(input))?,
+
+            MismatchKind::LOCATION(output,input) =>
+                write!(f,"'{}' is at location {} as output but {} as input",self.name,output,input)?,
+
+            MismatchKind::BLOCK(reason) => write!(f,"the block '{}' is not the same in both stages: {}",self.name,reason)?
+
+        }
+
+        for location in [self.output,self.input].iter().flatten() {
+            write!(f," ({} shader {}:{})",location.stage.as_str(),location.line,location.column)?;
+        }
+
+        Ok(())
+
+    }
+
+}
+//
+//
+/// Load the shaders of a program, one file per stage
+///
+/// # parameters
+///
+/// * paths - the files, their extension give their stage
+///   example: ["shader.vert","shader.frag"]
+///
+/// # Error causes
+///
+/// * A file can't be loaded
+/// * Two files are of the same stage
+/// * A compute shader is with other stages
+///
+pub fn load_program(paths:&[&str]) -> Result<ShaderProgram,EParser> {
+
+    let mut program = ShaderProgram::new();
+
+    for path in paths {
+        program.add_stage(load_file(path)?)?;
+    }
+
+    Ok(program)
+
+}
+//
+//
+impl Default for ShaderProgram {
+
+    fn default() -> Self { Self::new() }
+
+}
+//
+impl ShaderProgram {
+    //
+    pub fn new() -> Self { ShaderProgram { stages: Vec::new() } }
+    //
+    /// Add a shader to the program
+    ///
+    /// # Error causes
+    ///
+    /// * The stage of the shader is not known
+    /// * The program already has a shader of this stage
+    /// * A compute shader is with other stages
+    ///
+    pub fn add_stage(&mut self,shader:ShaderFileInfo) -> Result<(),EParser> {
+
+        let stage = match shader.shader_type() {
+
+            Some(stage) => stage,
+            None => return Err(EParser::PROGRAM("the stage of a shader is not known".to_string()))
+
+        };
+
+        if self.stage(stage).is_some() {
+            return Err(EParser::PROGRAM(format!("there is already a {} shader",stage.as_str())));
+        }
+
+        let compute = stage == ShaderType::COMPUTE || self.stage(ShaderType::COMPUTE).is_some();
+
+        if compute && !self.stages.is_empty() {
+            return Err(EParser::PROGRAM("a compute shader is alone in its program".to_string()));
+        }
+
+        let index = self.stages.iter().position(|s| pipeline_order(s) > pipeline_order(&shader)).unwrap_or(self.stages.len());
+
+        self.stages.insert(index,shader);
+
+        Ok(())
+
+    }
+    //
+    /// The shaders in the order of the pipeline
+    pub fn stages(&self) -> impl Iterator<Item = &ShaderFileInfo> { self.stages.iter() }
+    //
+    /// The shader of a stage
+    pub fn stage(&self,stage:ShaderType) -> Option<&ShaderFileInfo> {
+
+        self.stages.iter().find(|s| s.shader_type() == Some(stage))
+
+    }
+    //
+    /// Compare the outputs of every stage with the inputs of the next one. An input and an
+    /// output match when they have the same location, or the same name when the input has
+    /// no location. The blocks match by the name of the block
+    pub fn check_interfaces(&self) -> Vec<InterfaceMismatch> {
+
+        let mut mismatches:Vec<InterfaceMismatch> = Vec::new();
+
+        for pair in self.stages.windows(2) {
+
+            let link = Link { producer: &pair[0], consumer: &pair[1] };
+
+            link.check_variables(&mut mismatches);
+            link.check_blocks(&mut mismatches);
+
+        }
+
+        mismatches
+
+    }
+    //
+}
+//
+//
+/// Two stages that follow each other in the pipeline
+struct Link<'a> {
+
+    producer: &'a ShaderFileInfo,
+    consumer: &'a ShaderFileInfo,
+
+}
+//
+impl Link<'_> {
+    //
+    fn check_variables(&self,mismatches:&mut Vec<InterfaceMismatch>) {
+
+        let outputs:Vec<&ShaderVariables> = self.producer.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::OUT) && !v.name().starts_with("gl_"))
+            .collect();
+
+        let inputs = self.consumer.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::IN) && !v.name().starts_with("gl_"));
+
+        let mut used = vec![false;outputs.len()];
+
+        for input in inputs {
+
+            let by_location = input.location().and_then(|l| outputs.iter().position(|o| o.location() == Some(l)));
+            let found = by_location.or_else(|| outputs.iter().position(|o| o.name() == input.name()));
+
+            let output = match found {
+
+                Some(i) => { used[i] = true; outputs[i] },
+                None => {
+
+                    mismatches.push(self.mismatch(input.name(),MismatchKind::UNMATCHED_INPUT,None,Some(input.span())));
+                    continue;
+
+                }
+
+            };
+
+            let output_array = interface_array(self.producer,&StorageQualifier::OUT,output.array_size(),output.has_qualifier(&StorageQualifier::PATCH));
+            let input_array = interface_array(self.consumer,&StorageQualifier::IN,input.array_size(),input.has_qualifier(&StorageQualifier::PATCH));
+
+            let kind = if output.var_type().to_string() != input.var_type().to_string() {
+
+                Some(MismatchKind::TYPE(output.var_type().to_string(),input.var_type().to_string()))
+
+            } else if output_array != input_array {
+
+                Some(MismatchKind::ARRAY(output_array,input_array))
+
+            } else if interpolation(output.interpolation()) != interpolation(input.interpolation()) {
+
+                Some(MismatchKind::INTERPOLATION(output.interpolation(),input.interpolation()))
+
+            } else {
+
+                match (output.location(),input.location()) {
+
+                    (Some(o),Some(i)) if o != i => Some(MismatchKind::LOCATION(o,i)),
+                    _ => None
+
+                }
+
+            };
+
+            if let Some(kind) = kind {
+                mismatches.push(self.mismatch(input.name(),kind,Some(output.span()),Some(input.span())));
+            }
+
+        }
+
+        for (output,_) in outputs.iter().zip(used).filter(|(_,used)| !used) {
+            mismatches.push(self.mismatch(output.name(),MismatchKind::UNUSED_OUTPUT,Some(output.span()),None));
+        }
+
+    }
+    //
+    fn check_blocks(&self,mismatches:&mut Vec<InterfaceMismatch>) {
+
+        let outputs:Vec<&InterfaceBlock> = self.producer.blocks()
+            .filter(|b| b.has_qualifier(&StorageQualifier::OUT) && !b.name().starts_with("gl_"))
+            .collect();
+
+        let inputs = self.consumer.blocks()
+            .filter(|b| b.has_qualifier(&StorageQualifier::IN) && !b.name().starts_with("gl_"));
+
+        let mut used = vec![false;outputs.len()];
+
+        for input in inputs {
+
+            let output = match outputs.iter().position(|o| o.name() == input.name()) {
+
+                Some(i) => { used[i] = true; outputs[i] },
+                None => {
+
+                    mismatches.push(self.mismatch(input.name(),MismatchKind::UNMATCHED_INPUT,None,Some(input.span())));
+                    continue;
+
+                }
+
+            };
+
+            let output_array = interface_array(self.producer,&StorageQualifier::OUT,output.array_size(),output.has_qualifier(&StorageQualifier::PATCH));
+            let input_array = interface_array(self.consumer,&StorageQualifier::IN,input.array_size(),input.has_qualifier(&StorageQualifier::PATCH));
+
+            let kind = match output_array != input_array {
+
+                true => Some(MismatchKind::ARRAY(output_array,input_array)),
+                false => compare_members(output.members(),input.members()).map(MismatchKind::BLOCK)
+
+            };
+
+            if let Some(kind) = kind {
+                mismatches.push(self.mismatch(input.name(),kind,Some(output.span()),Some(input.span())));
+            }
+
+        }
+
+        for (output,_) in outputs.iter().zip(used).filter(|(_,used)| !used) {
+            mismatches.push(self.mismatch(output.name(),MismatchKind::UNUSED_OUTPUT,Some(output.span()),None));
+        }
+
+    }
+    //
+    fn mismatch(&self,name:&str,kind:MismatchKind,output:Option<Span>,input:Option<Span>) -> InterfaceMismatch {
+
+        InterfaceMismatch {
+            name: name.to_string(),
+            kind,
+            output: output.and_then(|span| location(self.producer,span)),
+            input: input.and_then(|span| location(self.consumer,span))
+        }
+
+    }
+    //
+}
+//
+//
+/// The array size of an input or an output without the array of the vertices of a
+/// primitive or a patch, the outer array that the per-vertex inputs and outputs have
+pub(crate) fn interface_array(
+    shader:     &ShaderFileInfo,
+    storage:    &StorageQualifier,
+    size:       Option<&ArraySize>,
+    patch:      bool) -> Option<ArraySize> {
+
+    let per_vertex = match storage {
+
+        StorageQualifier::OUT => shader.shader_type() == Some(ShaderType::TESSCONTROL),
+        _ => matches!(
+            shader.shader_type(),
+            Some(ShaderType::TESSCONTROL | ShaderType::TESSEVALUATION | ShaderType::GEOMETRY)
+        )
+
+    };
+
+    match per_vertex && !patch {
+
+        true => None,
+        false => size.cloned()
+
+    }
+
+}
+//
+//
+/// Describe the first difference between the members of two blocks
+pub(crate) fn compare_members(output:&[ShaderVariables],input:&[ShaderVariables]) -> Option<String> {
+
+    if output.len() != input.len() {
+        return Some(format!("{} members and {} members",output.len(),input.len()));
+    }
+
+    for (o,i) in output.iter().zip(input) {
+
+        if o.name() != i.name() {
+            return Some(format!("the member '{}' is '{}' in the other",o.name(),i.name()));
+        }
+
+        if o.var_type().to_string() != i.var_type().to_string() || o.array_size() != i.array_size() {
+            return Some(format!("the member '{}' has not the same type",o.name()));
+        }
+
+        if interpolation(o.interpolation()) != interpolation(i.interpolation()) {
+            return Some(format!("the member '{}' has not the same interpolation",o.name()));
+        }
+
+    }
+
+    None
+
+}
+//
+//
+/// The interpolation used when none is written
+fn interpolation(qualifier:Option<InterpolationQualifier>) -> InterpolationQualifier {
+
+    qualifier.unwrap_or(InterpolationQualifier::SMOOTH)
+
+}
+//
+//
+/// The stage, the line and the column of a span of a shader
+pub(crate) fn location(shader:&ShaderFileInfo,span:Span) -> Option<InterfaceLocation> {
+
+    let (line,column) = span.line_column(shader.source());
+
+    shader.shader_type().map(|stage| InterfaceLocation { stage, span, line, column })
+
+}
+//
+//
+/// The position of a stage in the pipeline
+fn pipeline_order(shader:&ShaderFileInfo) -> usize {
+
+    match shader.shader_type() {
+
+        Some(ShaderType::VERTEX) => 0,
+        Some(ShaderType::TESSCONTROL) => 1,
+        Some(ShaderType::TESSEVALUATION) => 2,
+        Some(ShaderType::GEOMETRY) => 3,
+        Some(ShaderType::FRAGMENT) => 4,
+        _ => 5
+
+    }
+
+}
+//
+//
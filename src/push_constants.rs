@@ -0,0 +1,216 @@
+//
+// ------------------------------------------------------------------------------------------
+// Push constants
+//
+// The block of vulkan glsl declared with 'layout(push_constant)'. It has no binding, the
+// application writes its bytes in the command buffer. The stages of a program share the
+// same bytes so the members they both see must be the same, and the bytes are few
+//
+use std::fmt;
+use crate::ShaderFileInfo;
+use crate::lexer::Span;
+use crate::limits::Limits;
+use crate::program::ShaderProgram;
+use crate::layout::{MemoryLayout,member_layouts};
+use crate::datatype::{ShaderType,VariableType,ArraySize};
+//
+//
+/// A member of the push constant block
+#[derive(Debug,PartialEq,Clone)]
+pub struct PushConstantMember {
+
+    pub name:       String,
+    pub var_type:   VariableType,
+    pub array_size: Option<ArraySize>,
+    // the one of the offset qualifier or the one of the std430 layout
+    pub offset:     usize,
+    pub size:       usize,
+
+}
+//
+//
+/// The push constant block of a shader
+#[derive(Debug,PartialEq,Clone)]
+pub struct PushConstantBlock {
+
+    pub name:           String,
+    pub instance_name:  Option<String>,
+    pub members:        Vec<PushConstantMember>,
+    // the bytes up to the end of the last member
+    pub size:           usize,
+    pub span:           Span,
+
+}
+//
+//
+/// What is wrong with the push constants
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum PushConstantIssueKind {
+
+    // the bytes of the block and the bytes there are
+    TOO_LARGE(usize,u32),
+    // a stage has only one push constant block: the name of the first one
+    SEVERAL_BLOCKS(String),
+    // a member overlaps a member of another stage at another offset or of another type:
+    // the other stage and its member
+    MEMBER_MISMATCH(ShaderType,String),
+
+}
+//
+//
+/// A push constant block or one of its members that breaks a rule
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct PushConstantIssue {
+
+    // the block for the sizes and the blocks, the member for the members
+    pub name:   String,
+    pub kind:   PushConstantIssueKind,
+    pub stage:  Option<ShaderType>,
+
+}
+//
+impl fmt::Display for PushConstantIssue {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        match &self.kind {
+
+            PushConstantIssueKind::TOO_LARGE(size,max) =>
+                write!(f,"the push constants '{}' take {} bytes but there are {}",self.name,size,max)?,
+
+            PushConstantIssueKind::SEVERAL_BLOCKS(first) =>
+                write!(f,"'{}' is a second push constant block, '{}' is already one",self.name,first)?,
+
+            PushConstantIssueKind::MEMBER_MISMATCH(stage,other) =>
+                write!(f,"'{}' doesn't match '{}' of the {} shader",self.name,other,stage.as_str())?
+
+        }
+
+        match self.stage {
+
+            Some(stage) => write!(f," ({} shader)",stage.as_str()),
+            None => Ok(())
+
+        }
+
+    }
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// The push constant block of the shader with the offsets of its members, None when
+    /// there is none or when a member can't be in it (an array without size). The layout
+    /// is std430 unless the block has another
+    pub fn push_constants(&self) -> Option<PushConstantBlock> {
+
+        let block = self.blocks().find(|b| b.is_push_constant())?;
+        let layouts = member_layouts(block.members(),block.memory_layout().unwrap_or(MemoryLayout::STD430))?;
+
+        let members = block.members().iter().zip(layouts).map(|(member,(offset,layout))| Some(PushConstantMember {
+            name: member.name().to_string(),
+            var_type: member.var_type().clone(),
+            array_size: member.array_size().cloned(),
+            offset,
+            size: layout.size?
+        })).collect::<Option<Vec<PushConstantMember>>>()?;
+
+        Some(PushConstantBlock {
+            name: block.name().to_string(),
+            instance_name: block.instance_name().map(|n| n.to_string()),
+            size: members.iter().map(|m| m.offset + m.size).max().unwrap_or(0),
+            members,
+            span: block.span()
+        })
+
+    }
+    //
+    /// Check that the shader has one push constant block at most and that it stays within
+    /// the bytes of the limits
+    pub fn check_push_constants(&self,limits:&Limits) -> Vec<PushConstantIssue> {
+
+        let mut issues:Vec<PushConstantIssue> = Vec::new();
+        let mut blocks = self.blocks().filter(|b| b.is_push_constant());
+
+        if let Some(first) = blocks.next() {
+
+            issues.extend(blocks.map(|b| PushConstantIssue {
+                name: b.name().to_string(),
+                kind: PushConstantIssueKind::SEVERAL_BLOCKS(first.name().to_string()),
+                stage: self.shader_type()
+            }));
+
+        }
+
+        if let Some(block) = self.push_constants().filter(|b| b.size > limits.max_push_constants_size as usize) {
+
+            issues.push(PushConstantIssue {
+                name: block.name,
+                kind: PushConstantIssueKind::TOO_LARGE(block.size,limits.max_push_constants_size),
+                stage: self.shader_type()
+            });
+
+        }
+
+        issues
+
+    }
+    //
+}
+//
+//
+impl ShaderProgram {
+    //
+    /// Check the push constants of every stage, and that the members two stages both see
+    /// start at the same offset and have the same type. The names can differ
+    pub fn check_push_constants(&self,limits:&Limits) -> Vec<PushConstantIssue> {
+
+        let mut issues:Vec<PushConstantIssue> = self.stages().flat_map(|s| s.check_push_constants(limits)).collect();
+
+        let blocks:Vec<(Option<ShaderType>,PushConstantBlock)> = self.stages()
+            .filter_map(|s| Some((s.shader_type(),s.push_constants()?)))
+            .collect();
+
+        for (i,(stage,block)) in blocks.iter().enumerate() {
+
+            for (other_stage,other) in blocks[i + 1..].iter() {
+
+                let Some(other_stage) = *other_stage else { continue };
+
+                for member in block.members.iter() {
+
+                    let overlap = other.members.iter().find(|m| {
+                        m.offset < member.offset + member.size && member.offset < m.offset + m.size
+                    });
+
+                    let Some(overlap) = overlap else { continue };
+
+                    let same = overlap.offset == member.offset
+                        && overlap.var_type == member.var_type
+                        && overlap.array_size == member.array_size;
+
+                    if !same {
+
+                        issues.push(PushConstantIssue {
+                            name: member.name.clone(),
+                            kind: PushConstantIssueKind::MEMBER_MISMATCH(other_stage,overlap.name.clone()),
+                            stage: *stage
+                        });
+
+                    }
+
+                }
+
+            }
+
+        }
+
+        issues
+
+    }
+    //
+}
+//
+//
@@ -0,0 +1,242 @@
+//
+// ------------------------------------------------------------------------------------------
+// Binding remapping
+//
+// Move the resources of a shader to other bindings and descriptor sets, to port an opengl
+// shader to vulkan for example. Only the layout qualifiers of the resources moved are
+// rewritten, the rest of the source is kept as it is
+//
+use std::collections::HashMap;
+use crate::ShaderFileInfo;
+use crate::lexer::Span;
+use crate::resource::ResourceKind;
+use crate::datatype::{DeclarationLine,StorageQualifier};
+//
+//
+/// How to choose the new set and binding of the resources
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Clone)]
+pub enum BindingPolicy {
+
+    // the set and the binding of a resource by its name (the name of the block for the
+    // blocks). The resources not in it are not moved
+    TABLE(HashMap<String,(u32,u32)>),
+    // a set by kind of resource, the set of a kind is its index in the list. The bindings
+    // of a set are given from 0 in the order of the declarations, an array takes a single
+    // binding as in vulkan
+    PACK(Vec<ResourceKind>),
+
+}
+//
+//
+/// The new set and binding of a resource
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct BindingRemap {
+
+    pub name:       String,
+    pub kind:       ResourceKind,
+    pub set:        u32,
+    pub binding:    u32,
+    // the declaration of the resource
+    pub span:       Span,
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Choose the set and the binding of the resources that take a binding: the opaque
+    /// uniforms and the uniform and storage blocks. The uniforms of the default block and
+    /// the push constants have none
+    pub fn remap_bindings(&self,policy:&BindingPolicy) -> Vec<BindingRemap> {
+
+        let mut remaps:Vec<BindingRemap> = Vec::new();
+        // the next binding free in each set
+        let mut next:HashMap<u32,u32> = HashMap::new();
+
+        for (name,kind,span) in self.binding_resources() {
+
+            let (set,binding) = match policy {
+
+                BindingPolicy::TABLE(table) => match table.get(name) {
+
+                    Some(value) => *value,
+                    None => continue
+
+                },
+
+                BindingPolicy::PACK(kinds) => {
+
+                    let Some(set) = kinds.iter().position(|k| *k == kind) else { continue };
+
+                    let set = set as u32;
+                    let binding = next.entry(set).or_default();
+                    let first = *binding;
+
+                    *binding += 1;
+
+                    (set,first)
+
+                }
+
+            };
+
+            remaps.push(BindingRemap { name: name.to_string(), kind, set, binding, span });
+
+        }
+
+        remaps
+
+    }
+    //
+    /// The source with the set and the binding of the remaps written in the layout
+    /// qualifiers of their declarations
+    ///     example:
+    ///         'uniform sampler2D albedo;'                 => 'layout(set = 1, binding = 0) uniform sampler2D albedo;'
+    ///         'layout (std140, binding = 3) uniform Camera' => 'layout (std140, binding = 0, set = 0) uniform Camera'
+    ///
+    pub fn write_bindings(&self,remaps:&[BindingRemap]) -> String {
+
+        let mut source = self.source().to_string();
+        let mut edits:Vec<(usize,usize,String)> = Vec::new();
+
+        for remap in remaps {
+
+            let declaration = &source[remap.span.start..remap.span.end];
+
+            match layout_parentheses(declaration) {
+
+                Some((open,close)) => edits.extend(layout_edits(
+                    declaration,
+                    remap.span.start,
+                    (open,close),
+                    &[("set",remap.set),("binding",remap.binding)]
+                )),
+                None => edits.push((
+                    remap.span.start,
+                    remap.span.start,
+                    format!("layout(set = {}, binding = {}) ",remap.set,remap.binding)
+                ))
+
+            }
+
+        }
+        //
+        // from the end so the positions before stay right
+        edits.sort_by_key(|(start,_,_)| std::cmp::Reverse(*start));
+
+        for (start,end,text) in edits {
+            source.replace_range(start..end,&text);
+        }
+
+        source
+
+    }
+    //
+    /// The samplers and the blocks that take a binding in the order they are declared
+    fn binding_resources(&self) -> Vec<(&str,ResourceKind,Span)> {
+
+        self.declarations().iter().filter_map(|d| match d {
+
+            DeclarationLine::VARIABLE(v) if v.has_qualifier(&StorageQualifier::UNIFORM) && v.var_type().is_opaque() =>
+                Some((v.name(),ResourceKind::OPAQUE,v.span())),
+
+            DeclarationLine::BLOCK(b) if b.has_qualifier(&StorageQualifier::UNIFORM) && !b.is_push_constant() =>
+                Some((b.name(),ResourceKind::UNIFORM_BLOCK,b.span())),
+
+            DeclarationLine::BLOCK(b) if b.has_qualifier(&StorageQualifier::BUFFER) =>
+                Some((b.name(),ResourceKind::STORAGE_BLOCK,b.span())),
+
+            _ => None
+
+        }).collect()
+
+    }
+    //
+}
+//
+//
+/// Where the parentheses of the layout qualifier of a declaration are. The layouts of the
+/// members of a block are not the one of the block
+pub(crate) fn layout_parentheses(declaration:&str) -> Option<(usize,usize)> {
+
+    let qualifiers = &declaration[..declaration.find('{').unwrap_or(declaration.len())];
+
+    let layout = qualifiers.find("layout")?;
+    let open = layout + declaration[layout..].find('(')?;
+    let close = open + declaration[open..].find(')')?;
+
+    Some((open,close))
+
+}
+//
+//
+/// The changes to make in a layout qualifier to give values to some of its variables. The
+/// value of a variable already written is replaced, the others are added at the end
+///
+/// # parameters
+///
+/// * declaration - the text of the declaration
+/// * offset - where the declaration is in the source
+/// * parentheses - where the parentheses of the layout are in the declaration
+/// * values - the variables and their values
+///
+pub(crate) fn layout_edits(
+    declaration:    &str,
+    offset:         usize,
+    parentheses:    (usize,usize),
+    values:         &[(&str,u32)]) -> Vec<(usize,usize,String)> {
+
+    let (open,close) = parentheses;
+    let mut edits:Vec<(usize,usize,String)> = Vec::new();
+    let mut missing:Vec<String> = Vec::new();
+
+    for (key,value) in values {
+
+        let mut start = open + 1;
+        let mut found = false;
+
+        for entry in declaration[open + 1..close].split(',') {
+
+            let end = start + entry.len();
+
+            if let Some((name,written)) = entry.split_once('=') {
+
+                if name.trim() == *key {
+                    //
+                    // replace the value without the spaces around it
+                    let value_start = start + name.len() + 1 + (written.len() - written.trim_start().len());
+                    let value_end = value_start + written.trim().len();
+
+                    edits.push((offset + value_start,offset + value_end,value.to_string()));
+                    found = true;
+
+                }
+
+            }
+
+            start = end + 1;
+
+        }
+
+        if !found {
+            missing.push(format!("{} = {}",key,value));
+        }
+
+    }
+
+    if !missing.is_empty() {
+
+        let written = declaration[open + 1..close].trim_end();
+        let separator = match written.trim().is_empty() { true => "", false => ", " };
+        let end = offset + open + 1 + written.len();
+
+        edits.push((end,end,format!("{}{}",separator,missing.join(", "))));
+
+    }
+
+    edits
+
+}
+//
+//
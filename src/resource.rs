@@ -0,0 +1,293 @@
+//
+// ------------------------------------------------------------------------------------------
+// Resources
+//
+// The uniforms and the blocks of a program merged over its stages. A resource declared by
+// several stages is one resource for the driver so every declaration must be the same
+//
+use std::fmt;
+use crate::ShaderFileInfo;
+use crate::layout::MemoryLayout;
+use crate::program::{ShaderProgram,InterfaceLocation,location,compare_members};
+use crate::datatype::{ShaderType,ShaderVariables,InterfaceBlock,StorageQualifier,LayoutVarType,ArraySize};
+//
+//
+/// The different kind of resource
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum ResourceKind {
+
+    // a uniform of the default block ('uniform mat4 model;')
+    UNIFORM,
+    // a sampler or an image
+    OPAQUE,
+    UNIFORM_BLOCK,
+    STORAGE_BLOCK,
+    // the block of the push constants of vulkan glsl, it has no binding
+    PUSH_CONSTANT,
+
+}
+//
+//
+/// A uniform or a block of a program with the stages that use it and the ones that declare it
+#[derive(Debug,PartialEq,Clone)]
+pub struct ProgramResource {
+
+    // the name of the variable, or of the block (not of its instance)
+    pub name:           String,
+    pub kind:           ResourceKind,
+    // the type of the variable, the name of the block for the blocks
+    pub type_name:      String,
+    pub array_size:     Option<ArraySize>,
+    pub binding:        Option<u32>,
+    // the descriptor set, vulkan glsl only
+    pub set:            Option<u32>,
+    pub location:       Option<u32>,
+    // only for the blocks
+    pub memory_layout:  Option<MemoryLayout>,
+    // the stages that use it from 'main', in the order of the pipeline
+    pub stages:         Vec<ShaderType>,
+    // one by stage that declares it, used or not
+    pub declarations:   Vec<InterfaceLocation>,
+
+}
+//
+//
+/// Two declarations of a resource that don't agree
+#[derive(Debug,PartialEq,Clone)]
+pub struct ResourceConflict {
+
+    pub name:   String,
+    pub reason: String,
+    pub first:  InterfaceLocation,
+    pub second: InterfaceLocation,
+
+}
+//
+impl fmt::Display for ResourceConflict {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        write!(
+            f,"'{}' is not the same in the {} shader ({}:{}) and the {} shader ({}:{}): {}",
+            self.name,
+            self.first.stage.as_str(),self.first.line,self.first.column,
+            self.second.stage.as_str(),self.second.line,self.second.column,
+            self.reason
+        )
+
+    }
+
+}
+//
+//
+/// What a resource is declared from
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone,Copy)]
+enum Declaration<'a> {
+
+    VARIABLE(&'a ShaderVariables),
+    BLOCK(&'a InterfaceBlock),
+
+}
+//
+impl Declaration<'_> {
+    //
+    fn resource(&self,shader:&ShaderFileInfo) -> Option<ProgramResource> {
+
+        let (name,kind,type_name,array_size,span) = match self {
+
+            Declaration::VARIABLE(var) => (
+                var.name(),
+                match var.var_type().is_opaque() { true => ResourceKind::OPAQUE, false => ResourceKind::UNIFORM },
+                var.var_type().to_string(),
+                var.array_size().cloned(),
+                var.span()
+            ),
+
+            Declaration::BLOCK(block) => (
+                block.name(),
+                match (block.is_push_constant(),block.has_qualifier(&StorageQualifier::BUFFER)) {
+                    (true,_) => ResourceKind::PUSH_CONSTANT,
+                    (_,true) => ResourceKind::STORAGE_BLOCK,
+                    _ => ResourceKind::UNIFORM_BLOCK
+                },
+                block.name().to_string(),
+                block.array_size().cloned(),
+                block.span()
+            )
+
+        };
+
+        let declaration = location(shader,span)?;
+
+        Some(ProgramResource {
+            name: name.to_string(),
+            kind,
+            type_name,
+            array_size,
+            binding: self.layout_value(LayoutVarType::BINDING),
+            set: self.layout_value(LayoutVarType::SET),
+            location: self.layout_value(LayoutVarType::LOCATION),
+            memory_layout: match self { Declaration::BLOCK(b) => b.memory_layout(), _ => None },
+            stages: match self.is_used() { true => vec![declaration.stage], false => Vec::new() },
+            declarations: vec![declaration]
+        })
+
+    }
+    //
+    fn is_used(&self) -> bool {
+
+        match self {
+
+            Declaration::VARIABLE(v) => v.is_used(),
+            Declaration::BLOCK(b) => b.is_used()
+
+        }
+
+    }
+    //
+    fn layout_value(&self,var:LayoutVarType) -> Option<u32> {
+
+        match self {
+
+            Declaration::VARIABLE(v) => v.layout_value(var),
+            Declaration::BLOCK(b) => b.layout_value(var)
+
+        }
+
+    }
+    //
+}
+//
+//
+impl ShaderProgram {
+    //
+    /// The uniforms and the blocks of every stage. A resource declared by several stages is
+    /// once in the list, as its first stage declares it
+    pub fn resources(&self) -> Vec<ProgramResource> { self.merge_resources().0 }
+    //
+    /// Find the resources declared by several stages in different ways: another type,
+    /// array size, binding, set, location, memory layout or other members
+    pub fn check_resources(&self) -> Vec<ResourceConflict> { self.merge_resources().1 }
+    //
+    fn merge_resources(&self) -> (Vec<ProgramResource>,Vec<ResourceConflict>) {
+
+        let mut resources:Vec<ProgramResource> = Vec::new();
+        let mut firsts:Vec<Declaration> = Vec::new();
+        let mut conflicts:Vec<ResourceConflict> = Vec::new();
+
+        for shader in self.stages() {
+
+            let variables = shader.variables()
+                .filter(|v| v.has_qualifier(&StorageQualifier::UNIFORM))
+                .map(Declaration::VARIABLE);
+
+            let blocks = shader.blocks()
+                .filter(|b| b.has_qualifier(&StorageQualifier::UNIFORM) || b.has_qualifier(&StorageQualifier::BUFFER))
+                .map(Declaration::BLOCK);
+
+            for declaration in variables.chain(blocks) {
+
+                let Some(resource) = declaration.resource(shader) else { continue };
+
+                let is_block = matches!(declaration,Declaration::BLOCK(_));
+                let existing = resources.iter().zip(&firsts).position(|(r,first)| {
+                    r.name == resource.name && matches!(first,Declaration::BLOCK(_)) == is_block
+                });
+
+                let Some(index) = existing else {
+
+                    resources.push(resource);
+                    firsts.push(declaration);
+                    continue;
+
+                };
+
+                if let Some(reason) = difference(&resources[index],firsts[index],&resource,declaration) {
+
+                    conflicts.push(ResourceConflict {
+                        name: resource.name.clone(),
+                        reason,
+                        first: resources[index].declarations[0],
+                        second: resource.declarations[0]
+                    });
+
+                }
+
+                resources[index].stages.extend(resource.stages);
+                resources[index].declarations.push(resource.declarations[0]);
+
+            }
+
+        }
+
+        (resources,conflicts)
+
+    }
+    //
+}
+//
+//
+/// Describe how two declarations of a resource are not the same
+fn difference(
+    first:              &ProgramResource,
+    first_declaration:  Declaration,
+    other:              &ProgramResource,
+    other_declaration:  Declaration) -> Option<String> {
+
+    if first.kind != other.kind {
+        return Some(format!("{} and {}",kind_text(first.kind),kind_text(other.kind)));
+    }
+
+    if first.type_name != other.type_name {
+        return Some(format!("a {} and a {}",first.type_name,other.type_name));
+    }
+
+    if first.array_size != other.array_size {
+        return Some("the array sizes are not the same".to_string());
+    }
+
+    let compare = |what:&str,a:Option<u32>,b:Option<u32>| match (a,b) {
+
+        (Some(a),Some(b)) if a != b => Some(format!("the {} is {} and {}",what,a,b)),
+        _ => None
+
+    };
+
+    let layout = compare("binding",first.binding,other.binding)
+        .or(compare("set",first.set,other.set))
+        .or(compare("location",first.location,other.location));
+
+    if let Some(reason) = layout {
+        return Some(reason);
+    }
+
+    if first.memory_layout != other.memory_layout {
+        return Some("the memory layouts are not the same".to_string());
+    }
+
+    match (first_declaration,other_declaration) {
+
+        (Declaration::BLOCK(a),Declaration::BLOCK(b)) => compare_members(a.members(),b.members()),
+        _ => None
+
+    }
+
+}
+//
+//
+/// How to name a kind of block in the messages
+fn kind_text(kind:ResourceKind) -> &'static str {
+
+    match kind {
+
+        ResourceKind::STORAGE_BLOCK => "a storage block",
+        ResourceKind::PUSH_CONSTANT => "a push constant block",
+        _ => "a uniform block"
+
+    }
+
+}
+//
+//
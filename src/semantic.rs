@@ -0,0 +1,1396 @@
+//
+// ------------------------------------------------------------------------------------------
+// Semantic checks
+//
+// An optional pass over the functions of a parsed shader that infer the type of every
+// expression and report what a glsl compiler would refuse: wrong operand types, calls
+// without a matching overload, writes to read-only variables, invalid swizzles, ...
+//
+use std::collections::HashMap;
+use std::fmt;
+use crate::{EParser,ShaderFileInfo};
+use crate::lexer::Span;
+use crate::datatype::{ScalarType,ShaderType,ShaderVariables,InterfaceBlock,StorageQualifier,ArraySize,VersionBranch};
+use crate::constant::{ConstValue,evaluate,type_shape,swizzle_indexes};
+use crate::literal::Literal;
+use crate::expression::{Expression,ExpressionKind,UnaryOperator,BinaryOperator};
+use crate::statement::{Statement,StatementKind,LocalDeclaration};
+use crate::function::{FunctionDeclaration,FunctionParameter,ParameterQualifier};
+use crate::builtin::{ShaderTarget,BuiltinQualifier,available_functions,available_variables,builtin_functions,builtin_variables};
+//
+//
+/// The type of a variable or of an expression
+#[derive(Debug,PartialEq,Clone)]
+pub enum GlslType {
+
+    VOID,
+    // scalars, vectors and matrices with their component type, columns and rows
+    BASIC(ScalarType,usize,usize),
+    // samplers, images and structs, known by their name
+    NAMED(String),
+    // the element type and the size, None for an array without size
+    ARRAY(Box<GlslType>,Option<usize>),
+    // something the checker cant know (a built-in, a struct member, an earlier error).
+    // It is accepted everywhere so one error is not reported several times
+    UNKNOWN,
+
+}
+//
+impl GlslType {
+    //
+    /// The type written with this name ('vec3' => BASIC(FLOAT,1,3))
+    pub fn from_name(name:&str) -> GlslType {
+
+        if name == "void" {
+            return GlslType::VOID;
+        }
+
+        match type_shape(name) {
+
+            Some((scalar,columns,rows)) => GlslType::BASIC(scalar,columns,rows),
+            None => GlslType::NAMED(name.to_string())
+
+        }
+
+    }
+    //
+    /// The type written in a declaration with its array size ('float[4]' => ARRAY(float,4))
+    pub fn parse(text:&str) -> GlslType {
+
+        match text.trim_end().strip_suffix(']').and_then(|t| t.rsplit_once('[')) {
+
+            Some((element,size)) => GlslType::ARRAY(
+                Box::new(GlslType::parse(element)),
+                size.trim().parse::<usize>().ok()
+            ),
+            None => GlslType::from_name(text.trim())
+
+        }
+
+    }
+    //
+    /// The type of a global variable of the shader
+    pub fn of_variable(var:&ShaderVariables) -> GlslType {
+
+        let element = GlslType::from_name(var.var_type().to_string().as_str());
+
+        match var.array_size() {
+
+            Some(size) => element.array_of(size),
+            None => element
+
+        }
+
+    }
+    //
+    /// An array of this type
+    pub fn array_of(self,size:&ArraySize) -> GlslType {
+
+        match size {
+
+            ArraySize::SIZED(n) => GlslType::ARRAY(Box::new(self),Some(*n as usize)),
+            ArraySize::UNSIZED => GlslType::ARRAY(Box::new(self),None)
+
+        }
+
+    }
+    //
+    pub fn scalar_type(&self) -> Option<ScalarType> {
+
+        match self {
+
+            GlslType::BASIC(scalar,_,_) => Some(*scalar),
+            _ => None
+
+        }
+
+    }
+    //
+    pub fn is_scalar(&self) -> bool { matches!(self,GlslType::BASIC(_,1,1)) }
+    //
+    fn is_unknown(&self) -> bool { *self == GlslType::UNKNOWN }
+    //
+    /// Check if a value of this type can be used where the other type is expected in a
+    /// shader compiled for the target
+    pub fn converts_implicitly_to(&self,other:&GlslType,target:&ShaderTarget) -> bool {
+
+        match (self,other) {
+
+            (GlslType::UNKNOWN,_) | (_,GlslType::UNKNOWN) => true,
+
+            (GlslType::BASIC(from,c1,r1),GlslType::BASIC(to,c2,r2)) => {
+
+                c1 == c2 && r1 == r2 && scalar_converts(*from,*to,target)
+
+            },
+
+            // there is no conversion between arrays
+            (GlslType::ARRAY(a,s1),GlslType::ARRAY(b,s2)) =>
+                (s1 == s2 || s1.is_none() || s2.is_none()) && (a == b || a.is_unknown() || b.is_unknown()),
+
+            _ => self == other
+
+        }
+
+    }
+    //
+}
+//
+impl fmt::Display for GlslType {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+
+            GlslType::VOID => write!(f,"void"),
+            GlslType::BASIC(scalar,1,1) => write!(f,"{}",scalar.as_str()),
+            GlslType::BASIC(scalar,1,rows) => write!(f,"{}vec{}",scalar.prefix(),rows),
+            GlslType::BASIC(scalar,columns,rows) if columns == rows => write!(f,"{}mat{}",scalar.prefix(),columns),
+            GlslType::BASIC(scalar,columns,rows) => write!(f,"{}mat{}x{}",scalar.prefix(),columns,rows),
+            GlslType::NAMED(name) => write!(f,"{}",name),
+            GlslType::ARRAY(element,Some(n)) => write!(f,"{}[{}]",element,n),
+            GlslType::ARRAY(element,None) => write!(f,"{}[]",element),
+            GlslType::UNKNOWN => write!(f,"<unknown>")
+
+        }
+
+    }
+
+}
+//
+//
+/// A name declared in a scope
+#[derive(Debug,Clone)]
+struct Symbol {
+
+    ty:         GlslType,
+    // the value of a 'const' variable when it could be evaluated
+    value:      Option<ConstValue>,
+    // why the variable cant be written ('const', 'uniform', ...)
+    read_only:  Option<&'static str>,
+
+}
+//
+//
+/// A function that a call can use
+struct Candidate {
+
+    return_type:    GlslType,
+    parameters:     Vec<(ParameterQualifier,GlslType)>,
+
+}
+//
+//
+/// The state of the checker while it walks the functions
+struct Checker<'a> {
+
+    info:           &'a ShaderFileInfo,
+    target:         ShaderTarget,
+    scopes:         Vec<HashMap<String,Symbol>>,
+    functions:      Vec<&'a FunctionDeclaration>,
+    errors:         Vec<EParser>,
+    // the return type of the function being checked and if it has a return statement
+    return_type:    GlslType,
+    returns:        bool,
+    loop_depth:     usize,
+    switch_depth:   usize,
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Check the types and the rules of glsl in the functions of the shader
+    ///
+    /// Return every error found, nothing when the shader is valid. The built-ins are the
+    /// ones of the stage and the version of the shader
+    ///
+    pub fn check_semantics(&self) -> Vec<EParser> {
+
+        let target = self.target();
+
+        let mut checker = Checker {
+            info:           self,
+            target,
+            scopes:         vec![HashMap::new()],
+            functions:      self.functions().collect(),
+            errors:         Vec::new(),
+            return_type:    GlslType::VOID,
+            returns:        false,
+            loop_depth:     0,
+            switch_depth:   0,
+        };
+
+        checker.check();
+
+        checker.errors
+
+    }
+    //
+}
+//
+//
+impl<'a> Checker<'a> {
+    //
+    fn check(&mut self) {
+        //
+        // the built-in variables and the globals are visible in every function
+        for builtin in available_variables(&self.target) {
+
+            let read_only = match builtin.qualifier {
+
+                BuiltinQualifier::IN => Some("a built-in input"),
+                BuiltinQualifier::CONST => Some("const"),
+                BuiltinQualifier::UNIFORM => Some("uniform"),
+                _ => None
+
+            };
+
+            self.scopes[0].insert(builtin.name.to_string(),Symbol {
+                ty: GlslType::parse(builtin.type_name),
+                value: None,
+                read_only
+            });
+
+        }
+
+        for var in self.info.variables() {
+
+            let read_only = var.storage_qualifiers().iter().find_map(|q| match q {
+
+                StorageQualifier::CONST => Some("const"),
+                StorageQualifier::UNIFORM => Some("uniform"),
+                StorageQualifier::IN => Some("in"),
+                _ => None
+
+            });
+
+            self.scopes[0].insert(var.name().to_string(),Symbol {
+                ty: GlslType::of_variable(var),
+                value: var.value().cloned(),
+                read_only
+            });
+
+        }
+        //
+        // the members of a block without instance name are used like globals
+        for block in self.info.blocks() {
+
+            let read_only = block.storage_qualifiers().iter().find_map(|q| match q {
+
+                StorageQualifier::UNIFORM => Some("uniform"),
+                StorageQualifier::IN => Some("in"),
+                _ => None
+
+            });
+
+            match block.instance_name() {
+
+                Some(instance) => {
+
+                    let element = GlslType::NAMED(block.name().to_string());
+
+                    self.scopes[0].insert(instance.to_string(),Symbol {
+                        ty: match block.array_size() { Some(size) => element.array_of(size), None => element },
+                        value: None,
+                        read_only
+                    });
+
+                },
+                // the members of a struct are not variables
+                None if is_struct(block) => {},
+
+                None => for member in block.members() {
+
+                    self.scopes[0].insert(member.name().to_string(),Symbol {
+                        ty: GlslType::of_variable(member),
+                        value: None,
+                        read_only
+                    });
+
+                }
+
+            }
+
+        }
+        //
+        self.check_function_declarations();
+        //
+        let functions = self.functions.clone();
+
+        for function in functions {
+
+            if let Some(body) = &function.body {
+
+                self.return_type = GlslType::from_name(&function.return_type);
+                self.returns = false;
+                self.scopes.push(HashMap::new());
+
+                for param in &function.parameters {
+
+                    if let Some(name) = &param.name {
+
+                        let symbol = Symbol {
+                            ty: parameter_type(param),
+                            value: None,
+                            read_only: param.constant.then_some("const")
+                        };
+
+                        self.declare(name,symbol,param.span);
+
+                    }
+
+                }
+
+                for statement in &body.statements {
+                    self.statement(statement);
+                }
+
+                if !self.returns && self.return_type != GlslType::VOID && !self.return_type.is_unknown() {
+
+                    self.error(function.span,format!(
+                        "the function '{}' must return a {} but has no return statement",
+                        function.name,
+                        self.return_type
+                    ));
+
+                }
+
+                self.scopes.pop();
+
+            }
+
+        }
+        //
+    }
+    //
+    /// The same signature cant be defined twice or declared with different return types
+    fn check_function_declarations(&mut self) {
+
+        let functions = self.functions.clone();
+
+        for (i,function) in functions.iter().enumerate() {
+
+            let params:Vec<GlslType> = function.parameters.iter().map(parameter_type).collect();
+
+            for previous in &functions[..i] {
+
+                let same_params = previous.name == function.name &&
+                    previous.parameters.iter().map(parameter_type).collect::<Vec<GlslType>>() == params;
+
+                if !same_params {
+                    continue;
+                }
+
+                if previous.return_type != function.return_type {
+
+                    self.error(function.span,format!(
+                        "'{}' was declared before with the return type {}",
+                        function.signature(),
+                        previous.return_type
+                    ));
+
+                } else if previous.is_definition() && function.is_definition() {
+
+                    self.error(function.span,format!("'{}' is already defined",function.signature()));
+
+                }
+
+            }
+
+        }
+
+    }
+    //
+    // ---------------------------------------------------------------------------------------
+    // Scopes
+    //
+    fn error(&mut self,span:Span,reason:String) {
+
+        self.errors.push(EParser::SEMANTIC(reason,span.start));
+
+    }
+    //
+    fn declare(&mut self,name:&str,symbol:Symbol,span:Span) {
+
+        let scope = self.scopes.last_mut().expect("there is always the global scope");
+
+        if scope.contains_key(name) {
+
+            self.error(span,format!("'{}' is already declared in this scope",name));
+            return;
+
+        }
+
+        scope.insert(name.to_string(),symbol);
+
+    }
+    //
+    fn lookup(&self,name:&str) -> Option<&Symbol> {
+
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+
+    }
+    //
+    /// The value of the constants visible from the current scope
+    fn constants(&self) -> HashMap<String,ConstValue> {
+
+        let mut constants = self.info.constants.clone();
+
+        for scope in &self.scopes {
+
+            for (name,symbol) in scope {
+
+                match &symbol.value {
+
+                    Some(value) if symbol.read_only == Some("const") => {
+                        constants.insert(name.to_string(),value.clone());
+                    },
+                    _ => {
+                        constants.remove(name);
+                    }
+
+                }
+
+            }
+
+        }
+
+        constants
+
+    }
+    //
+    // ---------------------------------------------------------------------------------------
+    // Statements
+    //
+    fn statement(&mut self,statement:&Statement) {
+
+        match &statement.kind {
+
+            StatementKind::DECLARATION(declaration) => self.declaration(declaration),
+
+            StatementKind::EXPRESSION(expr) => { self.expression(expr); },
+
+            StatementKind::BLOCK(statements) => {
+
+                self.scopes.push(HashMap::new());
+
+                for s in statements {
+                    self.statement(s);
+                }
+
+                self.scopes.pop();
+
+            },
+
+            StatementKind::IF(condition,then,otherwise) => {
+
+                self.condition(condition);
+                self.statement(then);
+
+                if let Some(o) = otherwise {
+                    self.statement(o);
+                }
+
+            },
+
+            StatementKind::FOR(init,condition,step,body) => {
+
+                self.scopes.push(HashMap::new());
+
+                if let Some(i) = init {
+                    self.statement(i);
+                }
+
+                if let Some(c) = condition {
+                    self.condition(c);
+                }
+
+                if let Some(s) = step {
+                    self.expression(s);
+                }
+
+                self.loop_body(body);
+                self.scopes.pop();
+
+            },
+
+            StatementKind::WHILE(condition,body) => {
+
+                self.condition(condition);
+                self.loop_body(body);
+
+            },
+
+            StatementKind::DO_WHILE(body,condition) => {
+
+                self.loop_body(body);
+                self.condition(condition);
+
+            },
+
+            StatementKind::SWITCH(value,statements) => {
+
+                let ty = self.expression(value);
+
+                if !ty.is_unknown() && !matches!(ty,GlslType::BASIC(ScalarType::INT | ScalarType::UINT,1,1)) {
+                    self.error(value.span,format!("a switch needs an int or an uint but found a {}",ty));
+                }
+
+                self.switch_depth += 1;
+                self.scopes.push(HashMap::new());
+
+                for s in statements {
+                    self.statement(s);
+                }
+
+                self.scopes.pop();
+                self.switch_depth -= 1;
+
+            },
+
+            StatementKind::CASE(value) => {
+
+                if self.switch_depth == 0 {
+                    self.error(statement.span,"'case' outside of a switch".to_string());
+                }
+
+                if evaluate(value,&self.constants()).is_err() {
+                    self.error(value.span,"the value of a case must be a constant".to_string());
+                }
+
+            },
+
+            StatementKind::DEFAULT if self.switch_depth == 0 =>
+                self.error(statement.span,"'default' outside of a switch".to_string()),
+
+            StatementKind::BREAK if self.loop_depth == 0 && self.switch_depth == 0 =>
+                self.error(statement.span,"'break' outside of a loop or a switch".to_string()),
+
+            StatementKind::CONTINUE if self.loop_depth == 0 =>
+                self.error(statement.span,"'continue' outside of a loop".to_string()),
+
+            StatementKind::RETURN(value) => {
+
+                let expected = self.return_type.clone();
+                self.returns = true;
+
+                match value {
+
+                    Some(v) => {
+
+                        let ty = self.expression(v);
+
+                        if expected == GlslType::VOID {
+                            self.error(v.span,"a void function cant return a value".to_string());
+                        } else if !ty.converts_implicitly_to(&expected,&self.target) {
+                            self.error(v.span,format!("cant return a {} from a function that return a {}",ty,expected));
+                        }
+
+                    },
+                    None if expected != GlslType::VOID =>
+                        self.error(statement.span,format!("the function must return a {}",expected)),
+                    None => {}
+
+                }
+
+            },
+
+            StatementKind::DISCARD if self.info.shader_type().is_some_and(|s| s != ShaderType::FRAGMENT) =>
+                self.error(statement.span,"'discard' is only allowed in a fragment shader".to_string()),
+
+            _ => {}
+
+        }
+
+    }
+    //
+    fn loop_body(&mut self,body:&Statement) {
+
+        self.loop_depth += 1;
+        self.statement(body);
+        self.loop_depth -= 1;
+
+    }
+    //
+    fn condition(&mut self,condition:&Expression) {
+
+        let ty = self.expression(condition);
+
+        if !ty.is_unknown() && ty != GlslType::BASIC(ScalarType::BOOL,1,1) {
+            self.error(condition.span,format!("a condition must be a bool but found a {}",ty));
+        }
+
+    }
+    //
+    fn declaration(&mut self,declaration:&LocalDeclaration) {
+
+        let constant = declaration.qualifiers.iter().any(|q| q == "const");
+        let base = GlslType::from_name(&declaration.type_name);
+
+        if base == GlslType::VOID {
+            self.error(
+                declaration.declarators.first().map(|d| d.span).unwrap_or_default(),
+                "a variable cant be void".to_string()
+            );
+            return;
+        }
+
+        for declarator in &declaration.declarators {
+
+            let array = declarator.array.as_ref().or(declaration.type_array.as_ref());
+
+            let mut ty = match array {
+
+                Some(size) => GlslType::ARRAY(Box::new(base.clone()),self.array_size(size.as_ref())),
+                None => base.clone()
+
+            };
+
+            let mut value:Option<ConstValue> = None;
+
+            match &declarator.initializer {
+
+                Some(init) => {
+
+                    let init_ty = self.expression(init);
+
+                    // 'float a[] = float[](1.0,2.0)' take the size of the initializer
+                    if let (GlslType::ARRAY(e,None),GlslType::ARRAY(_,Some(n))) = (&ty,&init_ty) {
+                        ty = GlslType::ARRAY(e.clone(),Some(*n));
+                    }
+
+                    if !init_ty.converts_implicitly_to(&ty,&self.target) {
+                        self.error(init.span,format!("cant initialize '{}' that is a {} with a {}",declarator.name,ty,init_ty));
+                    }
+
+                    if constant {
+                        value = evaluate(init,&self.constants()).ok();
+                    }
+
+                },
+                None if constant =>
+                    self.error(declarator.span,format!("the constant '{}' must be initialized",declarator.name)),
+                None => {}
+
+            }
+
+            let symbol = Symbol { ty, value, read_only: constant.then_some("const") };
+
+            self.declare(&declarator.name,symbol,declarator.span);
+
+        }
+
+    }
+    //
+    /// The size of an array declared in a function. None when it has no size or it cant be
+    /// evaluated (the error is reported)
+    fn array_size(&mut self,size:Option<&Expression>) -> Option<usize> {
+
+        let size = size?;
+
+        match evaluate(size,&self.constants()).ok().and_then(|v| v.as_array_size()) {
+
+            Some(n) => Some(n as usize),
+            None => {
+
+                self.error(size.span,"the size of an array must be a positive constant integer".to_string());
+                None
+
+            }
+
+        }
+
+    }
+    //
+    // ---------------------------------------------------------------------------------------
+    // Expressions
+    //
+    /// Return the type of the expression and report the errors inside it
+    fn expression(&mut self,expr:&Expression) -> GlslType {
+
+        match self.expression_type(expr) {
+
+            Ok(ty) => ty,
+            Err(reason) => {
+
+                self.error(expr.span,reason);
+                GlslType::UNKNOWN
+
+            }
+
+        }
+
+    }
+    //
+    fn expression_type(&mut self,expr:&Expression) -> Result<GlslType,String> {
+
+        match &expr.kind {
+
+            ExpressionKind::LITERAL(lit) => Ok(GlslType::BASIC(ScalarType::of(lit),1,1)),
+
+            ExpressionKind::IDENTIFIER(name) => match self.lookup(name) {
+
+                Some(symbol) => Ok(symbol.ty.clone()),
+                None if builtin_variables(name).next().is_some() =>
+                    Err(format!("the built-in '{}' is not available in {}",name,target_text(&self.target))),
+                None => Err(format!("'{}' is not declared",name))
+
+            },
+
+            ExpressionKind::UNARY(op,operand) => {
+
+                let ty = self.expression(operand);
+
+                if op.modifies_operand() {
+                    self.lvalue(operand)?;
+                }
+
+                unary_type(*op,&ty)
+
+            },
+
+            ExpressionKind::BINARY(op,left,right) => {
+
+                let left = self.expression(left);
+                let right = self.expression(right);
+
+                binary_type(*op,&left,&right,&self.target)
+
+            },
+
+            ExpressionKind::TERNARY(condition,accept,reject) => {
+
+                self.condition(condition);
+
+                let accept = self.expression(accept);
+                let reject = self.expression(reject);
+
+                if reject.converts_implicitly_to(&accept,&self.target) {
+                    Ok(if reject.is_unknown() { reject } else { accept })
+                } else if accept.converts_implicitly_to(&reject,&self.target) {
+                    Ok(reject)
+                } else {
+                    Err(format!("the two results of '?:' are a {} and a {}",accept,reject))
+                }
+
+            },
+
+            ExpressionKind::CALL(name,args) => {
+
+                let types:Vec<GlslType> = args.iter().map(|a| self.expression(a)).collect();
+
+                let declared_struct = self.info.blocks().find(|b| is_struct(b) && b.name() == name);
+
+                match (type_shape(name),declared_struct) {
+
+                    (Some((scalar,columns,rows)),_) => constructor_type(GlslType::BASIC(scalar,columns,rows),&types),
+
+                    // a struct is constructed with a value by member
+                    (None,Some(declared)) => match declared.members().len() == args.len() {
+
+                        true => Ok(GlslType::NAMED(name.to_string())),
+                        false => Err(format!("the struct '{}' has {} members but is constructed with {} arguments",name,declared.members().len(),args.len()))
+
+                    },
+
+                    (None,None) => self.call_type(name,args,&types)
+
+                }
+
+            },
+
+            ExpressionKind::ARRAY_CONSTRUCTOR(name,size,args) => {
+
+                let element = GlslType::from_name(name);
+                let types:Vec<GlslType> = args.iter().map(|a| self.expression(a)).collect();
+
+                if let Some(n) = size.as_ref().and_then(|s| self.array_size(Some(s))) {
+
+                    if n != args.len() {
+                        return Err(format!("the array constructor has {} arguments for a size of {}",args.len(),n));
+                    }
+
+                }
+
+                for (arg,ty) in args.iter().zip(&types) {
+
+                    if !ty.converts_implicitly_to(&element,&self.target) {
+                        self.error(arg.span,format!("cant use a {} to construct an array of {}",ty,element));
+                    }
+
+                }
+
+                Ok(GlslType::ARRAY(Box::new(element),Some(args.len())))
+
+            },
+
+            ExpressionKind::INDEX(value,index) => {
+
+                let ty = self.expression(value);
+                let index_ty = self.expression(index);
+
+                if !index_ty.is_unknown() && !matches!(index_ty,GlslType::BASIC(ScalarType::INT | ScalarType::UINT,1,1)) {
+                    return Err(format!("an index must be an int or an uint but found a {}",index_ty));
+                }
+
+                let (element,size,kind) = match ty {
+
+                    GlslType::ARRAY(element,size) => (*element,size,"array"),
+                    GlslType::BASIC(scalar,1,rows) if rows > 1 => (GlslType::BASIC(scalar,1,1),Some(rows),"vector"),
+                    GlslType::BASIC(scalar,columns,rows) if columns > 1 => (GlslType::BASIC(scalar,1,rows),Some(columns),"matrix"),
+                    GlslType::UNKNOWN => return Ok(GlslType::UNKNOWN),
+                    ty => return Err(format!("a {} cant be indexed",ty))
+
+                };
+
+                let constant_index = evaluate(index,&self.constants()).ok().and_then(|v| match v {
+
+                    ConstValue::SCALAR(l) => Some(l),
+                    _ => None
+
+                });
+
+                if let (Some(lit),Some(size)) = (constant_index,size) {
+
+                    let out = match lit {
+
+                        Literal::INT(i) => i < 0 || i as usize >= size,
+                        Literal::UINT(u) => u as usize >= size,
+                        _ => false
+
+                    };
+
+                    if out {
+                        return Err(format!("the index is out of the range of a {} of size {}",kind,size));
+                    }
+
+                }
+
+                Ok(element)
+
+            },
+
+            ExpressionKind::FIELD(value,field) => {
+
+                match self.expression(value) {
+
+                    GlslType::BASIC(scalar,1,rows) => {
+
+                        let indexes = swizzle_indexes(field,rows)?;
+
+                        Ok(GlslType::BASIC(scalar,1,indexes.len()))
+
+                    },
+                    // the members of the structs and of the blocks are not known yet
+                    GlslType::NAMED(_) | GlslType::UNKNOWN => Ok(GlslType::UNKNOWN),
+                    ty => Err(format!("a {} has no field '{}'",ty,field))
+
+                }
+
+            },
+
+            ExpressionKind::METHOD(value,method,args) => {
+
+                let ty = self.expression(value);
+
+                if method != "length" || !args.is_empty() {
+                    return Err(format!("unknown method '{}'",method));
+                }
+
+                match ty {
+
+                    GlslType::ARRAY(..) | GlslType::UNKNOWN => Ok(GlslType::BASIC(ScalarType::INT,1,1)),
+                    GlslType::BASIC(_,columns,rows) if columns > 1 || rows > 1 => Ok(GlslType::BASIC(ScalarType::INT,1,1)),
+                    ty => Err(format!("a {} has no length",ty))
+
+                }
+
+            },
+
+            ExpressionKind::ASSIGNMENT(op,target,value) => {
+
+                let target_ty = self.expression(target);
+                let value_ty = self.expression(value);
+
+                self.lvalue(target)?;
+
+                let result = match op {
+
+                    Some(op) => binary_type(*op,&target_ty,&value_ty,&self.target)?,
+                    None => value_ty
+
+                };
+
+                if !result.converts_implicitly_to(&target_ty,&self.target) {
+                    return Err(format!("cant assign a {} to a {}",result,target_ty));
+                }
+
+                Ok(target_ty)
+
+            },
+
+            ExpressionKind::SEQUENCE(list) => {
+
+                let mut ty = GlslType::VOID;
+
+                for e in list {
+                    ty = self.expression(e);
+                }
+
+                Ok(ty)
+
+            },
+
+        }
+
+    }
+    //
+    /// Check that an expression can be written
+    fn lvalue(&self,expr:&Expression) -> Result<(),String> {
+
+        match &expr.kind {
+
+            ExpressionKind::IDENTIFIER(name) => match self.lookup(name) {
+
+                Some(Symbol { read_only: Some(reason), .. }) =>
+                    Err(format!("'{}' cant be modified because it is {}",name,reason)),
+                _ => Ok(())
+
+            },
+
+            ExpressionKind::INDEX(value,_) => self.lvalue(value),
+
+            ExpressionKind::FIELD(value,field) => {
+
+                let mut seen = field.chars().collect::<Vec<char>>();
+                seen.sort();
+                seen.dedup();
+
+                let is_swizzle = field.chars().all(|c| "xyzwrgbastpq".contains(c));
+
+                if is_swizzle && seen.len() != field.len() {
+                    return Err(format!("the swizzle '{}' cant be written because it repeat a component",field));
+                }
+
+                self.lvalue(value)
+
+            },
+
+            _ => Err("the expression cant be modified".to_string())
+
+        }
+
+    }
+    //
+    /// Find the function called and return its type
+    fn call_type(&mut self,name:&str,args:&[Expression],types:&[GlslType]) -> Result<GlslType,String> {
+        //
+        // the user functions and the built-ins. Prototypes and definitions of the same
+        // signature are one candidate
+        let mut candidates:Vec<Candidate> = Vec::new();
+
+        let declared = self.functions.iter().filter(|f| f.name == name).map(|f| Candidate {
+            return_type: GlslType::from_name(&f.return_type),
+            parameters: f.parameters.iter().map(|p| (p.qualifier,parameter_type(p))).collect()
+        });
+
+        let builtins = available_functions(name,&self.target).into_iter().map(|f| Candidate {
+            return_type: GlslType::parse(&f.return_type),
+            parameters: f.parameters.iter().map(|(q,t)| (*q,GlslType::parse(t))).collect()
+        });
+
+        for candidate in declared.chain(builtins) {
+
+            if !candidates.iter().any(|c| c.parameters == candidate.parameters) {
+                candidates.push(candidate);
+            }
+
+        }
+
+        if candidates.is_empty() {
+
+            return match builtin_functions(name).is_empty() {
+
+                true => Err(format!("the function '{}' is not declared",name)),
+                false => Err(format!("the built-in '{}' is not available in {}",name,target_text(&self.target)))
+
+            };
+
+        }
+        //
+        // every matching candidate with the rank of the conversion of each argument
+        let mut matches:Vec<(&Candidate,Vec<u8>)> = Vec::new();
+
+        for candidate in &candidates {
+
+            if candidate.parameters.len() != types.len() {
+                continue;
+            }
+
+            let mut ranks:Vec<u8> = Vec::new();
+            let mut matching = true;
+
+            for ((qualifier,param_ty),arg) in candidate.parameters.iter().zip(types) {
+
+                // the value of an 'out' parameter is converted back to the argument
+                let converts = match qualifier {
+
+                    ParameterQualifier::IN => arg.converts_implicitly_to(param_ty,&self.target),
+                    ParameterQualifier::OUT => param_ty.converts_implicitly_to(arg,&self.target),
+                    ParameterQualifier::INOUT =>
+                        arg.converts_implicitly_to(param_ty,&self.target) && param_ty.converts_implicitly_to(arg,&self.target)
+
+                };
+
+                if !converts {
+                    matching = false;
+                    break;
+                }
+
+                ranks.push(conversion_rank(arg,param_ty));
+
+            }
+
+            if matching {
+                matches.push((candidate,ranks));
+            }
+
+        }
+
+        let types_text = || types.iter().map(|t| t.to_string()).collect::<Vec<String>>().join(",");
+
+        if matches.is_empty() {
+            return Err(format!("no overload of '{}' takes ({})",name,types_text()));
+        }
+        //
+        // the best function is better or as good as the others for every argument
+        let best = matches.iter().find(|(_,ranks)| {
+
+            matches.iter().all(|(_,other)| ranks.iter().zip(other).all(|(r,o)| r <= o))
+
+        });
+
+        let function = match best {
+
+            Some((f,_)) => *f,
+            None => return Err(format!("the call of '{}' with ({}) is ambiguous",name,types_text()))
+
+        };
+        //
+        // the 'out' arguments are written by the function
+        let mut errors:Vec<(Span,String)> = Vec::new();
+
+        for ((qualifier,_),arg) in function.parameters.iter().zip(args) {
+
+            if *qualifier != ParameterQualifier::IN {
+
+                if let Err(reason) = self.lvalue(arg) {
+                    errors.push((arg.span,format!("the argument is an output of '{}': {}",name,reason)));
+                }
+
+            }
+
+        }
+
+        let return_type = function.return_type.clone();
+
+        for (span,reason) in errors {
+            self.error(span,reason);
+        }
+
+        Ok(return_type)
+
+    }
+    //
+}
+//
+//
+/// How to name the stage and the version of a shader in the messages
+fn target_text(target:&ShaderTarget) -> String {
+
+    match target.stage {
+
+        Some(stage) => format!("a {} shader with glsl {}",stage.as_str(),target.version),
+        None => format!("glsl {}",target.version)
+
+    }
+
+}
+//
+//
+/// The type of a parameter with its array size
+fn parameter_type(param:&FunctionParameter) -> GlslType {
+
+    let element = GlslType::from_name(&param.type_name);
+
+    match &param.array_size {
+
+        Some(size) => element.array_of(size),
+        None => element
+
+    }
+
+}
+//
+//
+/// How good is the conversion of an argument for the overload resolution (section 6.1 of
+/// the specification). Lower is better
+fn conversion_rank(arg:&GlslType,param:&GlslType) -> u8 {
+
+    match (arg.scalar_type(),param.scalar_type()) {
+
+        _ if arg == param || arg.is_unknown() => 0,
+        (Some(ScalarType::FLOAT),Some(ScalarType::DOUBLE)) => 1,
+        (Some(ScalarType::INT | ScalarType::UINT),Some(ScalarType::FLOAT)) => 2,
+        _ => 3
+
+    }
+
+}
+//
+//
+fn unary_type(op:UnaryOperator,ty:&GlslType) -> Result<GlslType,String> {
+
+    let scalar = match ty {
+
+        GlslType::BASIC(scalar,_,_) => *scalar,
+        GlslType::UNKNOWN => return Ok(GlslType::UNKNOWN),
+        ty => return Err(format!("the operator '{}' cant be used on a {}",op.as_str(),ty))
+
+    };
+
+    let valid = match op {
+
+        UnaryOperator::NOT => ty.is_scalar() && scalar == ScalarType::BOOL,
+        UnaryOperator::BIT_NOT => matches!(scalar,ScalarType::INT | ScalarType::UINT),
+        _ => scalar != ScalarType::BOOL
+
+    };
+
+    match valid {
+
+        true => Ok(ty.clone()),
+        false => Err(format!("the operator '{}' cant be used on a {}",op.as_str(),ty))
+
+    }
+
+}
+//
+//
+/// Check if a component type converts implicitly to another. Glsl es and glsl 1.10 have
+/// no implicit conversion, int => uint and int,uint,float => double exist since glsl 4.00
+fn scalar_converts(from:ScalarType,to:ScalarType,target:&ShaderTarget) -> bool {
+
+    from == to || (
+        target.branch != VersionBranch::ES && target.version >= 120 &&
+        from.converts_implicitly_to(to) &&
+        (target.version >= 400 || to == ScalarType::FLOAT)
+    )
+
+}
+//
+//
+/// The type of the result of an operator between two operands
+fn binary_type(op:BinaryOperator,left:&GlslType,right:&GlslType,target:&ShaderTarget) -> Result<GlslType,String> {
+    //
+    let mismatch = || Err(format!(
+        "the operator '{}' cant be used between a {} and a {}",
+        op.as_str(),
+        left,
+        right
+    ));
+    //
+    if left.is_unknown() || right.is_unknown() {
+
+        let is_comparison = matches!(op,
+            BinaryOperator::LT | BinaryOperator::GT | BinaryOperator::LE | BinaryOperator::GE |
+            BinaryOperator::EQ | BinaryOperator::NE | BinaryOperator::AND | BinaryOperator::OR |
+            BinaryOperator::XOR
+        );
+
+        return Ok(match is_comparison {
+
+            true => GlslType::BASIC(ScalarType::BOOL,1,1),
+            false => GlslType::UNKNOWN
+
+        });
+
+    }
+    //
+    // '==' and '!=' compare whole values of the same type, arrays included
+    if matches!(op,BinaryOperator::EQ | BinaryOperator::NE) {
+
+        return match left.converts_implicitly_to(right,target) || right.converts_implicitly_to(left,target) {
+
+            true => Ok(GlslType::BASIC(ScalarType::BOOL,1,1)),
+            false => mismatch()
+
+        };
+
+    }
+    //
+    let (GlslType::BASIC(s1,c1,r1),GlslType::BASIC(s2,c2,r2)) = (left,right) else {
+        return mismatch();
+    };
+    //
+    // the component type both operands are converted to
+    let scalar = if scalar_converts(*s1,*s2,target) {
+        *s2
+    } else if scalar_converts(*s2,*s1,target) {
+        *s1
+    } else {
+        return mismatch();
+    };
+    //
+    let left_scalar = *c1 == 1 && *r1 == 1;
+    let right_scalar = *c2 == 1 && *r2 == 1;
+    //
+    // component-wise operation, a scalar is applied to every component
+    let component_wise = |scalar:ScalarType| -> Result<GlslType,String> {
+
+        if left_scalar {
+            Ok(GlslType::BASIC(scalar,*c2,*r2))
+        } else if right_scalar || (c1 == c2 && r1 == r2) {
+            Ok(GlslType::BASIC(scalar,*c1,*r1))
+        } else {
+            mismatch()
+        }
+
+    };
+    //
+    match op {
+
+        BinaryOperator::AND | BinaryOperator::OR | BinaryOperator::XOR => {
+
+            match scalar == ScalarType::BOOL && left_scalar && right_scalar {
+
+                true => Ok(GlslType::BASIC(ScalarType::BOOL,1,1)),
+                false => mismatch()
+
+            }
+
+        },
+
+        BinaryOperator::LT | BinaryOperator::GT | BinaryOperator::LE | BinaryOperator::GE => {
+
+            match scalar != ScalarType::BOOL && left_scalar && right_scalar {
+
+                true => Ok(GlslType::BASIC(ScalarType::BOOL,1,1)),
+                false => mismatch()
+
+            }
+
+        },
+
+        BinaryOperator::MOD | BinaryOperator::BIT_AND | BinaryOperator::BIT_OR | BinaryOperator::BIT_XOR => {
+
+            match matches!(scalar,ScalarType::INT | ScalarType::UINT) && *c1 == 1 && *c2 == 1 {
+
+                true => component_wise(scalar),
+                false => mismatch()
+
+            }
+
+        },
+        //
+        // the shifted value keep its type, the shift can be an int or an uint
+        BinaryOperator::SHL | BinaryOperator::SHR => {
+
+            let integers = matches!(s1,ScalarType::INT | ScalarType::UINT) &&
+                matches!(s2,ScalarType::INT | ScalarType::UINT);
+
+            match integers && *c1 == 1 && *c2 == 1 && (right_scalar || (r1 == r2 && !left_scalar)) {
+
+                true => Ok(left.clone()),
+                false => mismatch()
+
+            }
+
+        },
+
+        _ if scalar == ScalarType::BOOL => mismatch(),
+        //
+        // linear algebra products
+        BinaryOperator::MUL if !left_scalar && !right_scalar && (*c1 > 1 || *c2 > 1) => {
+
+            match (*c1 > 1,*c2 > 1) {
+
+                // matrix * matrix and matrix * vector
+                (true,_) if c1 == r2 => Ok(GlslType::BASIC(scalar,*c2,*r1)),
+                // vector * matrix
+                (false,true) if r1 == r2 => Ok(GlslType::BASIC(scalar,1,*c2)),
+                _ => mismatch()
+
+            }
+
+        },
+
+        _ => component_wise(scalar)
+
+    }
+    //
+}
+//
+//
+/// Check if a block is the declaration of a struct, a block without storage qualifier
+///     example: 'struct Light { vec3 color; };'
+fn is_struct(block:&InterfaceBlock) -> bool {
+
+    ![StorageQualifier::UNIFORM,StorageQualifier::BUFFER,StorageQualifier::IN,StorageQualifier::OUT]
+        .iter()
+        .any(|q| block.has_qualifier(q))
+
+}
+//
+//
+/// The type built by a constructor and the check of its arguments
+fn constructor_type(ty:GlslType,args:&[GlslType]) -> Result<GlslType,String> {
+    //
+    let GlslType::BASIC(_,columns,rows) = ty else {
+        return Ok(ty);
+    };
+    //
+    if args.is_empty() {
+        return Err(format!("the constructor of {} needs arguments",ty));
+    }
+    //
+    let mut sizes:Vec<usize> = Vec::new();
+
+    for arg in args {
+
+        match arg {
+
+            GlslType::BASIC(_,c,r) => sizes.push(c * r),
+            GlslType::UNKNOWN => return Ok(ty),
+            arg => return Err(format!("cant use a {} to construct a {}",arg,ty))
+
+        }
+
+    }
+    //
+    // one scalar fill the vector or the diagonal, a matrix can be built from any matrix
+    if args.len() == 1 && (sizes[0] == 1 || matches!(args[0],GlslType::BASIC(_,c,_) if c > 1 && columns > 1)) {
+        return Ok(ty);
+    }
+
+    if args.iter().any(|a| matches!(a,GlslType::BASIC(_,c,_) if *c > 1)) && columns > 1 {
+        return Err(format!("a matrix argument must be alone to construct a {}",ty));
+    }
+    //
+    let needed = columns * rows;
+    let total:usize = sizes.iter().sum();
+
+    if total < needed {
+        return Err(format!("not enough components to construct a {}: {} instead of {}",ty,total,needed));
+    }
+
+    if total - sizes[sizes.len() - 1] >= needed {
+        return Err(format!("too many arguments to construct a {}",ty));
+    }
+
+    Ok(ty)
+    //
+}
+//
+//
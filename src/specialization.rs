@@ -0,0 +1,392 @@
+//
+// ------------------------------------------------------------------------------------------
+// Specialization constants
+//
+// The constants of vulkan glsl declared with 'layout(constant_id = N)' and the sizes of the
+// work group given by 'local_size_x_id'. The application changes their value when it
+// creates the pipeline, so the reflection is the one of their default values until it is
+// specialized with the values of the application
+//
+use std::collections::HashMap;
+use crate::{ShaderFileInfo,EParser};
+use crate::lexer::Span;
+use crate::literal::Literal;
+use crate::constant::ConstValue;
+use crate::remap::{layout_parentheses,layout_edits};
+use crate::datatype::{DeclarationLine,StorageQualifier,LayoutVarType,ScalarType};
+//
+//
+/// The layout variables of the size of the work group and of their ids, by axis
+const LOCAL_SIZES: [(&str,LayoutVarType,LayoutVarType);3] = [
+    ("local_size_x",LayoutVarType::LOCAL_SIZE_X,LayoutVarType::LOCAL_SIZE_X_ID),
+    ("local_size_y",LayoutVarType::LOCAL_SIZE_Y,LayoutVarType::LOCAL_SIZE_Y_ID),
+    ("local_size_z",LayoutVarType::LOCAL_SIZE_Z,LayoutVarType::LOCAL_SIZE_Z_ID),
+];
+//
+//
+/// What the value of a specialization constant changes in the reflection
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum SpecializationUse {
+
+    // the size of the array of a variable, of a block or of a member of a block
+    ARRAY_SIZE(String),
+    // a constant whose value is computed from it
+    CONSTANT(String),
+    // the size of the work group on an axis, 0 for x
+    LOCAL_SIZE(usize),
+
+}
+//
+//
+/// A constant whose value the application can change
+#[derive(Debug,PartialEq,Clone)]
+pub struct SpecializationConstant {
+
+    pub id:         u32,
+    // None for a size of the work group that no constant declares
+    pub name:       Option<String>,
+    pub scalar:     ScalarType,
+    pub default:    Literal,
+    // in the order of the declarations
+    pub uses:       Vec<SpecializationUse>,
+    // the declaration of the constant or the one of the work group
+    pub span:       Span,
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// The specialization constants of the shader sorted by id, with where their values
+    /// are used. A constant computed from one is a use of it and what it is used for too
+    pub fn specialization_constants(&self) -> Vec<SpecializationConstant> {
+
+        let mut constants:Vec<SpecializationConstant> = Vec::new();
+        // the constants that change when a specialization constant changes, by name
+        let mut depends:HashMap<&str,Vec<u32>> = HashMap::new();
+
+        for declaration in self.declarations() {
+
+            match declaration {
+
+                DeclarationLine::VARIABLE(v) => {
+
+                    let (text,value) = split_value(&self.source()[v.span().start..v.span().end]);
+
+                    for (name,size) in array_sizes(text) {
+
+                        let ids = dependencies(size,&depends);
+                        add_use(&mut constants,&ids,SpecializationUse::ARRAY_SIZE(name.to_string()));
+
+                    }
+
+                    if !v.has_qualifier(&StorageQualifier::CONST) {
+                        continue;
+                    }
+
+                    match (v.layout_value(LayoutVarType::CONSTANT_ID),v.value()) {
+
+                        (Some(id),Some(ConstValue::SCALAR(default))) => {
+
+                            constants.push(SpecializationConstant {
+                                id,
+                                name: Some(v.name().to_string()),
+                                scalar: ScalarType::of(default),
+                                default: *default,
+                                uses: Vec::new(),
+                                span: v.span()
+                            });
+
+                            depends.insert(v.name(),vec![id]);
+
+                        },
+
+                        _ => {
+
+                            let ids = dependencies(value.unwrap_or(""),&depends);
+
+                            if !ids.is_empty() {
+
+                                add_use(&mut constants,&ids,SpecializationUse::CONSTANT(v.name().to_string()));
+                                depends.insert(v.name(),ids);
+
+                            }
+
+                        }
+
+                    }
+
+                },
+
+                DeclarationLine::BLOCK(b) => {
+
+                    for (name,size) in array_sizes(&self.source()[b.span().start..b.span().end]) {
+
+                        let ids = dependencies(size,&depends);
+                        add_use(&mut constants,&ids,SpecializationUse::ARRAY_SIZE(name.to_string()));
+
+                    }
+
+                },
+
+                DeclarationLine::DEFAULT_QUALIFIER(qualifiers,span) if qualifiers.contains(&StorageQualifier::IN) => {
+
+                    for (axis,(_,size,id)) in LOCAL_SIZES.iter().enumerate() {
+
+                        let Some(id) = layout_value(qualifiers,*id) else { continue };
+
+                        if !constants.iter().any(|c| c.id == id) {
+
+                            constants.push(SpecializationConstant {
+                                id,
+                                name: None,
+                                scalar: ScalarType::UINT,
+                                default: Literal::UINT(layout_value(qualifiers,*size).unwrap_or(1)),
+                                uses: Vec::new(),
+                                span: *span
+                            });
+
+                        }
+
+                        add_use(&mut constants,&[id],SpecializationUse::LOCAL_SIZE(axis));
+
+                    }
+
+                },
+
+                _ => {}
+
+            }
+
+        }
+
+        constants.sort_by_key(|c| c.id);
+
+        constants
+
+    }
+    //
+    /// The size of the work group of a compute shader, None when the shader doesn't
+    /// declare it. An axis not written is 1
+    pub fn local_size(&self) -> Option<[u32;3]> {
+
+        self.declarations().iter().find_map(|d| match d {
+
+            DeclarationLine::DEFAULT_QUALIFIER(qualifiers,_) if qualifiers.contains(&StorageQualifier::IN) => {
+
+                let sizes = LOCAL_SIZES.map(|(_,size,id)| (layout_value(qualifiers,size),layout_value(qualifiers,id)));
+
+                match sizes.iter().any(|(size,id)| size.is_some() || id.is_some()) {
+
+                    true => Some(sizes.map(|(size,_)| size.unwrap_or(1))),
+                    false => None
+
+                }
+
+            },
+
+            _ => None
+
+        })
+
+    }
+    //
+    /// The source with the values of the specialization constants in their declarations
+    /// and the sizes of the work group written in its layout. The values are converted to
+    /// the type of the constants and the ids without value keep their default
+    ///     example:
+    ///         'layout(constant_id = 3) const int SAMPLES = 4;' => 'layout(constant_id = 3) const int SAMPLES = 8;'
+    ///         'layout(local_size_x_id = 0) in;'                => 'layout(local_size_x_id = 0, local_size_x = 64) in;'
+    ///
+    pub fn write_specialization(&self,values:&HashMap<u32,Literal>) -> String {
+
+        let mut source = self.source().to_string();
+        let mut edits:Vec<(usize,usize,String)> = Vec::new();
+
+        for declaration in self.declarations() {
+
+            match declaration {
+
+                DeclarationLine::VARIABLE(v) if v.has_qualifier(&StorageQualifier::CONST) => {
+
+                    let (Some(id),Some(ConstValue::SCALAR(default))) = (v.layout_value(LayoutVarType::CONSTANT_ID),v.value()) else { continue };
+                    let Some(value) = values.get(&id) else { continue };
+
+                    let text = &source[v.span().start..v.span().end];
+                    let (_,Some(written)) = split_value(text) else { continue };
+                    //
+                    // the value without the spaces around it and the ';'
+                    let start = v.span().start + text.len() - written.len() + (written.len() - written.trim_start().len());
+                    let end = start + written.trim().trim_end_matches(';').trim_end().len();
+
+                    edits.push((start,end,literal_text(ScalarType::of(default).convert(*value))));
+
+                },
+
+                DeclarationLine::DEFAULT_QUALIFIER(qualifiers,span) if qualifiers.contains(&StorageQualifier::IN) => {
+
+                    let sizes:Vec<(&str,u32)> = LOCAL_SIZES.iter().filter_map(|(name,_,id)| {
+
+                        let value = values.get(&layout_value(qualifiers,*id)?)?;
+
+                        match ScalarType::UINT.convert(*value) {
+
+                            Literal::UINT(size) => Some((*name,size)),
+                            _ => None
+
+                        }
+
+                    }).collect();
+
+                    let text = &source[span.start..span.end];
+
+                    if let (false,Some(parentheses)) = (sizes.is_empty(),layout_parentheses(text)) {
+                        edits.extend(layout_edits(text,span.start,parentheses,&sizes));
+                    }
+
+                },
+
+                _ => {}
+
+            }
+
+        }
+        //
+        // from the end so the positions before stay right
+        edits.sort_by_key(|(start,_,_)| std::cmp::Reverse(*start));
+
+        for (start,end,text) in edits {
+            source.replace_range(start..end,&text);
+        }
+
+        source
+
+    }
+    //
+    /// A copy of the reflection with the values of the specialization constants: the
+    /// sizes of the arrays and of the work group and the constants computed from them. The
+    /// parsers of pragmas registered are not in the copy
+    ///
+    /// # parameters
+    ///
+    /// * values - the value of the constants by id
+    ///
+    /// # Error causes
+    ///
+    /// * A declaration is wrong with the values (an array size of 0 for example)
+    ///
+    pub fn specialize(&self,values:&HashMap<u32,Literal>) -> Result<ShaderFileInfo,EParser> {
+
+        let mut info = match self.shader_type() {
+
+            Some(stage) => ShaderFileInfo::with_shader_type(stage),
+            None => ShaderFileInfo::new()
+
+        };
+
+        info.parse_line(self.write_specialization(values).as_bytes())?;
+
+        Ok(info)
+
+    }
+    //
+}
+//
+//
+/// Find the value of a layout variable in the qualifiers of a declaration
+fn layout_value(qualifiers:&[StorageQualifier],var:LayoutVarType) -> Option<u32> {
+
+    qualifiers.iter().find_map(|q| match q {
+
+        StorageQualifier::LAYOUT(layout) => layout.get(var),
+        _ => None
+
+    })
+
+}
+//
+//
+/// Split a declaration at the '=' of its value. The '=' of the layout are not the one of
+/// the value
+fn split_value(text:&str) -> (&str,Option<&str>) {
+
+    let start = layout_parentheses(text).map_or(0,|(_,close)| close);
+
+    match text[start..].find('=') {
+
+        Some(i) => (&text[..start + i],Some(&text[start + i + 1..])),
+        None => (text,None)
+
+    }
+
+}
+//
+//
+/// The names declared as arrays in a declaration with the expression of their size
+///     example:
+///         'uniform Lights { vec4 colors[N]; } lights[M];' => [("colors","N"),("lights","M")]
+///
+fn array_sizes(text:&str) -> Vec<(&str,&str)> {
+
+    text.match_indices('[').filter_map(|(open,_)| {
+
+        let before = text[..open].trim_end();
+        let name = &before[before.rfind(|c:char| !(c.is_alphanumeric() || c == '_')).map_or(0,|i| i + 1)..];
+        let close = open + text[open..].find(']')?;
+
+        Some((name,&text[open + 1..close]))
+
+    }).collect()
+
+}
+//
+//
+/// The ids of the specialization constants an expression depends on
+fn dependencies(expression:&str,depends:&HashMap<&str,Vec<u32>>) -> Vec<u32> {
+
+    let mut ids:Vec<u32> = expression
+        .split(|c:char| !(c.is_alphanumeric() || c == '_'))
+        .filter_map(|w| depends.get(w))
+        .flatten()
+        .copied()
+        .collect();
+
+    ids.sort();
+    ids.dedup();
+
+    ids
+
+}
+//
+//
+/// Add a use to the specialization constants of the ids, once
+fn add_use(constants:&mut [SpecializationConstant],ids:&[u32],used:SpecializationUse) {
+
+    for constant in constants.iter_mut().filter(|c| ids.contains(&c.id)) {
+
+        if !constant.uses.contains(&used) {
+            constant.uses.push(used.clone());
+        }
+
+    }
+
+}
+//
+//
+/// The text of a literal in glsl
+fn literal_text(lit:Literal) -> String {
+
+    match lit {
+
+        Literal::BOOL(v) => v.to_string(),
+        Literal::INT(v) => v.to_string(),
+        Literal::UINT(v) => format!("{}u",v),
+        Literal::FLOAT(v) => format!("{:?}",v),
+        Literal::DOUBLE(v) => format!("{:?}lf",v),
+
+    }
+
+}
+//
+//
@@ -0,0 +1,420 @@
+//
+// ------------------------------------------------------------------------------------------
+// Statements
+//
+// Parse the body of the functions to a tree following the section 6 of the GLSL
+// specification so the control flow of a shader can be walked
+//
+use crate::EParser;
+use crate::lexer::Span;
+use crate::expression::{Expression,ExpressionKind,Parser};
+//
+//
+/// A statement of a function body and the part of the source it comes from
+#[derive(Debug,PartialEq,Clone)]
+pub struct Statement {
+
+    pub kind:   StatementKind,
+    pub span:   Span,
+
+}
+//
+//
+/// The different kind of statement
+///     example:
+///         'float a = 1.0, b;'             => DECLARATION
+///         'a += b;'                       => EXPRESSION(ASSIGNMENT(..))
+///         'for (int i = 0; i < 4; i++) {}' => FOR(Some(DECLARATION),Some(i < 4),Some(i++),BLOCK)
+///
+#[allow(non_camel_case_types)]
+#[derive(Debug,PartialEq,Clone)]
+pub enum StatementKind {
+
+    DECLARATION (LocalDeclaration),
+    EXPRESSION  (Expression),
+    BLOCK       (Vec<Statement>),
+    // condition, then, else
+    IF          (Expression,Box<Statement>,Option<Box<Statement>>),
+    // init, condition, step, body
+    FOR         (Option<Box<Statement>>,Option<Expression>,Option<Expression>,Box<Statement>),
+    WHILE       (Expression,Box<Statement>),
+    DO_WHILE    (Box<Statement>,Expression),
+    // the body is a list of statements where the labels are CASE and DEFAULT
+    SWITCH      (Expression,Vec<Statement>),
+    CASE        (Expression),
+    DEFAULT,
+    BREAK,
+    CONTINUE,
+    RETURN      (Option<Expression>),
+    DISCARD,
+    // the 'barrier()' of the compute and tessellation control shaders
+    BARRIER,
+    // a ';' alone
+    EMPTY,
+
+}
+//
+//
+/// A variable declared in a function ('const float a = 1.0, b[2];')
+#[derive(Debug,PartialEq,Clone)]
+pub struct LocalDeclaration {
+
+    // 'const', precision and 'precise' qualifiers as written
+    pub qualifiers:     Vec<String>,
+    pub type_name:      String,
+    // when the array is on the type ('float[2] a'). Some(None) is an array without size
+    pub type_array:     Option<Option<Expression>>,
+    pub declarators:    Vec<Declarator>,
+
+}
+//
+//
+/// One of the variables of a declaration
+#[derive(Debug,PartialEq,Clone)]
+pub struct Declarator {
+
+    pub name:           String,
+    // Some(None) is an array without size ('float a[] = float[](1.0)')
+    pub array:          Option<Option<Expression>>,
+    pub initializer:    Option<Expression>,
+    pub span:           Span,
+
+}
+//
+//
+/// The qualifiers a local variable can have
+const LOCAL_QUALIFIERS: [&str;5] = ["const", "highp", "mediump", "lowp", "precise"];
+//
+//
+impl Parser<'_> {
+    //
+    /// Parse the statements until the '}' that close the current block. The '}' is consumed
+    pub(crate) fn statements_until_close(&mut self) -> Result<Vec<Statement>,EParser> {
+
+        let mut statements:Vec<Statement> = Vec::new();
+
+        while !self.accept("}") {
+
+            if self.is_end() {
+                return Err(self.error("expected '}'".to_string()));
+            }
+
+            statements.push(self.statement()?);
+
+        }
+
+        Ok(statements)
+
+    }
+    //
+    /// Parse one statement
+    pub(crate) fn statement(&mut self) -> Result<Statement,EParser> {
+
+        let start = match self.peek() {
+
+            Some(t) => t.span,
+            None => return Err(self.error("expected a statement".to_string()))
+
+        };
+
+        let keyword = self.peek().and_then(|t| t.identifier()).unwrap_or("");
+
+        let kind = match keyword {
+
+            "if" => {
+
+                self.next();
+                let condition = self.condition()?;
+                let then = Box::new(self.statement()?);
+
+                let otherwise = match self.peek().and_then(|t| t.identifier()) == Some("else") {
+
+                    true => { self.next(); Some(Box::new(self.statement()?)) },
+                    false => None
+
+                };
+
+                StatementKind::IF(condition,then,otherwise)
+
+            },
+
+            "for" => {
+
+                self.next();
+                self.expect("(")?;
+
+                let init = match self.accept(";") {
+
+                    true => None,
+                    false => Some(Box::new(self.simple_statement()?))
+
+                };
+
+                let condition = match self.check(";") {
+
+                    true => None,
+                    false => Some(self.expression()?)
+
+                };
+
+                self.expect(";")?;
+
+                let step = match self.check(")") {
+
+                    true => None,
+                    false => Some(self.expression()?)
+
+                };
+
+                self.expect(")")?;
+
+                StatementKind::FOR(init,condition,step,Box::new(self.statement()?))
+
+            },
+
+            "while" => {
+
+                self.next();
+                let condition = self.condition()?;
+
+                StatementKind::WHILE(condition,Box::new(self.statement()?))
+
+            },
+
+            "do" => {
+
+                self.next();
+                let body = Box::new(self.statement()?);
+
+                if self.peek().and_then(|t| t.identifier()) != Some("while") {
+                    return Err(self.error("expected 'while'".to_string()));
+                }
+
+                self.next();
+                let condition = self.condition()?;
+                self.expect(";")?;
+
+                StatementKind::DO_WHILE(body,condition)
+
+            },
+
+            "switch" => {
+
+                self.next();
+                let value = self.condition()?;
+                self.expect("{")?;
+
+                StatementKind::SWITCH(value,self.statements_until_close()?)
+
+            },
+
+            "case" => {
+
+                self.next();
+                let value = self.expression()?;
+                self.expect(":")?;
+
+                StatementKind::CASE(value)
+
+            },
+
+            "default" => {
+
+                self.next();
+                self.expect(":")?;
+
+                StatementKind::DEFAULT
+
+            },
+
+            "break" | "continue" | "discard" => {
+
+                self.next();
+                self.expect(";")?;
+
+                match keyword {
+
+                    "break" => StatementKind::BREAK,
+                    "continue" => StatementKind::CONTINUE,
+                    _ => StatementKind::DISCARD
+
+                }
+
+            },
+
+            "return" => {
+
+                self.next();
+
+                let value = match self.check(";") {
+
+                    true => None,
+                    false => Some(self.expression()?)
+
+                };
+
+                self.expect(";")?;
+
+                StatementKind::RETURN(value)
+
+            },
+
+            _ if self.accept("{") => StatementKind::BLOCK(self.statements_until_close()?),
+
+            _ => return self.simple_statement()
+
+        };
+
+        Ok(Statement { kind, span: start.merge(self.previous_span()) })
+
+    }
+    //
+    /// A declaration, an expression or nothing followed by a ';'
+    fn simple_statement(&mut self) -> Result<Statement,EParser> {
+
+        let start = self.peek().map(|t| t.span).unwrap_or_default();
+
+        let kind = if self.accept(";") {
+
+            StatementKind::EMPTY
+
+        } else if self.is_declaration() {
+
+            StatementKind::DECLARATION(self.local_declaration()?)
+
+        } else {
+
+            let expr = self.expression()?;
+            self.expect(";")?;
+
+            match &expr.kind {
+
+                ExpressionKind::CALL(name,args) if name == "barrier" && args.is_empty() => StatementKind::BARRIER,
+                _ => StatementKind::EXPRESSION(expr)
+
+            }
+
+        };
+
+        Ok(Statement { kind, span: start.merge(self.previous_span()) })
+
+    }
+    //
+    /// '(' expression ')'
+    fn condition(&mut self) -> Result<Expression,EParser> {
+
+        self.expect("(")?;
+        let condition = self.expression()?;
+        self.expect(")")?;
+
+        Ok(condition)
+
+    }
+    //
+    /// Look ahead to find if the next tokens are a declaration. A declaration start with a
+    /// qualifier or with a type followed by a name ('vec3 a', 'Light l', 'float[2] a')
+    fn is_declaration(&self) -> bool {
+
+        let first = match self.peek().and_then(|t| t.identifier()) {
+
+            Some(f) => f,
+            None => return false
+
+        };
+
+        if LOCAL_QUALIFIERS.contains(&first) {
+            return true;
+        }
+
+        let mut n:usize = 1;
+
+        if self.peek_at(n).is_some_and(|t| t.is_symbol("[")) {
+
+            let mut depth:usize = 0;
+
+            while let Some(t) = self.peek_at(n) {
+
+                if t.is_symbol("[") {
+                    depth += 1;
+                } else if t.is_symbol("]") {
+                    depth -= 1;
+                }
+
+                n += 1;
+
+                if depth == 0 {
+                    break;
+                }
+
+            }
+
+        }
+
+        self.peek_at(n).and_then(|t| t.identifier()).is_some()
+
+    }
+    //
+    /// qualifiers* type array? declarator (',' declarator)* ';'
+    fn local_declaration(&mut self) -> Result<LocalDeclaration,EParser> {
+
+        let mut qualifiers:Vec<String> = Vec::new();
+
+        while let Some(q) = self.peek().and_then(|t| t.identifier()).filter(|q| LOCAL_QUALIFIERS.contains(q)) {
+
+            qualifiers.push(q.to_string());
+            self.next();
+
+        }
+
+        let (type_name,_) = self.expect_identifier()?;
+        let type_array = self.array_declarator()?;
+
+        let mut declarators:Vec<Declarator> = Vec::new();
+
+        loop {
+
+            let (name,start) = self.expect_identifier()?;
+            let array = self.array_declarator()?;
+
+            let initializer = match self.accept("=") {
+
+                true => Some(self.assignment()?),
+                false => None
+
+            };
+
+            declarators.push(Declarator { name, array, initializer, span: start.merge(self.previous_span()) });
+
+            if !self.accept(",") {
+                break;
+            }
+
+        }
+
+        self.expect(";")?;
+
+        Ok(LocalDeclaration { qualifiers, type_name, type_array, declarators })
+
+    }
+    //
+    /// '[' expression? ']'
+    fn array_declarator(&mut self) -> Result<Option<Option<Expression>>,EParser> {
+
+        if !self.accept("[") {
+            return Ok(None);
+        }
+
+        if self.accept("]") {
+            return Ok(Some(None));
+        }
+
+        let size = self.expression()?;
+        self.expect("]")?;
+
+        Ok(Some(Some(size)))
+
+    }
+    //
+}
+//
+//
@@ -0,0 +1,438 @@
+//
+// ------------------------------------------------------------------------------------------
+// Static use
+//
+// A variable is statically used when a function reachable from 'main' reads or writes it.
+// The drivers only give a location to the uniforms and the attributes used this way (the
+// "active" ones), the others are removed when the program is linked
+//
+use std::collections::HashSet;
+use crate::ShaderFileInfo;
+use crate::datatype::{DeclarationLine,ShaderVariables,InterfaceBlock};
+use crate::expression::{Expression,ExpressionKind,UnaryOperator};
+use crate::statement::{Statement,StatementKind};
+//
+//
+/// What the functions reachable from 'main' use
+struct Usage<'a> {
+
+    info:           &'a ShaderFileInfo,
+    // the instance names of the blocks, their members are accessed through them
+    instances:      HashSet<&'a str>,
+    // the local variables and parameters of the function walked, one set by scope
+    scopes:         Vec<HashSet<&'a str>>,
+    // the globals used by their name, a block instance used as a whole is one of them
+    names:          HashSet<&'a str>,
+    // the members used through the instance of their block ('light.color')
+    members:        HashSet<(&'a str,&'a str)>,
+    // the globals assigned, a part of them ('color.rgb = ...') is enough
+    written:        HashSet<&'a str>,
+    discards:       bool,
+    visited:        HashSet<&'a str>,
+    pending:        Vec<&'a str>,
+
+}
+//
+impl<'a> Usage<'a> {
+    //
+    /// Walk every function that 'main' can call
+    fn walk(info:&'a ShaderFileInfo) -> Self {
+
+        let mut usage = Usage {
+            info,
+            instances: info.blocks().filter_map(|b| b.instance_name()).collect(),
+            scopes: Vec::new(),
+            names: HashSet::new(),
+            members: HashSet::new(),
+            written: HashSet::new(),
+            discards: false,
+            visited: HashSet::new(),
+            pending: vec!["main"]
+        };
+
+        while let Some(name) = usage.pending.pop() {
+
+            if !usage.visited.insert(name) {
+                continue;
+            }
+            //
+            // the overloads are not told apart, all of them are walked
+            for function in usage.info.functions().filter(|f| f.name == name) {
+
+                let Some(body) = &function.body else { continue };
+
+                usage.scopes.push(function.parameters.iter().filter_map(|p| p.name.as_deref()).collect());
+
+                for statement in body.statements.iter() {
+                    usage.statement(statement);
+                }
+
+                usage.scopes.pop();
+
+            }
+
+        }
+
+        usage
+
+    }
+    //
+    fn is_local(&self,name:&str) -> bool { self.scopes.iter().any(|s| s.contains(name)) }
+    //
+    fn scoped(&mut self,statement:&'a Statement) {
+
+        self.scopes.push(HashSet::new());
+        self.statement(statement);
+        self.scopes.pop();
+
+    }
+    //
+    fn statement(&mut self,statement:&'a Statement) {
+
+        match &statement.kind {
+
+            StatementKind::DECLARATION(declaration) => {
+
+                if let Some(Some(size)) = &declaration.type_array {
+                    self.expression(size);
+                }
+                //
+                // a variable is visible after its initializer
+                for declarator in declaration.declarators.iter() {
+
+                    if let Some(Some(size)) = &declarator.array {
+                        self.expression(size);
+                    }
+
+                    if let Some(value) = &declarator.initializer {
+                        self.expression(value);
+                    }
+
+                    if let Some(scope) = self.scopes.last_mut() {
+                        scope.insert(declarator.name.as_str());
+                    }
+
+                }
+
+            },
+
+            StatementKind::EXPRESSION(expr) | StatementKind::CASE(expr) | StatementKind::RETURN(Some(expr)) => self.expression(expr),
+
+            StatementKind::BLOCK(statements) => {
+
+                self.scopes.push(HashSet::new());
+
+                for s in statements.iter() {
+                    self.statement(s);
+                }
+
+                self.scopes.pop();
+
+            },
+
+            StatementKind::IF(condition,then,otherwise) => {
+
+                self.expression(condition);
+                self.scoped(then);
+
+                if let Some(otherwise) = otherwise {
+                    self.scoped(otherwise);
+                }
+
+            },
+
+            StatementKind::FOR(init,condition,step,body) => {
+
+                self.scopes.push(HashSet::new());
+
+                if let Some(init) = init {
+                    self.statement(init);
+                }
+
+                for expr in [condition,step].into_iter().flatten() {
+                    self.expression(expr);
+                }
+
+                self.scoped(body);
+                self.scopes.pop();
+
+            },
+
+            StatementKind::WHILE(condition,body) | StatementKind::DO_WHILE(body,condition) => {
+
+                self.expression(condition);
+                self.scoped(body);
+
+            },
+
+            StatementKind::SWITCH(value,statements) => {
+
+                self.expression(value);
+                self.scopes.push(HashSet::new());
+
+                for s in statements.iter() {
+                    self.statement(s);
+                }
+
+                self.scopes.pop();
+
+            },
+
+            StatementKind::DISCARD => self.discards = true,
+
+            _ => {}
+
+        }
+
+    }
+    //
+    fn expression(&mut self,expr:&'a Expression) {
+
+        match &expr.kind {
+
+            ExpressionKind::LITERAL(_) => {},
+
+            ExpressionKind::IDENTIFIER(name) => {
+
+                if !self.is_local(name) {
+                    self.names.insert(name.as_str());
+                }
+
+            },
+
+            ExpressionKind::FIELD(base,field) => match self.instance(base) {
+                //
+                // only the member is used, not the whole block
+                Some((instance,index)) => {
+
+                    self.members.insert((instance,field.as_str()));
+
+                    if let Some(index) = index {
+                        self.expression(index);
+                    }
+
+                },
+                None => self.expression(base)
+
+            },
+
+            ExpressionKind::CALL(name,arguments) => {
+
+                if !self.is_local(name) && !self.visited.contains(name.as_str()) {
+                    self.pending.push(name.as_str());
+                }
+
+                for a in arguments.iter() {
+                    self.expression(a);
+                }
+
+            },
+
+            ExpressionKind::ARRAY_CONSTRUCTOR(_,size,arguments) => {
+
+                if let Some(size) = size {
+                    self.expression(size);
+                }
+
+                for a in arguments.iter() {
+                    self.expression(a);
+                }
+
+            },
+
+            ExpressionKind::UNARY(operator,operand) => {
+
+                if matches!(
+                    operator,
+                    UnaryOperator::PRE_INCREMENT | UnaryOperator::PRE_DECREMENT |
+                    UnaryOperator::POST_INCREMENT | UnaryOperator::POST_DECREMENT
+                ) {
+                    self.write(operand);
+                }
+
+                self.expression(operand)
+
+            },
+
+            ExpressionKind::ASSIGNMENT(_,left,right) => {
+
+                self.write(left);
+                self.expression(left);
+                self.expression(right);
+
+            },
+
+            ExpressionKind::BINARY(_,left,right) |
+            ExpressionKind::INDEX(left,right) => {
+
+                self.expression(left);
+                self.expression(right);
+
+            },
+
+            ExpressionKind::TERNARY(condition,a,b) => {
+
+                self.expression(condition);
+                self.expression(a);
+                self.expression(b);
+
+            },
+
+            ExpressionKind::METHOD(base,_,arguments) => {
+
+                self.expression(base);
+
+                for a in arguments.iter() {
+                    self.expression(a);
+                }
+
+            },
+
+            ExpressionKind::SEQUENCE(expressions) => {
+
+                for e in expressions.iter() {
+                    self.expression(e);
+                }
+
+            }
+
+        }
+
+    }
+    //
+    /// Record the global an assignment writes ('color', 'color.rgb', 'colors[i]')
+    fn write(&mut self,target:&'a Expression) {
+
+        match &target.kind {
+
+            ExpressionKind::IDENTIFIER(name) if !self.is_local(name) => { self.written.insert(name.as_str()); },
+            ExpressionKind::INDEX(base,_) | ExpressionKind::FIELD(base,_) => self.write(base),
+            _ => {}
+
+        }
+
+    }
+    //
+    /// The block instance an expression is, with its index when the block is an array
+    /// ('light' or 'lights[i]')
+    fn instance(&self,expr:&'a Expression) -> Option<(&'a str,Option<&'a Expression>)> {
+
+        let (name,index) = match &expr.kind {
+
+            ExpressionKind::IDENTIFIER(name) => (name,None),
+            ExpressionKind::INDEX(base,index) => match &base.kind {
+
+                ExpressionKind::IDENTIFIER(name) => (name,Some(index.as_ref())),
+                _ => return None
+
+            },
+            _ => return None
+
+        };
+
+        match self.instances.contains(name.as_str()) && !self.is_local(name) {
+
+            true => Some((name.as_str(),index)),
+            false => None
+
+        }
+
+    }
+    //
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// The variables used by the functions reachable from 'main', the ones a driver
+    /// keeps active. The members of the blocks are not in it
+    pub fn used_variables(&self) -> impl Iterator<Item = &ShaderVariables> {
+
+        self.variables().filter(|v| v.is_used())
+
+    }
+    //
+    /// The variables declared but never used from 'main', a driver removes them and
+    /// doesn't give them a location
+    pub fn unused_variables(&self) -> impl Iterator<Item = &ShaderVariables> {
+
+        self.variables().filter(|v| !v.is_used())
+
+    }
+    //
+    /// The names of the functions of the shader that 'main' calls, directly or through
+    /// other functions, with 'main' itself. The built-in functions are not in it
+    pub fn reachable_functions(&self) -> Vec<String> {
+
+        let mut names:Vec<String> = Usage::walk(self).visited
+            .into_iter()
+            .filter(|name| self.function(name).is_some())
+            .map(str::to_string)
+            .collect();
+        names.sort();
+
+        names
+
+    }
+    //
+    /// Check if a function reachable from 'main' assigns a global or a built-in variable,
+    /// or a part of it ('gl_FragDepth', 'color.a')
+    pub fn writes_variable(&self,name:&str) -> bool { Usage::walk(self).written.contains(name) }
+    //
+    /// Check if a function reachable from 'main' has a 'discard'
+    pub fn uses_discard(&self) -> bool { Usage::walk(self).discards }
+    //
+    /// Mark every variable and block member with its static use. Done after the
+    /// whole shader is parsed because 'main' can call a function declared after it
+    pub(crate) fn mark_static_use(&mut self) {
+
+        let (names,members):(HashSet<String>,HashSet<(String,String)>) = {
+
+            let usage = Usage::walk(self);
+
+            (
+                usage.names.iter().map(|n| n.to_string()).collect(),
+                usage.members.iter().map(|(i,m)| (i.to_string(),m.to_string())).collect()
+            )
+
+        };
+
+        for declaration in self.declarations.iter_mut() {
+
+            match declaration {
+
+                DeclarationLine::VARIABLE(var) => var.set_used(names.contains(var.name())),
+                DeclarationLine::BLOCK(block) => mark_block(block,&names,&members),
+                _ => {}
+
+            }
+
+        }
+
+    }
+    //
+}
+//
+//
+/// Mark the members of a block. Without an instance name they are used by their own
+/// name, with one they are all used when the instance is used as a whole
+fn mark_block(block:&mut InterfaceBlock,names:&HashSet<String>,members:&HashSet<(String,String)>) {
+
+    let instance = block.instance_name().map(str::to_string);
+
+    for member in block.members_mut() {
+
+        let used = match &instance {
+
+            Some(i) => names.contains(i) || members.contains(&(i.clone(),member.name().to_string())),
+            None => names.contains(member.name())
+
+        };
+
+        member.set_used(used);
+
+    }
+
+}
+//
+//
@@ -0,0 +1,452 @@
+//
+// ------------------------------------------------------------------------------------------
+// Version checks
+//
+// Find what a shader uses that doesn't exist in the version and the profile declared by its
+// '#version' line: keywords, qualifiers, layout qualifiers, types and built-ins. A driver
+// refuses them when the parser would accept them
+//
+use std::collections::HashSet;
+use std::fmt;
+use crate::ShaderFileInfo;
+use crate::lexer::{Span,Token,tokenize};
+use crate::datatype::VersionBranch;
+use crate::builtin::{Availability,ShaderTarget,available_functions,available_variable,builtin_functions,builtin_variables};
+//
+//
+/// Something used by a shader that its version doesn't have
+#[derive(Debug,PartialEq,Clone)]
+pub struct VersionIssue {
+
+    // how the feature is written ('buffer', 'layout(binding)', 'uint', 'imageStore')
+    pub feature:            String,
+    // the first use of the feature
+    pub span:               Span,
+    // the first version of the profile of the shader that has it. None if the profile
+    // never has it
+    pub minimum_version:    Option<u16>,
+    // the version where the profile of the shader removed it
+    pub removed_in:         Option<u16>,
+    // the extension that make it available without changing the version
+    pub extension:          Option<&'static str>,
+    pub branch:             VersionBranch,
+
+}
+//
+impl fmt::Display for VersionIssue {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        let profile = match self.branch {
+
+            VersionBranch::ES => " es",
+            VersionBranch::COMPATIBILITY => " compatibility",
+            _ => ""
+
+        };
+
+        match (self.removed_in,self.minimum_version,self.extension) {
+
+            (None,None,Some(extension)) => return write!(f,"'{}' needs the extension {}",self.feature,extension),
+
+            (Some(removed),_,_) if self.branch == VersionBranch::ES =>
+                write!(f,"'{}' was removed in glsl {} es",self.feature,removed)?,
+
+            (Some(removed),_,_) => write!(f,"'{}' was removed from the core profile in glsl {}",self.feature,removed)?,
+
+            (None,Some(version),_) => write!(f,"'{}' needs glsl {}{} or later",self.feature,version,profile)?,
+
+            (None,None,_) if self.branch == VersionBranch::ES => write!(f,"'{}' doesn't exist in glsl es",self.feature)?,
+
+            (None,None,_) => write!(f,"'{}' doesn't exist in desktop glsl",self.feature)?
+
+        }
+
+        match self.extension {
+
+            Some(extension) => write!(f,", or the extension {}",extension),
+            None => Ok(())
+
+        }
+
+    }
+
+}
+//
+//
+/// The storage, interpolation and memory qualifiers that glsl added over time
+const QUALIFIERS: [(&str,Availability);18] = [
+    //
+    ("attribute",       Availability::since(110,100).removed_in_core(140)),
+    ("varying",         Availability::since(110,100).removed_in_core(140)),
+    ("centroid",        Availability::since(120,300)),
+    ("invariant",       Availability::since(120,100)),
+    ("flat",            Availability::since(130,300)),
+    ("smooth",          Availability::since(130,300)),
+    ("noperspective",   Availability::desktop(130)),
+    ("patch",           Availability::since(400,320).extension("GL_ARB_tessellation_shader")),
+    ("sample",          Availability::since(400,320).extension("GL_ARB_gpu_shader5")),
+    ("precise",         Availability::since(400,320).extension("GL_ARB_gpu_shader5")),
+    ("subroutine",      Availability::desktop(400).extension("GL_ARB_shader_subroutine")),
+    ("buffer",          Availability::since(430,310).extension("GL_ARB_shader_storage_buffer_object")),
+    ("shared",          Availability::since(430,310).extension("GL_ARB_compute_shader")),
+    ("coherent",        Availability::since(420,310).extension("GL_ARB_shader_image_load_store")),
+    ("volatile",        Availability::since(420,310).extension("GL_ARB_shader_image_load_store")),
+    ("restrict",        Availability::since(420,310).extension("GL_ARB_shader_image_load_store")),
+    ("readonly",        Availability::since(420,310).extension("GL_ARB_shader_image_load_store")),
+    ("writeonly",       Availability::since(420,310).extension("GL_ARB_shader_image_load_store")),
+    //
+];
+//
+//
+/// The identifiers that can be in the parentheses of a layout qualifier
+const LAYOUT_QUALIFIERS: [(&str,Availability);26] = [
+    //
+    ("location",            Availability::since(330,300).extension("GL_ARB_explicit_attrib_location")),
+    ("index",               Availability::desktop(330).extension("GL_ARB_blend_func_extended")),
+    ("binding",             Availability::since(420,310).extension("GL_ARB_shading_language_420pack")),
+    ("component",           Availability::desktop(440).extension("GL_ARB_enhanced_layouts")),
+    ("offset",              Availability::since(440,310).extension("GL_ARB_enhanced_layouts")),
+    ("align",               Availability::desktop(440).extension("GL_ARB_enhanced_layouts")),
+    ("xfb_buffer",          Availability::desktop(440).extension("GL_ARB_enhanced_layouts")),
+    ("xfb_offset",          Availability::desktop(440).extension("GL_ARB_enhanced_layouts")),
+    ("xfb_stride",          Availability::desktop(440).extension("GL_ARB_enhanced_layouts")),
+    ("shared",              Availability::since(140,300).extension("GL_ARB_uniform_buffer_object")),
+    ("packed",              Availability::since(140,300).extension("GL_ARB_uniform_buffer_object")),
+    ("std140",              Availability::since(140,300).extension("GL_ARB_uniform_buffer_object")),
+    ("row_major",           Availability::since(140,300).extension("GL_ARB_uniform_buffer_object")),
+    ("column_major",        Availability::since(140,300).extension("GL_ARB_uniform_buffer_object")),
+    ("std430",              Availability::since(430,310).extension("GL_ARB_shader_storage_buffer_object")),
+    ("local_size_x",        Availability::since(430,310).extension("GL_ARB_compute_shader")),
+    ("local_size_y",        Availability::since(430,310).extension("GL_ARB_compute_shader")),
+    ("local_size_z",        Availability::since(430,310).extension("GL_ARB_compute_shader")),
+    ("early_fragment_tests",Availability::since(420,310).extension("GL_ARB_shader_image_load_store")),
+    ("origin_upper_left",   Availability::desktop(150).extension("GL_ARB_fragment_coord_conventions")),
+    ("pixel_center_integer",Availability::desktop(150).extension("GL_ARB_fragment_coord_conventions")),
+    ("invocations",         Availability::since(400,320).extension("GL_ARB_gpu_shader5")),
+    ("bindless_sampler",    Availability::only_with("GL_ARB_bindless_texture")),
+    ("bound_sampler",       Availability::only_with("GL_ARB_bindless_texture")),
+    ("bindless_image",      Availability::only_with("GL_ARB_bindless_texture")),
+    ("bound_image",         Availability::only_with("GL_ARB_bindless_texture")),
+    //
+];
+//
+//
+/// Where a type exists, None for the names that are not a type or that every version has
+fn type_availability(name:&str) -> Option<Availability> {
+    //
+    // the samplers of signed and unsigned integers came with the integers
+    let (integer,sampler) = match name.strip_prefix('i').or(name.strip_prefix('u')) {
+
+        Some(rest) if rest.starts_with("sampler") => (true,rest),
+        _ => (false,name)
+
+    };
+
+    let base = match sampler {
+
+        "uint" | "uvec2" | "uvec3" | "uvec4" => Availability::since(130,300),
+
+        "mat2x2" | "mat2x3" | "mat2x4" | "mat3x2" | "mat3x3" | "mat3x4" | "mat4x2" | "mat4x3" | "mat4x4" =>
+            Availability::since(120,300),
+
+        "atomic_uint" => Availability::since(420,310).extension("GL_ARB_shader_atomic_counters"),
+
+        "sampler1D" | "sampler1DShadow" => Availability::desktop(110),
+        "sampler3D" | "sampler2DShadow" => Availability::since(110,300),
+        "samplerCubeShadow" => Availability::since(130,300),
+        "sampler1DArray" | "sampler1DArrayShadow" => Availability::desktop(130),
+        "sampler2DArray" | "sampler2DArrayShadow" => Availability::since(130,300).extension("GL_EXT_texture_array"),
+        "sampler2DRect" | "sampler2DRectShadow" => Availability::desktop(140).extension("GL_ARB_texture_rectangle"),
+        "samplerBuffer" => Availability::since(140,320).extension("GL_ARB_texture_buffer_object"),
+        "sampler2DMS" => Availability::since(150,310).extension("GL_ARB_texture_multisample"),
+        "sampler2DMSArray" => Availability::since(150,320).extension("GL_ARB_texture_multisample"),
+        "samplerCubeArray" | "samplerCubeArrayShadow" =>
+            Availability::since(400,320).extension("GL_ARB_texture_cube_map_array"),
+
+        _ if name == "double" || name.starts_with("dvec") || name.starts_with("dmat") =>
+            Availability::desktop(400).extension("GL_ARB_gpu_shader_fp64"),
+
+        _ if name.trim_start_matches(['i','u']).starts_with("image") =>
+            Availability::since(420,310).extension("GL_ARB_shader_image_load_store"),
+
+        "sampler2D" | "samplerCube" if integer => Availability::since(110,100),
+
+        _ => return None
+
+    };
+
+    match integer {
+
+        true => Some(Availability {
+            desktop: base.desktop.map(|v| v.max(130)),
+            es: base.es.map(|v| v.max(300)),
+            ..base
+        }),
+        false => Some(base)
+
+    }
+
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// Find what the shader uses that its '#version' and profile don't have. The
+    /// extensions enabled by the shader are accepted. Only the first use of a feature
+    /// is reported
+    ///     example:
+    ///         '#version 330 core' with 'layout(binding = 0) uniform sampler2D tex;'
+    ///             => 'layout(binding)' needs glsl 420 or later, or the extension
+    ///                GL_ARB_shading_language_420pack
+    ///
+    pub fn check_version(&self) -> Vec<VersionIssue> {
+
+        let target = self.target();
+        //
+        // the preprocessor lines are blanked so the positions stay the same. The source
+        // was parsed already, a token error can't happen
+        let tokens = tokenize(&without_preprocessor(self.source()),0).unwrap_or_default();
+        //
+        // the built-in names the shader declares itself are not the built-ins
+        let declared:HashSet<&str> = self.variables().map(|v| v.name())
+            .chain(self.blocks().flat_map(|b| b.members().iter().map(|m| m.name()).chain(b.instance_name())))
+            .chain(self.functions().map(|f| f.name.as_str()))
+            .collect();
+
+        let mut issues:Vec<VersionIssue> = Vec::new();
+        let mut report = |feature:String,span:Span,availability:Availability| {
+
+            if !availability.is_available(&target) && !issues.iter().any(|i:&VersionIssue| i.feature == feature) {
+                issues.push(issue(feature,span,&availability,&target));
+            }
+
+        };
+
+        let mut braces:usize = 0;
+        let mut parentheses:usize = 0;
+        let mut i:usize = 0;
+
+        while i < tokens.len() {
+
+            let token = &tokens[i];
+            let next = tokens.get(i + 1);
+            let previous = i.checked_sub(1).map(|p| &tokens[p]);
+
+            let name = match token.identifier() {
+
+                Some(name) => name,
+                None => {
+
+                    match token {
+
+                        t if t.is_symbol("{") => braces += 1,
+                        t if t.is_symbol("}") => braces = braces.saturating_sub(1),
+                        t if t.is_symbol("(") => parentheses += 1,
+                        t if t.is_symbol(")") => parentheses = parentheses.saturating_sub(1),
+                        _ => {}
+
+                    }
+
+                    i += 1;
+                    continue;
+
+                }
+
+            };
+
+            let followed_by = |symbol:&str| next.is_some_and(|t| t.is_symbol(symbol));
+            let followed_by_name = next.is_some_and(|t| t.identifier().is_some());
+            //
+            // the identifiers in the parentheses of the layout, not their values
+            if name == "layout" && followed_by("(") {
+
+                let end = closing_parenthesis(&tokens,i + 1);
+
+                for j in i + 2..end {
+
+                    let Some(qualifier) = tokens[j].identifier() else { continue };
+
+                    if tokens[j - 1].is_symbol("=") {
+                        continue;
+                    }
+
+                    if let Some((_,availability)) = LAYOUT_QUALIFIERS.iter().find(|(q,_)| *q == qualifier) {
+                        report(format!("layout({})",qualifier),tokens[j].span,*availability);
+                    }
+
+                }
+
+                i = end + 1;
+                continue;
+
+            }
+            //
+            // a block: 'uniform Lights {', 'out VertexData {'
+            let is_block = followed_by_name && tokens.get(i + 2).is_some_and(|t| t.is_symbol("{"));
+
+            if is_block && braces == 0 {
+
+                match name {
+
+                    "uniform" => report("uniform block".to_string(),token.span,
+                        Availability::since(140,300).extension("GL_ARB_uniform_buffer_object")),
+
+                    "in" | "out" => report(format!("{} block",name),token.span,
+                        Availability::since(150,320).extension("GL_EXT_shader_io_blocks")),
+
+                    _ => {}
+
+                }
+
+            }
+            //
+            // the qualifiers are followed by a type or another qualifier. It keeps the
+            // variables of the old shaders named like a newer keyword ('sample', 'buffer')
+            if followed_by_name {
+
+                if let Some((_,availability)) = QUALIFIERS.iter().find(|(q,_)| *q == name) {
+                    report(name.to_string(),token.span,*availability);
+                }
+                //
+                // 'in' and 'out' replaced 'attribute' and 'varying' for the globals
+                if (name == "in" || name == "out") && braces == 0 && parentheses == 0 {
+                    report(name.to_string(),token.span,Availability::since(130,300));
+                }
+
+            }
+            //
+            // a type starts a declaration or a constructor
+            if followed_by_name || followed_by("(") || followed_by("[") {
+
+                if let Some(availability) = type_availability(name) {
+                    report(name.to_string(),token.span,availability);
+                }
+
+            }
+            //
+            // the built-ins
+            let is_member = previous.is_some_and(|t| t.is_symbol("."));
+
+            if !is_member && !declared.contains(name) {
+
+                let known = match followed_by("(") {
+
+                    true => available_functions(name,&target).is_empty().then(|| {
+                        builtin_functions(name).iter().map(|f| f.availability).collect::<Vec<Availability>>()
+                    }),
+                    false => available_variable(name,&target).is_none().then(|| {
+                        builtin_variables(name).map(|v| v.availability).collect::<Vec<Availability>>()
+                    })
+
+                };
+                //
+                // the one the shader could use the soonest. A built-in of another stage is
+                // not a version issue
+                let closest = known.unwrap_or_default().into_iter()
+                    .filter(|a| a.stages.is_empty() || target.stage.is_none_or(|s| a.stages.contains(&s)))
+                    .min_by_key(|a| a.minimum_version(target.branch).unwrap_or(u16::MAX));
+
+                if let Some(availability) = closest {
+                    report(name.to_string(),token.span,availability);
+                }
+
+            }
+
+            i += 1;
+
+        }
+
+        issues
+
+    }
+    //
+}
+//
+//
+/// Describe why a feature can't be used by the target
+fn issue(feature:String,span:Span,availability:&Availability,target:&ShaderTarget) -> VersionIssue {
+
+    let minimum_version = availability.minimum_version(target.branch);
+    let is_recent_enough = minimum_version.is_some_and(|v| target.version >= v);
+
+    let removed_in = match target.branch {
+
+        VersionBranch::ES => availability.removed_in_core.map(|_| 300),
+        _ => availability.removed_in_core
+
+    };
+
+    VersionIssue {
+        feature,
+        span,
+        minimum_version,
+        removed_in: removed_in.filter(|_| is_recent_enough),
+        extension: availability.extension,
+        branch: target.branch
+    }
+
+}
+//
+//
+/// The index of the ')' that close the '(' at this index, the last token if there is none
+fn closing_parenthesis(tokens:&[Token],open:usize) -> usize {
+
+    let mut depth:usize = 0;
+
+    for (i,token) in tokens.iter().enumerate().skip(open) {
+
+        if token.is_symbol("(") {
+            depth += 1;
+        } else if token.is_symbol(")") {
+
+            depth -= 1;
+
+            if depth == 0 {
+                return i;
+            }
+
+        }
+
+    }
+
+    tokens.len().saturating_sub(1)
+
+}
+//
+//
+/// Replace the preprocessor lines with spaces. A line ending with '\' continues on the
+/// next one
+fn without_preprocessor(source:&str) -> String {
+
+    let mut result = String::with_capacity(source.len());
+    let mut continued = false;
+
+    for line in source.split_inclusive('\n') {
+
+        let is_preprocessor = continued || line.trim_start().starts_with('#');
+
+        match is_preprocessor {
+
+            true => {
+
+                continued = line.trim_end().ends_with('\\');
+                result.extend(line.chars().map(|c| match c {
+
+                    '\n' => "\n".to_string(),
+                    _ => " ".repeat(c.len_utf8())
+
+                }));
+
+            },
+            false => result.push_str(line)
+
+        }
+
+    }
+
+    result
+
+}
+//
+//
@@ -0,0 +1,237 @@
+//
+// ------------------------------------------------------------------------------------------
+// Vertex input
+//
+// The attributes a vertex shader reads from the vertex buffers and a layout of them in
+// one interleaved buffer. An application can compare the format of its buffers with it
+//
+use std::fmt;
+use crate::ShaderFileInfo;
+use crate::layout::{scalar_size,round_up};
+use crate::locations::location_count;
+use crate::datatype::{ShaderType,ScalarType,StorageQualifier};
+//
+//
+/// An input of a vertex shader
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct VertexAttribute {
+
+    pub name:           String,
+    // the first location, the one written or the one 'assign_locations' gives
+    pub location:       u32,
+    // a matrix takes a location by column and an array a location by element
+    pub location_count: u32,
+    // the components read at each location ('vec3' => 3)
+    pub components:     u32,
+    pub scalar:         ScalarType,
+    // the bytes of the whole attribute
+    pub size:           usize,
+    // where the attribute is in a vertex of the interleaved layout
+    pub offset:         usize,
+    // false when main doesn't read it, the drivers remove it then
+    pub used:           bool,
+
+}
+//
+impl VertexAttribute {
+    //
+    /// The locations the attribute takes, the ones after the last of a u32 are not counted
+    pub fn locations(&self) -> impl Iterator<Item = u32> {
+
+        (self.location as u64..self.location as u64 + self.location_count as u64).map_while(|l| u32::try_from(l).ok())
+
+    }
+    //
+}
+//
+//
+/// The attributes of a vertex shader sorted by location, interleaved in one buffer
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct VertexLayout {
+
+    pub attributes: Vec<VertexAttribute>,
+    // the bytes of a vertex
+    pub stride:     usize,
+
+}
+//
+//
+/// An attribute of the format of a vertex buffer of an application
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub struct VertexBufferAttribute {
+
+    pub location:   u32,
+    pub components: u32,
+    pub scalar:     ScalarType,
+
+}
+//
+//
+/// Why a vertex buffer format doesn't match the attributes of a shader
+#[allow(non_camel_case_types,clippy::upper_case_acronyms)]
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum VertexMismatchKind {
+
+    // the shader reads a location the buffer doesn't give
+    MISSING_ATTRIBUTE,
+    // the buffer gives a location the shader doesn't read
+    UNUSED_ATTRIBUTE,
+    // the components of the shader and of the buffer
+    COMPONENTS(u32,u32),
+    // the type of the components of the shader and of the buffer
+    SCALAR_TYPE(ScalarType,ScalarType),
+
+}
+//
+//
+/// An attribute of a shader and of a buffer that don't match
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct VertexMismatch {
+
+    pub location:   u32,
+    // the name of the attribute in the shader, None when the shader doesn't read it
+    pub name:       Option<String>,
+    pub kind:       VertexMismatchKind,
+
+}
+//
+impl fmt::Display for VertexMismatch {
+
+    fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
+
+        let name = self.name.as_deref().unwrap_or("");
+
+        match &self.kind {
+
+            VertexMismatchKind::MISSING_ATTRIBUTE =>
+                write!(f,"the buffer has no attribute at the location {} for '{}'",self.location,name),
+
+            VertexMismatchKind::UNUSED_ATTRIBUTE =>
+                write!(f,"the shader doesn't read the attribute at the location {}",self.location),
+
+            VertexMismatchKind::COMPONENTS(shader,buffer) =>
+                write!(f,"'{}' has {} components but the buffer gives {} at the location {}",name,shader,buffer,self.location),
+
+            VertexMismatchKind::SCALAR_TYPE(shader,buffer) =>
+                write!(f,"'{}' is of {} but the buffer gives {} at the location {}",name,shader.as_str(),buffer.as_str(),self.location)
+
+        }
+
+    }
+
+}
+//
+//
+impl VertexLayout {
+    //
+    /// Compare the format of a vertex buffer with the attributes. An attribute that the
+    /// shader doesn't use can be missing from the buffer. A matrix or an array is given by
+    /// an attribute for each of its locations
+    pub fn check_format(&self,format:&[VertexBufferAttribute]) -> Vec<VertexMismatch> {
+
+        let mut mismatches:Vec<VertexMismatch> = Vec::new();
+
+        for attribute in self.attributes.iter() {
+
+            for location in attribute.locations() {
+
+                let mismatch = |kind| VertexMismatch { location, name: Some(attribute.name.clone()), kind };
+
+                let Some(buffer) = format.iter().find(|b| b.location == location) else {
+
+                    if attribute.used {
+                        mismatches.push(mismatch(VertexMismatchKind::MISSING_ATTRIBUTE));
+                    }
+
+                    continue;
+
+                };
+
+                if buffer.components != attribute.components {
+                    mismatches.push(mismatch(VertexMismatchKind::COMPONENTS(attribute.components,buffer.components)));
+                }
+
+                if buffer.scalar != attribute.scalar {
+                    mismatches.push(mismatch(VertexMismatchKind::SCALAR_TYPE(attribute.scalar,buffer.scalar)));
+                }
+
+            }
+
+        }
+
+        for buffer in format {
+
+            let read = self.attributes.iter().any(|a| {
+                (a.location as u64..a.location as u64 + a.location_count as u64).contains(&(buffer.location as u64))
+            });
+
+            if !read {
+                mismatches.push(VertexMismatch { location: buffer.location, name: None, kind: VertexMismatchKind::UNUSED_ATTRIBUTE });
+            }
+
+        }
+
+        mismatches
+
+    }
+    //
+}
+//
+//
+impl ShaderFileInfo {
+    //
+    /// The attributes of a vertex shader, None for the other stages. The inputs without
+    /// location are given the ones of 'assign_locations' and the attributes follow each
+    /// other in the order of their locations, aligned on 4 bytes
+    pub fn vertex_layout(&self) -> Option<VertexLayout> {
+
+        if self.shader_type() != Some(ShaderType::VERTEX) {
+            return None;
+        }
+
+        let assignments = self.assign_locations();
+
+        let mut attributes:Vec<VertexAttribute> = self.variables()
+            .filter(|v| v.has_qualifier(&StorageQualifier::IN) && !v.name().starts_with("gl_"))
+            .filter_map(|v| {
+
+                let location = v.location().or_else(|| {
+                    assignments.iter().find(|a| !a.output && a.span == v.span()).map(|a| a.location)
+                })?;
+
+                let (_,rows) = v.var_type().dimensions()?;
+                let scalar = v.var_type().scalar_type()?;
+                let location_count = location_count(v.var_type(),v.array_size(),true)?;
+
+                Some(VertexAttribute {
+                    name: v.name().to_string(),
+                    location,
+                    location_count,
+                    components: rows as u32,
+                    scalar,
+                    size: (location_count as usize).checked_mul(rows * scalar_size(scalar))?,
+                    offset: 0,
+                    used: v.is_used()
+                })
+
+            })
+            .collect();
+
+        attributes.sort_by_key(|a| a.location);
+
+        let mut stride = 0;
+
+        for attribute in attributes.iter_mut() {
+
+            attribute.offset = round_up(stride,4);
+            stride = attribute.offset.saturating_add(attribute.size);
+
+        }
+
+        Some(VertexLayout { attributes, stride: round_up(stride,4) })
+
+    }
+    //
+}
+//
+//